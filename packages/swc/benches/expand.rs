@@ -0,0 +1,237 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use swc_core::atoms::Atom;
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::{
+    Ident, IdentName, JSXAttr, JSXAttrName, JSXAttrOrSpread, JSXAttrValue, JSXElementName,
+    JSXOpeningElement, Lit, Str,
+};
+use swc_core::ecma::visit::VisitMutWith;
+use tailwind_expand_swc::{transform_source, Config, TailwindExpandVisitor};
+
+/// 100 aliases named Alias0..Alias99, each expanding to two utilities
+fn alias_config() -> Config {
+    let aliases = (0..100)
+        .map(|index| (format!("Alias{}", index), format!("p-{0} m-{0}", index)))
+        .collect();
+    Config {
+        aliases,
+        ..Default::default()
+    }
+}
+
+/// `<div className="...">`, visited directly so a benchmark measures the class
+/// string expansion rather than parsing and printing
+fn class_name_element(class_name: &str) -> JSXOpeningElement {
+    JSXOpeningElement {
+        name: JSXElementName::Ident(Ident::new_no_ctxt(Atom::from("div"), DUMMY_SP)),
+        span: DUMMY_SP,
+        attrs: vec![JSXAttrOrSpread::JSXAttr(JSXAttr {
+            span: DUMMY_SP,
+            name: JSXAttrName::Ident(IdentName::new(Atom::from("className"), DUMMY_SP)),
+            value: Some(JSXAttrValue::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: Atom::from(class_name),
+                raw: None,
+            }))),
+        })],
+        self_closing: true,
+        type_args: None,
+    }
+}
+
+/// A module of `elements` components, each element's className built by `class_name`
+/// so no two strings are alike and the expansion cache doesn't hide the work
+fn synthetic_module(elements: usize, class_name: impl Fn(usize) -> String) -> String {
+    (0..elements)
+        .map(|index| {
+            format!(
+                "export const C{0} = () => <div className=\"{1}\"><span className=\"{1} z-{0}\" /></div>;\n",
+                index,
+                class_name(index)
+            )
+        })
+        .collect()
+}
+
+/// 100 aliases and a single element whose className has 500 tokens,
+/// as found in machine-generated markup
+fn long_class_name(c: &mut Criterion) {
+    let config = alias_config();
+
+    let class_name: Vec<String> = (0..500)
+        .map(|index| match index % 5 {
+            0 => format!("Alias{}", index % 100),
+            1 => format!("hover:Alias{}", index % 100),
+            _ => format!("text-{}", index),
+        })
+        .collect();
+    let source = format!(
+        "export const Page = () => <div className=\"{}\" />;",
+        class_name.join(" ")
+    );
+
+    c.bench_function("transform 500-token className", |b| {
+        b.iter(|| transform_source(black_box(&source), &config).unwrap())
+    });
+}
+
+/// A className of plain utilities, the common case: no token is an alias, so the
+/// string is borrowed back without building any output
+fn no_alias_class_name(c: &mut Criterion) {
+    let mut visitor = TailwindExpandVisitor::new(alias_config());
+    let element = class_name_element(
+        "flex items-center justify-between gap-4 px-4 py-2 text-sm font-medium text-gray-900 hover:bg-gray-50 md:px-6 dark:text-white",
+    );
+
+    c.bench_function("className without aliases", |b| {
+        b.iter(|| {
+            let mut element = element.clone();
+            element.visit_mut_with(&mut visitor);
+            element
+        })
+    });
+}
+
+/// A design-system-sized alias set: components with size and tone variants, each
+/// expanding to the 5-15 utilities typical of real component classes
+fn realistic_aliases() -> Config {
+    let base = [
+        ("Button", "inline-flex items-center justify-center gap-2 rounded-md font-medium transition-colors focus-visible:outline-none focus-visible:ring-2 disabled:pointer-events-none disabled:opacity-50"),
+        ("Card", "rounded-lg border bg-white p-6 shadow-sm dark:border-gray-800 dark:bg-gray-950"),
+        ("Input", "flex h-10 w-full rounded-md border px-3 py-2 text-sm placeholder:text-gray-400 focus-visible:ring-2"),
+        ("Badge", "inline-flex items-center rounded-full border px-2.5 py-0.5 text-xs font-semibold"),
+        ("Stack", "flex flex-col gap-4"),
+        ("Heading", "scroll-m-20 text-2xl font-semibold tracking-tight"),
+    ];
+    let sizes = [
+        ("Sm", "h-9 px-3 text-sm"),
+        ("Md", "h-10 px-4 py-2"),
+        ("Lg", "h-11 px-8 text-lg"),
+    ];
+    let tones = [
+        ("Primary", "bg-blue-600 text-white hover:bg-blue-700"),
+        ("Muted", "bg-gray-100 text-gray-900 hover:bg-gray-200"),
+        ("Danger", "bg-red-600 text-white hover:bg-red-700"),
+    ];
+    let mut aliases = std::collections::HashMap::new();
+    for (name, value) in base {
+        aliases.insert(name.to_string(), value.to_string());
+        for (size, size_value) in sizes {
+            aliases.insert(
+                format!("{}{}", name, size),
+                format!("{} {}", value, size_value),
+            );
+        }
+        for (tone, tone_value) in tones {
+            aliases.insert(
+                format!("{}{}", name, tone),
+                format!("{} {}", value, tone_value),
+            );
+        }
+    }
+    Config {
+        aliases,
+        ..Default::default()
+    }
+}
+
+/// 200 distinct classNames over the realistic alias set, each visited by a fresh
+/// visitor so the expansion cache starts empty
+fn realistic_alias_set(c: &mut Criterion) {
+    let config = realistic_aliases();
+    let names = ["Button", "Card", "Input", "Badge", "Stack", "Heading"];
+    let variants = ["Sm", "Md", "Lg", "Primary", "Muted", "Danger"];
+    let elements: Vec<JSXOpeningElement> = (0..200)
+        .map(|index| {
+            class_name_element(&format!(
+                "{}{} md:{}{} mt-{} hover:underline",
+                names[index % names.len()],
+                variants[index % variants.len()],
+                names[(index / 6) % names.len()],
+                variants[(index / 36) % variants.len()],
+                index
+            ))
+        })
+        .collect();
+
+    c.bench_function("200 classNames, realistic alias set", |b| {
+        b.iter_batched(
+            || (TailwindExpandVisitor::new(config.clone()), elements.clone()),
+            |(mut visitor, mut elements)| {
+                for element in &mut elements {
+                    element.visit_mut_with(&mut visitor);
+                }
+                (visitor, elements)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+/// Single tokens through the visitor's per-token lookup, the innermost step of
+/// every class string expansion
+fn expand_token(c: &mut Criterion) {
+    let visitor = TailwindExpandVisitor::new(alias_config());
+    let tokens = [
+        "Alias42",
+        "md:hover:Alias7",
+        "[&:hover]:!Alias99",
+        "text-white",
+        "lg:px-4",
+    ];
+
+    c.bench_function("expand_token", |b| {
+        b.iter(|| {
+            for token in tokens {
+                black_box(visitor.expand_token(black_box(token)));
+            }
+        })
+    });
+}
+
+/// 1000 elements of plain utilities: nothing matches an alias, so every string
+/// should be left as-is without building output
+fn no_alias_module(c: &mut Criterion) {
+    let config = alias_config();
+    let source = synthetic_module(1000, |index| {
+        format!(
+            "flex items-center gap-{} text-sm hover:bg-gray-{}",
+            index % 8,
+            index % 9
+        )
+    });
+
+    c.bench_function("transform 1000 elements, no aliases", |b| {
+        b.iter(|| transform_source(black_box(&source), &config).unwrap())
+    });
+}
+
+/// 1000 elements mixing aliases, variants, important and arbitrary variants
+fn heavy_expansion_module(c: &mut Criterion) {
+    let config = alias_config();
+    let source = synthetic_module(1000, |index| {
+        format!(
+            "Alias{} md:Alias{} [&:hover]:!Alias{} dark:(Alias{} text-white) gap-{}",
+            index % 100,
+            (index + 1) % 100,
+            (index + 2) % 100,
+            (index + 3) % 100,
+            index
+        )
+    });
+
+    c.bench_function("transform 1000 elements, heavy expansion", |b| {
+        b.iter(|| transform_source(black_box(&source), &config).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    long_class_name,
+    no_alias_class_name,
+    realistic_alias_set,
+    expand_token,
+    no_alias_module,
+    heavy_expansion_module
+);
+criterion_main!(benches);
@@ -8,6 +8,570 @@ const absolutePath = resolve(join(__dirname, '..', 'tailwind_expand_swc.wasm'));
 // Use relative path for Turbopack compatibility (https://github.com/vercel/next.js/issues/78156)
 const wasmPath = relative(process.cwd(), absolutePath);
 
+/**
+ * SWC plugin options.
+ * Everything beyond the shared plugin options is forwarded to the WASM plugin as-is.
+ */
+export interface SWCPluginOptions extends ExpandPluginOptions {
+  /**
+   * Where debug mode records the expanded aliases: an attribute (`dataAttr`), a JSX comment
+   * as the element's first child (`comment`, for frameworks that strip `data-*` attributes),
+   * or nowhere (`none`).
+   * @default 'dataAttr'
+   */
+  debugMode?: 'dataAttr' | 'comment' | 'none';
+  /**
+   * Attribute holding the alias names when `debugMode` is `dataAttr`, e.g. `'x-expand'`.
+   * @default 'data-expand'
+   */
+  debugAttribute?: string;
+  /**
+   * Order of the alias names listed in `data-expand`: sorted by name, or in the order
+   * they were expanded on the element.
+   * @default 'alphabetical'
+   */
+  debugOrder?: 'alphabetical' | 'source';
+  /**
+   * List each alias with its utilities in the debug output, e.g.
+   * `data-expand="Button=px-4 py-2; lg:ButtonLg=lg:h-12"`, instead of the alias names alone.
+   * @default false
+   */
+  debugVerbose?: boolean;
+  /**
+   * Where `data-expand` is inserted: before every other attribute (so spreads after it
+   * can override it) or after them. With `'last'`, spreads following the class attribute
+   * stay after `data-expand`, so they override it along with `className`.
+   * @default 'last'
+   */
+  debugAttributePosition?: 'first' | 'last';
+  /**
+   * Aliases left out of `data-expand`, though still expanded.
+   * @default []
+   */
+  debugExclude?: string[];
+  /**
+   * Alias names never expanded, even with variants, e.g. `['Button']` when `Button` is also
+   * a class from external CSS.
+   * @default []
+   */
+  ignore?: string[];
+  /**
+   * Aliases whose utilities are always important, as if written `!Reset`, e.g. for resets.
+   * @default []
+   */
+  importantAliases?: string[];
+  /**
+   * Variants whose expanded utilities are always important, e.g. `['print']` turns
+   * `print:Button` into `print:!px-4 print:!py-2` while `hover:Button` is unchanged.
+   * @default []
+   */
+  importantVariants?: string[];
+  /**
+   * Turn a CSS `!important` left in an alias value (e.g. `px-4 !important`) into Tailwind's
+   * `!` modifier instead of dropping it. A build warning is reported either way.
+   * @default false
+   */
+  convertImportant?: boolean;
+  /**
+   * Resolved theme values substituted for `theme()` references in alias values, e.g.
+   * `{ 'colors.brand': '#0af' }` turns `text-[theme(colors.brand)]` into `text-[#0af]`.
+   * The plugin can't call into Tailwind, so resolve the keys you use ahead of time
+   * (e.g. from `resolveConfig`). Keys missing from the map are left as-is.
+   * @default {}
+   */
+  theme?: Record<string, string>;
+  /**
+   * Apply an opacity suffix on an alias token to its color utilities, e.g. `Button/50`
+   * gives `bg-red-500/50`. Colors are detected from `bg-`, `text-`, `border-` and `ring-`.
+   * @default false
+   */
+  aliasOpacity?: boolean;
+  /**
+   * Expand alias references written as `{{Name}}` inside a token, once per utility of
+   * the alias with the surrounding text kept: `[&>li]:{{Button}}` becomes
+   * `[&>li]:px-4 [&>li]:py-2`. Alias names appearing anywhere else inside a token,
+   * such as `grid-cols-[var(--Button)]`, are never expanded.
+   */
+  aliasInterpolation?: boolean;
+  /**
+   * Also resolve aliases written in another case style, so `button-lg`, `buttonLg`
+   * and `button_lg` all resolve to `ButtonLg`. Aliases that share a normalized form
+   * are rejected as an invalid config.
+   * @default false
+   */
+  normalizeKeys?: boolean;
+  /**
+   * Leave class strings untouched and only add `data-expand` listing the aliases
+   * that would be expanded. Implies `debug`.
+   * @default false
+   */
+  dryRun?: boolean;
+  /**
+   * Keep alias class names instead of inlining their utilities, and record each used
+   * alias's utilities under `components` in the `reportPath` report, so a separate step
+   * can generate `@layer components { .Button { @apply ... } }`.
+   * @default false
+   */
+  extractComponents?: boolean;
+  /**
+   * Replace each alias token with a short class hashed from its expanded utilities
+   * (e.g. `lg:Button` -> `tw-1c2e3f4a`) and record each hashed class's utilities under
+   * `classes` in the `reportPath` report, so a separate step can emit the CSS.
+   * @default false
+   */
+  hashClasses?: boolean;
+  /**
+   * Expand aliases in string literals inside any array literal,
+   * e.g. `["Button", big && "ButtonLg"].join(" ")`.
+   * @default false
+   */
+  expandArrays?: boolean;
+  /**
+   * Write per-module alias usage counts to this directory. SWC transforms modules in
+   * parallel, so each module writes its own `<hash>.json` file holding `{ [filename]: report }`;
+   * merge the files' objects for the whole build. Failed writes are reported as warnings.
+   * Resolved relative to the SWC working directory; must be writable from the WASI sandbox.
+   */
+  reportPath?: string;
+  /**
+   * Write the names of every alias expanded in each module to this directory, one `<hash>.json`
+   * file per module holding `{ [filename]: names }` like `reportPath` (a sorted array); the union
+   * of all files' names lists the aliases used by the build, e.g. to tree-shake generated CSS.
+   * Resolved relative to the SWC working directory; must be writable from the WASI sandbox.
+   */
+  usedAliasesPath?: string;
+  /**
+   * Also write every diagnostic (unknown aliases, empty values, capped expansions, ...)
+   * to this directory, one `<hash>.json` file per module holding `{ [filename]: diagnostics }`
+   * like `reportPath`; modules without diagnostics write no file. Each entry has `kind`,
+   * `severity` (`'error'` or `'warn'`), `message` and, for source problems, a 1-based
+   * `line` and `column`. Options that fail to load still write their error here.
+   * Resolved relative to the SWC working directory; must be writable from the WASI sandbox.
+   */
+  diagnosticsPath?: string;
+  /**
+   * Override how each kind of diagnostic is reported: `'error'`, `'warn'` or `'off'`,
+   * e.g. `{ 'unknown-alias': 'error', 'output-limit': 'off' }`. Kinds are `empty-alias`,
+   * `unknown-variant`, `unknown-alias`, `non-string-class-name`, `empty-utility`,
+   * `output-limit`, `css-important`, `css-syntax`, `deprecated-alias`, `protected-utility`, `unexpanded-alias` and `impure-class-value`; the rest keep their default severity.
+   * Options that fail to load are always reported as errors.
+   * @default {}
+   */
+  severity?: Partial<Record<string, 'error' | 'warn' | 'off'>>;
+  /**
+   * Write every expanded alias token to this directory, one `<hash>.json` file per module
+   * holding `{ [filename]: expansions }` like `reportPath`, listing each token's
+   * `token`, `alias`, `utilities` and 1-based `line`/`column`, e.g. for hover tooltips
+   * in an editor extension.
+   * Resolved relative to the SWC working directory; must be writable from the WASI sandbox.
+   */
+  expansionMapPath?: string;
+  /**
+   * Append a block comment summarizing each module's expansions to its output,
+   * e.g. `tailwind-expand: 12 tokens, 2 aliases: Button, Card`.
+   * @default false
+   */
+  summaryComment?: boolean;
+  /**
+   * Only transform modules whose filename matches one of these globs, e.g. `['packages/web/**']`.
+   * `*` matches within a path segment and `**` across segments.
+   * @default []
+   */
+  filenameInclude?: string[];
+  /**
+   * Never transform modules whose filename matches one of these globs, e.g.
+   * `['**\/*.stories.tsx']`. Takes precedence over `filenameInclude`.
+   * @default []
+   */
+  filenameExclude?: string[];
+  /**
+   * Keep each matched alias token in front of its expanded utilities, once per class
+   * string, e.g. `lg:Button` → `lg:Button lg:px-4 lg:py-2`. Only literal alias names are
+   * kept, not glob, shortcut or BEM-composed matches.
+   * @default false
+   */
+  keepAliasClass?: boolean;
+  /**
+   * `keepAliasClass` for the listed aliases only, e.g. `['Card']` while legacy BEM CSS still
+   * targets `.Card`. Other aliases are replaced as usual.
+   * @default []
+   */
+  keepAliasFor?: string[];
+  /**
+   * Sort class strings containing aliases into Tailwind's recommended class order.
+   * @default false
+   */
+  sort?: boolean;
+  /**
+   * Reorder class strings containing aliases into base utilities first, then one group
+   * per variant prefix (`hover:`, `lg:`, ...) in order of first appearance, keeping the
+   * written order within each group. Lighter than `sort`, which reorders everything.
+   * @default false
+   */
+  groupVariants?: boolean;
+  /**
+   * Migration mode, the inverse of expansion: replace runs of utilities matching an
+   * alias value with the alias name, e.g. `px-4 py-2 flex` becomes `Button flex`.
+   * Conservative: the utilities must be contiguous and in the alias's order, the
+   * longest alias wins, and single-utility aliases are never collapsed.
+   * @default false
+   */
+  collapse?: boolean;
+  /**
+   * Drop repeated classes from expanded class strings, keeping the first occurrence.
+   * When disabled, `"Button Button"` keeps both copies of the utilities.
+   * @default false
+   */
+  dedupe?: boolean;
+  /**
+   * Expand only the first token of each alias in a class string, leaving later ones as
+   * written: `Button hover:Button` becomes `px-4 py-2 hover:Button`. For migration scripts.
+   * @default false
+   */
+  expandFirstOnly?: boolean;
+  /**
+   * Drop utilities overridden later in the same class string, last one wins:
+   * `flex grid` becomes `grid`. Only display, position and float utilities are
+   * resolved, and only under the same variants and important modifier.
+   * @default false
+   */
+  resolveConflicts?: boolean;
+  /**
+   * Output tuned for a runtime tailwind-merge (`cn`): enables `dedupe` and disables
+   * `resolveConflicts`, `sort` and `groupVariants`. Exact repeats are dropped, while
+   * conflicting utilities like `flex grid` are kept in order for `twMerge` to resolve.
+   * @default false
+   */
+  cnFriendly?: boolean;
+  /**
+   * Classes that `dedupe` and `resolveConflicts` never remove, e.g. `['block']` to keep
+   * a forced `block` next to a later `flex`.
+   * @default []
+   */
+  safelist?: string[];
+  /**
+   * Classes removed from every class string after expansion, whether written directly or
+   * produced by an alias, e.g. `['card', 'debug-outline']` for semantic or debugging markers
+   * kept in source for tooling but not shipped.
+   * @default []
+   */
+  stripClasses?: string[];
+  /**
+   * Collapse repeated adjacent variants in expanded class strings, so
+   * `hover:hover:bg-red` becomes `hover:bg-red`.
+   * @default false
+   */
+  minify?: boolean;
+  /**
+   * Reorder the variants of expanded utilities canonically (state variants, then `dark`,
+   * then breakpoints), so `dark:hover:Button` and `hover:dark:Button` give the same classes.
+   * Arbitrary and custom variants keep their position.
+   * @default false
+   */
+  normalizeVariantOrder?: boolean;
+  /**
+   * Most classes a single class string may expand to. Aliases that would go past the limit,
+   * and every alias after them, are left unexpanded with a build warning.
+   */
+  maxOutputTokens?: number;
+  /**
+   * Drop variants from an alias's utilities that the token's prefix already applies,
+   * e.g. `dark:Button` with `dark:text-white` gives `dark:text-white` rather than `dark:dark:text-white`.
+   * @default true
+   */
+  dedupeVariants?: boolean;
+  /**
+   * Ignore aliases named like a built-in Tailwind utility, such as `flex` or `text-lg`, so the
+   * real utility keeps working. Each one is reported with a `protected-utility` warning.
+   * Off by default because it also drops aliases in a utility's namespace, like `text-primary`;
+   * aliases named exactly like a utility (`flex`, `grid`, ...) are still reported when off.
+   * @default false
+   */
+  protectUtilities?: boolean;
+  /**
+   * Variants always stacked literally even with `dedupeVariants`, for intentional
+   * doubles: with `['hover']`, `hover:Button` over `hover:bg-red` gives `hover:hover:bg-red`.
+   * @default []
+   */
+  neverDedupeVariants?: string[];
+  /**
+   * Apply an alias token's important modifier (`!Button`) only to the alias's utilities
+   * without variants, so `hover:bg-red` inside the alias stays unbanged.
+   * @default false
+   */
+  importantBaseOnly?: boolean;
+  /**
+   * Variants allowed in front of an alias, e.g. `['hover', 'lg', 'dark']`. Other variants
+   * (like a misspelled `hvoer:Button`) produce a build warning but are still expanded.
+   */
+  knownVariants?: string[];
+  /**
+   * Lowercase recognized variants in front of aliases, so `Hover:Button` expands with
+   * `hover:`. Recognized variants are `knownVariants` when set, otherwise common
+   * Tailwind variants. Alias names keep their case.
+   * @default false
+   */
+  lowercaseVariants?: boolean;
+  /**
+   * Recognize variants regardless of case, so `HOVER:Button` doesn't stack `hover:` on the
+   * alias's own `hover:` utilities and counts as a known variant. Variants keep their case
+   * unless `lowercaseVariants` is set; alias names are still case-sensitive.
+   * @default false
+   */
+  caseInsensitiveVariants?: boolean;
+  /**
+   * What happens to tokens that look like aliases (PascalCase, e.g. a renamed `ButtonPrimary`)
+   * but match none:
+   * - `passthrough`: leave them as-is
+   * - `remove`: drop them from the class string
+   * - `keep`: leave them as-is and emit a build warning
+   * @default 'passthrough'
+   */
+  fallback?: 'passthrough' | 'remove' | 'keep';
+  /**
+   * Marker for alias tokens, e.g. `'@'` to write aliases as `@Button` or `lg:@Button`.
+   * Unmarked alias names still expand.
+   */
+  aliasPrefix?: string;
+  /**
+   * Fail the build for tokens carrying `aliasPrefix` that match no alias (e.g. a typo
+   * like `@Buton`), while normal utilities are never reported.
+   * @default false
+   */
+  strictPrefix?: boolean;
+  /**
+   * Warn about class attributes that are almost certainly mistakes, such as `className={0}`,
+   * `className={true}` or a `hover:` token with no utility. They are left as-is either way.
+   * @default false
+   */
+  strict?: boolean;
+  /**
+   * Warn when an expanded class string still contains an alias, e.g. from aliases whose values
+   * name each other in a cycle. Aliases kept through `keepAliasClass` or `keepAliasFor` are fine.
+   * @default false
+   */
+  assertFullyExpanded?: boolean;
+  /**
+   * Treat alias keys written as `/pattern/` as UnoCSS-style shortcuts, tried when no
+   * literal alias matches. The pattern must match the whole token and its captures can
+   * be used in the value, e.g. `{ '/btn-(\\w+)/': 'bg-$1-500 text-white' }`.
+   * @default false
+   */
+  unoShortcuts?: boolean;
+  /**
+   * Expand tokens made of aliases joined by `bemDelimiter`, e.g. `Button--Lg` as `Button`
+   * followed by `Lg`. Tokens with any unknown segment are left alone.
+   * @default false
+   */
+  bemComposition?: boolean;
+  /**
+   * Separator between composed aliases when `bemComposition` is enabled.
+   * @default '--'
+   */
+  bemDelimiter?: string;
+  /**
+   * How expanded class strings are spaced:
+   * - `single`: join tokens with single spaces
+   * - `preserve`: keep the original spacing between tokens (single spaces when `sort`, `dedupe`, `resolveConflicts` or `groupVariants` is on)
+   * - `original`: like `single`, but strings without aliases are never rewritten
+   * @default 'single'
+   */
+  whitespace?: 'single' | 'preserve' | 'original';
+  /**
+   * Expand these attributes and emit the result under another attribute name,
+   * e.g. `{ twClass: 'className' }` turns `twClass="Button"` into `className="..."`.
+   */
+  renameAttributes?: Record<string, string>;
+  /**
+   * Further attributes treated like `className`, e.g. `['data-class']` for web components
+   * bound through `<my-card data-class={'Button'}>`.
+   * @default []
+   */
+  classAttributes?: string[];
+  /**
+   * Which of the built-in class attributes are expanded, e.g. `['className']` so a React-only
+   * project leaves `class` alone. Also applies to `createElement` props and JSX spreads.
+   * @default ['className', 'class', 'classes']
+   */
+  defaultClassAttributes?: Array<'className' | 'class' | 'classes'>;
+  /**
+   * Namespaced attributes treated like `className`, written as `namespace:name`,
+   * e.g. `['xml:class']`.
+   * @default []
+   */
+  namespacedClassAttributes?: string[];
+  /**
+   * Also expand the `part` attribute of web components, e.g. `<my-card part="Card">`.
+   * `class` on custom elements is always expanded.
+   * @default false
+   */
+  expandPartAttributes?: boolean;
+  /**
+   * Components whose sole child is a class string, e.g. `['Classes']` expands
+   * `<Classes>Button ButtonLg</Classes>`.
+   * @default []
+   */
+  expandChildrenOf?: string[];
+  /**
+   * Variables holding maps of class strings, e.g. `['styles']` expands every string value in
+   * `const styles = { primary: 'Button', danger: 'ButtonDanger' }`.
+   * @default []
+   */
+  expandStyleMaps?: string[];
+  /**
+   * Expand every string value inside objects asserted `as const`, the usual shape for
+   * type-safe variant maps, e.g. `{ size: { lg: 'ButtonLg' } } as const`.
+   * @default false
+   */
+  expandConstObjects?: boolean;
+  /**
+   * Expand `const` string declarations passed by name to a class attribute, e.g.
+   * `const c = 'Button'` used as `<div className={c} />`. The declaration itself is
+   * rewritten; `let`/`var` bindings and non-literal initializers are left alone.
+   * @default false
+   */
+  expandConstStrings?: boolean;
+  /**
+   * Expand string defaults of destructured class properties, e.g.
+   * `function Btn({ className = 'Button' }) {}`. Only properties named like a class attribute match.
+   * @default false
+   */
+  expandDefaultParams?: boolean;
+  /**
+   * Attribute declaring a variant for every alias expanded inside an element, e.g.
+   * `'data-tw-variant'` turns `<div data-tw-variant="dark"><p className="Button" /></div>` into
+   * `dark:` utilities on the `<p>`. Nested scopes stack and the attribute is removed.
+   */
+  variantScopeAttribute?: string;
+  /**
+   * Marker attribute for adopting the plugin element by element, e.g. `'data-tw-expand'`:
+   * only elements carrying it are expanded, and the marker is removed from the output.
+   */
+  optInAttribute?: string;
+  /**
+   * Expand the strings returned by `useMemo`/`useCallback` callbacks (also as
+   * `React.useMemo`/`React.useCallback`), e.g. `useMemo(() => 'Button ButtonLg', [])`.
+   * @default false
+   */
+  expandMemoBodies?: boolean;
+  /**
+   * Functions taking class strings as arguments, e.g. `['useClasses', 'clsx']` expands
+   * `useClasses('Button ButtonLg')` and `clsx('Card', active && 'CardActive')`.
+   * Strings nested in arrays and ternaries are expanded too, as in
+   * `clsx(['Button', active ? 'Active' : 'Idle'])`. Alias keys of object arguments become
+   * one key per utility sharing the value: `clsx({ Button: big })` gives
+   * `clsx({ 'px-4': big, 'py-2': big })`. Keys whose value could have side effects, like
+   * `clsx({ Button: track() })`, are left as-is with an `impure-class-value` warning.
+   * @default []
+   */
+  classHooks?: string[];
+  /**
+   * Tags whose template literals are class strings, e.g. `` cx`Button ButtonLg` ``. Only
+   * templates without `${}` interpolations are expanded. Calls to the same names are handled
+   * like `classHooks`, so `cx('Button')` expands too.
+   * @default ['cx']
+   */
+  templateTags?: string[];
+  /**
+   * Expand string literals in the interpolations of styled-components templates, e.g.
+   * `` styled.div`${big ? 'Button' : ''}` ``. Only literals of conditional and logical expressions
+   * are rewritten; functions and the CSS text are left alone.
+   * @default false
+   */
+  expandStyledInterpolations?: boolean;
+  /**
+   * Functions returning class builders, e.g. `['makeClass']`. String arguments are expanded
+   * where the builder is defined: `makeClass('Button')`, `makeClass.bind(null, 'Button')`
+   * and curried calls like `makeClass('Button')('ButtonLg')`.
+   * @default []
+   */
+  classFactories?: string[];
+  /**
+   * Expand every string value inside `cva`/`tv` variant configs.
+   * @default false
+   */
+  expandCva?: boolean;
+  /**
+   * Function names treated as `cva`/`tv` when `expandCva` is enabled.
+   * @default ['cva', 'tv']
+   */
+  cvaFunctions?: string[];
+  /**
+   * Expand the `className` property of object literals passed to styling functions,
+   * e.g. `styled('div', { className: 'Button' })`.
+   * @default false
+   */
+  expandObjects?: boolean;
+  /**
+   * Function names whose object arguments are expanded when `expandObjects` is enabled.
+   * @default ['styled']
+   */
+  objectFunctions?: string[];
+  /**
+   * Expand `className` properties anywhere in objects passed to CSS-in-JS helpers,
+   * including under nested selectors, e.g. `cx({ '&:hover': { className: 'Button' } })`.
+   * Selectors and style values are left untouched.
+   */
+  expandCssObjects?: boolean;
+  /**
+   * Function names whose object arguments are searched when `expandCssObjects` is enabled.
+   * @default ['css', 'cx']
+   */
+  cssFunctions?: string[];
+  /**
+   * Expand `className`/`class` properties of object literals spread into JSX attributes,
+   * e.g. `<div {...(big ? { className: 'ButtonLg' } : {})} />`.
+   * @default false
+   */
+  expandSpreadObjects?: boolean;
+  /**
+   * Expand the `className` property of objects assigned to `defaultProps`,
+   * e.g. `Button.defaultProps = { className: 'Button' }`.
+   * @default false
+   */
+  expandDefaultProps?: boolean;
+  /**
+   * Expand the `className` property of objects passed to or returned from styled-components
+   * `.attrs` calls, e.g. `styled.div.attrs(() => ({ className: 'Button' }))`.
+   * @default false
+   */
+  expandStyledAttrs?: boolean;
+  /**
+   * Functions whose second argument is an element's props object, as in
+   * `React.createElement('div', { className: 'Button' })` or compiled JSX. Props merged with
+   * `Object.assign`, `_extends` or `_objectSpread` are covered too. `h` and `createElement`
+   * cover Preact/Inferno pragmas such as `h('div', { class: 'Button' })`. Pass `[]` to disable.
+   * @default ['React.createElement', '_jsx', '_jsxs', 'h', 'createElement']
+   */
+  createElementFunctions?: string[];
+  /**
+   * Aliases that also add an inline style when used without variants,
+   * e.g. `{ Hero: { class: 'px-4', style: 'background-image: url(/hero.png)' } }`.
+   * The style is appended to an existing string `style` attribute; expression styles are left alone.
+   */
+  componentAliases?: Record<string, { class: string; style?: string }>;
+  /**
+   * Secondary alias layer consulted for names the CSS aliases don't define, e.g. a shared
+   * base library that each project overrides. Aliases extracted from `cssPath` and
+   * `componentAliases` always take precedence.
+   * @default {}
+   */
+  fallbackAliases?: Record<string, string>;
+  /**
+   * Legacy alias names mapped to their replacement, e.g. `{ OldButton: 'Button' }`. They keep
+   * expanding, to the replacement's utilities once the old alias is removed, but every use
+   * emits a `deprecated-alias` warning naming the replacement.
+   * @default {}
+   */
+  deprecatedAliases?: Record<string, string>;
+}
+
+/** Config received by the WASM plugin */
+export type SWCPluginConfig = Omit<SWCPluginOptions, 'cssPath' | 'mergerFn'> & {
+  aliases: Record<string, string>;
+  debug: boolean;
+};
+
 /**
  * Creates an SWC plugin configuration for tailwind-expand.
  *
@@ -61,14 +625,14 @@ const wasmPath = relative(process.cwd(), absolutePath);
  * ```
  */
 export default function tailwindExpandSWC(
-  options: ExpandPluginOptions
-): [string, { aliases: Record<string, string>; debug: boolean }] {
-  const { cssPath, mergerFn, debug = false } = options;
+  options: SWCPluginOptions
+): [string, SWCPluginConfig] {
+  const { cssPath, mergerFn, debug = false, ...pluginOptions } = options;
 
   // Extract aliases from CSS at config time
   const absoluteCssPath = resolve(cssPath);
   const { aliases: rawAliases } = extract(absoluteCssPath);
   const aliases = expand(rawAliases, { mergerFn });
 
-  return [wasmPath, { aliases, debug }];
+  return [wasmPath, { ...pluginOptions, aliases, debug }];
 }
@@ -1,65 +1,1129 @@
-use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use swc_core::{
     atoms::Atom,
+    common::{
+        comments::{Comment, CommentKind, Comments},
+        BytePos, SourceMapper, Span, Spanned, DUMMY_SP,
+    },
     ecma::{
-        ast::{JSXAttr, JSXAttrName, JSXAttrOrSpread, JSXAttrValue, JSXOpeningElement, Lit, Program, Str},
-        visit::{visit_mut_pass, VisitMut, VisitMutWith},
+        ast::{
+            ArrayLit, AssignExpr, AssignTarget, BinExpr, BinaryOp, BlockStmt, BlockStmtOrExpr,
+            CallExpr, Callee, CondExpr, Expr, FnExpr, JSXAttr, JSXAttrName, JSXAttrOrSpread,
+            JSXAttrValue, JSXClosingElement, JSXElement, JSXElementChild, JSXElementName,
+            JSXEmptyExpr, JSXExpr, JSXExprContainer, JSXNamespacedName, JSXOpeningElement,
+            KeyValueProp, Lit, MemberExpr, MemberProp, ObjectLit, ObjectPat, ObjectPatProp,
+            ParenExpr, Pat, Program, Prop, PropName, PropOrSpread, ReturnStmt, SimpleAssignTarget,
+            Stmt, Str, TaggedTpl, TsConstAssertion, UnaryOp, VarDecl, VarDeclKind, VarDeclarator,
+        },
+        visit::{visit_mut_pass, Visit, VisitMut, VisitMutWith, VisitWith},
+    },
+    plugin::{
+        errors::HANDLER, metadata::TransformPluginMetadataContextKind, plugin_transform,
+        proxies::TransformPluginProgramMetadata,
     },
-    plugin::{plugin_transform, proxies::TransformPluginProgramMetadata},
 };
 
 /// Plugin configuration
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     /// Pre-expanded aliases map (alias name -> expanded utilities)
     /// The TypeScript wrapper extracts and expands aliases from CSS at config time
-    #[serde(default)]
+    /// Values may also be written as arrays of utilities, e.g. {"Button": ["px-4", "py-2"]}
+    /// Synonyms can share an entry, e.g. {"Button|Btn": "px-4 py-2"}; a key naming a
+    /// single alias wins over a shared one
+    #[serde(default, deserialize_with = "deserialize_aliases")]
     pub aliases: HashMap<String, String>,
+    /// Aliases that also set an inline style, e.g.
+    /// {"Hero": {"class": "px-4", "style": "background-image: url(/hero.png)"}}.
+    /// These override same-named entries in aliases
+    #[serde(default)]
+    pub component_aliases: HashMap<String, ComponentAlias>,
+    /// Secondary alias layer, e.g. a shared base library, consulted for names missing
+    /// from aliases and component_aliases (which always win)
+    #[serde(default, deserialize_with = "deserialize_aliases")]
+    pub fallback_aliases: HashMap<String, String>,
+    /// Legacy alias names mapped to their replacement, e.g. {"OldButton": "Button"}.
+    /// They keep expanding (to the replacement's utilities when the old name is no
+    /// longer an alias) but each use warns and points to the replacement
+    #[serde(default)]
+    pub deprecated_aliases: HashMap<String, String>,
+    /// Alias names never expanded, even with variants (e.g. "Button" when it is also
+    /// a class from external CSS). Ignored tokens are always left as-is
+    #[serde(default)]
+    pub ignore: HashSet<String>,
+    /// Aliases whose utilities are always important, as if every use was written
+    /// "!Reset". Aliases that include one of these by name are not affected
+    #[serde(default)]
+    pub important_aliases: HashSet<String>,
+    /// Variants whose expanded utilities are always important, e.g. ["print"] turns
+    /// print:Button into "print:!px-4 print:!py-2" while hover:Button is unchanged
+    #[serde(default)]
+    pub important_variants: HashSet<String>,
+    /// Turn a CSS `!important` left in an alias value (e.g. "px-4 !important",
+    /// copied from an @apply) into Tailwind's ! modifier instead of dropping it.
+    /// A warning is reported either way
+    #[serde(default)]
+    pub convert_important: bool,
+    /// Resolved theme values, e.g. {"colors.brand": "#0af"}, substituted for theme()
+    /// references in alias values: "text-[theme(colors.brand)]" gives "text-[#0af]".
+    /// Keys missing from the map are left for Tailwind to resolve
+    #[serde(default)]
+    pub theme: HashMap<String, String>,
+    /// Treat an opacity suffix on an alias token as applying to the alias's color
+    /// utilities, e.g. Button/50 with "bg-red-500 px-4" gives "bg-red-500/50 px-4".
+    /// Colors are detected heuristically from bg-, text-, border- and ring- utilities
+    #[serde(default)]
+    pub alias_opacity: bool,
+    /// Expand alias references written as {{Name}} inside a token into one class per
+    /// utility of the alias, keeping the text around it: "[&>li]:{{Button}}!" gives
+    /// "[&>li]:px-4! [&>li]:py-2!". Alias names that merely appear inside a token,
+    /// as in grid-cols-[var(--Button)], are never expanded
+    #[serde(default)]
+    pub alias_interpolation: bool,
+    /// Also resolve aliases written in another case style, e.g. button-lg,
+    /// buttonLg and button_lg all resolve to ButtonLg.
+    /// An exact key always wins; among aliases with the same normalized form,
+    /// the alphabetically first name is used (Config::validate rejects such configs)
+    #[serde(default)]
+    pub normalize_keys: bool,
     /// Enable debug mode to add data-expand attribute with alias names
     /// When true: adds data-expand="Button ButtonMd" attribute
     /// When false: no data-expand attribute
     #[serde(default)]
     pub debug: bool,
+    /// Where debug mode puts the expanded alias names
+    #[serde(default)]
+    pub debug_mode: DebugMode,
+    /// Attribute holding the alias names under DebugMode::DataAttr, e.g. "x-expand"
+    /// for frameworks that strip data-* attributes. Defaults to "data-expand"
+    #[serde(default)]
+    pub debug_attribute: Option<String>,
+    /// Order of the alias names listed in data-expand
+    #[serde(default)]
+    pub debug_order: DebugOrder,
+    /// List each alias with what it resolved to, e.g. data-expand="Button=px-4 py-2;
+    /// lg:ButtonLg=lg:h-12", instead of the alias names alone
+    #[serde(default)]
+    pub debug_verbose: bool,
+    /// Where data-expand is inserted among the element's attributes
+    #[serde(default)]
+    pub debug_attribute_position: DebugAttributePosition,
+    /// Aliases left out of data-expand, though still expanded
+    /// (e.g. low-level spacing aliases used everywhere)
+    #[serde(default)]
+    pub debug_exclude: HashSet<String>,
+    /// Leave class strings untouched and only add data-expand listing the
+    /// aliases that would be expanded (implies debug)
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Keep alias class names instead of inlining their utilities, and record each
+    /// used alias's utilities under "components" in the usage report so a later
+    /// step can generate `@layer components { .Button { @apply ... } }`
+    #[serde(default)]
+    pub extract_components: bool,
+    /// Replace each alias token with a short class hashed from its expanded utilities
+    /// (e.g. "lg:Button" -> "tw-1c2e3f4a") and record the hashed class's utilities
+    /// under "classes" in the usage report, for a later step to emit the CSS
+    #[serde(default)]
+    pub hash_classes: bool,
+    /// Expand string literals found in any array literal
+    /// e.g. ["Button", big && "ButtonLg"].join(" ")
+    #[serde(default)]
+    pub expand_arrays: bool,
+    /// Write alias usage counts to this directory when the transform finishes.
+    /// The plugin runs once per module, in parallel, so each module writes its own
+    /// file keyed by its filename (see write_module_report) instead of merging into
+    /// a shared one.
+    #[serde(default)]
+    pub report_path: Option<String>,
+    /// Write the names of every alias expanded in the module (a sorted array) to this
+    /// directory, one file per module like report_path, so the wrapper can tree-shake
+    /// generated CSS from the union of all modules' names
+    #[serde(default)]
+    pub used_aliases_path: Option<String>,
+    /// Also write every diagnostic to this directory, one file per module like
+    /// report_path, for editors and CI to surface inline. Modules without
+    /// diagnostics write nothing; configs that fail to load still write their error
+    #[serde(default)]
+    pub diagnostics_path: Option<String>,
+    /// Write where each alias token sits and what it expanded to to this directory,
+    /// one file per module like report_path, for editor features like hover tooltips
+    #[serde(default)]
+    pub expansion_map_path: Option<String>,
+    /// Per-kind override of how diagnostics are reported, e.g.
+    /// {"unknown-alias": "error", "output-limit": "off"}. Kinds left out keep their
+    /// default severity; configs that fail to load are always errors
+    #[serde(default)]
+    pub severity: HashMap<DiagnosticKind, Severity>,
+    /// Append a comment summarizing the module's expansions to the output, e.g.
+    /// /* tailwind-expand: 12 tokens, 2 aliases: Button, Card */
+    #[serde(default)]
+    pub summary_comment: bool,
+    /// Only transform modules whose filename matches one of these globs, e.g.
+    /// ["packages/web/**"]. `*` matches within a path segment and `**` across
+    /// segments; patterns not starting with "/" can match from any directory
+    #[serde(default)]
+    pub filename_include: Vec<String>,
+    /// Never transform modules whose filename matches one of these globs,
+    /// e.g. ["**/*.stories.tsx"]. Takes precedence over filename_include
+    #[serde(default)]
+    pub filename_exclude: Vec<String>,
+    /// Keep each matched alias token (e.g. "lg:Button") in front of its expanded
+    /// utilities, once per class string, so `.Button` stays targetable from external CSS.
+    /// Only literal alias names are kept, not glob, shortcut or composed matches
+    #[serde(default)]
+    pub keep_alias_class: bool,
+    /// keep_alias_class for the listed aliases only, e.g. ["Card"] while legacy BEM
+    /// CSS still targets `.Card`; other aliases are replaced as usual
+    #[serde(default)]
+    pub keep_alias_for: HashSet<String>,
+    /// Sort class strings containing aliases into Tailwind's recommended order
+    /// (see UTILITY_ORDER and VARIANT_ORDER)
+    #[serde(default)]
+    pub sort: bool,
+    /// Reorder class strings containing aliases into base utilities first, then one
+    /// run per variant prefix in order of first appearance, keeping the relative
+    /// order within each run: "hover:a b lg:c hover:d" becomes "b hover:a hover:d lg:c"
+    #[serde(default)]
+    pub group_variants: bool,
+    /// Migration mode, the inverse of expansion: replace runs of utilities that
+    /// exactly match an alias value (same utilities, same order, contiguous) with
+    /// the alias name, longest alias first. Aliases with a single utility are skipped
+    #[serde(default)]
+    pub collapse: bool,
+    /// Drop repeated classes from expanded class strings, keeping the first occurrence.
+    /// When false, "Button Button" keeps both copies of the utilities
+    #[serde(default)]
+    pub dedupe: bool,
+    /// Expand only the first token of each alias in a class string and leave later
+    /// ones as written: "Button hover:Button" becomes "px-4 py-2 hover:Button"
+    #[serde(default)]
+    pub expand_first_only: bool,
+    /// Drop utilities overridden later in the same class string within a group of
+    /// CONFLICT_GROUPS (display, position, float), e.g. "flex grid" becomes "grid".
+    /// Utilities only conflict under the same variants and important modifier
+    #[serde(default)]
+    pub resolve_conflicts: bool,
+    /// Output tuned for a runtime tailwind-merge (cn): turns on dedupe and turns
+    /// off resolve_conflicts, sort and group_variants, so exact repeats are dropped
+    /// while conflicting utilities keep their order for twMerge to resolve
+    #[serde(default)]
+    pub cn_friendly: bool,
+    /// Classes that dedupe and resolve_conflicts never remove, e.g. a forced "block"
+    #[serde(default)]
+    pub safelist: HashSet<String>,
+    /// Classes removed from every class string after expansion, whether written
+    /// directly or produced by an alias, e.g. semantic markers kept in source for
+    /// tooling: ["card", "debug-outline"]
+    #[serde(default)]
+    pub strip_classes: HashSet<String>,
+    /// Collapse repeated adjacent variants in the final class string, e.g.
+    /// "hover:hover:bg-red" becomes "hover:bg-red"
+    #[serde(default)]
+    pub minify: bool,
+    /// Reorder the variants of expanded utilities into VARIANT_ORDER (structural and
+    /// state variants, then dark, then breakpoints, then print), so dark:hover:Button
+    /// and hover:dark:Button give the same classes. Other variants stay where they
+    /// are and known variants are only reordered between them
+    #[serde(default)]
+    pub normalize_variant_order: bool,
+    /// Most classes a single class string may expand to. Aliases that would go past
+    /// the limit, and every alias after them, are left unexpanded with a warning
+    #[serde(default)]
+    pub max_output_tokens: Option<usize>,
+    /// Drop variants from an alias's utilities that the token's prefix already
+    /// applies, e.g. dark:Button with "dark:text-white" gives "dark:text-white"
+    /// rather than "dark:dark:text-white". Defaults to true
+    #[serde(default)]
+    pub dedupe_variants: Option<bool>,
+    /// Refuse aliases named like a built-in utility (see UTILITY_ORDER), e.g. "flex"
+    /// or "text-lg", so real utilities keep working; each one is dropped with a
+    /// warning. Off by default, since it also drops custom aliases in a utility's
+    /// namespace like "text-primary" or "bg-brand"; aliases named exactly like a
+    /// utility ("flex", "grid", ...) still get a warning then
+    #[serde(default)]
+    pub protect_utilities: bool,
+    /// Variants always stacked literally even when dedupe_variants is on, for
+    /// intentional doubles: with ["hover"], hover:Button and "hover:bg-red" give
+    /// "hover:hover:bg-red"
+    #[serde(default)]
+    pub never_dedupe_variants: HashSet<String>,
+    /// Variants allowed in front of an alias, e.g. ["hover", "lg", "dark"].
+    /// When set, other variants (like a misspelled "hvoer:Button") produce a warning
+    /// but are still expanded. Arbitrary variants ("[&>*]", "data-[state=open]")
+    /// and group names ("group-hover/sidebar" checks "group-hover") are handled leniently
+    #[serde(default)]
+    pub known_variants: Option<HashSet<String>>,
+    /// Lowercase recognized variants in front of an alias, so "Hover:Button" expands
+    /// with "hover:" instead of the invalid "Hover:". Recognized variants are
+    /// known_variants when set, otherwise the common variants in VARIANT_ORDER.
+    /// Alias names and unrecognized variants keep their case
+    #[serde(default)]
+    pub lowercase_variants: bool,
+    /// Recognize variants regardless of case, e.g. "HOVER:Button" with "hover:bg-blue"
+    /// doesn't stack the variant and "HOVER" counts as a known variant. Variants
+    /// keep the case they were written in (see lowercase_variants); alias names
+    /// are still matched case-sensitively
+    #[serde(default)]
+    pub case_insensitive_variants: bool,
+    /// What happens to alias-shaped tokens (e.g. a renamed "ButtonPrimary") that
+    /// match no alias
+    #[serde(default)]
+    pub fallback: UnknownAliasFallback,
+    /// Marker for alias tokens, e.g. "@" to write aliases as "@Button" or "lg:@Button".
+    /// Marked tokens resolve to the alias without the marker; unmarked names still work
+    #[serde(default)]
+    pub alias_prefix: Option<String>,
+    /// Report an error for tokens carrying alias_prefix that match no alias, while
+    /// unmarked tokens (normal utilities) are never reported
+    #[serde(default)]
+    pub strict_prefix: bool,
+    /// Warn about class attributes that are almost certainly mistakes, such as
+    /// className={0}, className={true} or a stray "hover:" with no utility.
+    /// They are left as-is either way
+    #[serde(default)]
+    pub strict: bool,
+    /// Warn when an expanded class string still contains a token matching an alias,
+    /// e.g. from aliases whose values name each other in a cycle. Aliases kept on
+    /// purpose through keep_alias_class or keep_alias_for are not reported
+    #[serde(default)]
+    pub assert_fully_expanded: bool,
+    /// Treat alias keys written as "/pattern/" as UnoCSS-style shortcuts, tried when
+    /// no literal alias matches. The pattern must match the whole token and its
+    /// captures can be used in the value, e.g. {"/btn-(\\w+)/": "bg-$1-500 text-white"}
+    #[serde(default)]
+    pub uno_shortcuts: bool,
+    /// Expand tokens made of aliases joined by bem_delimiter, e.g. "Button--Lg" as
+    /// Button followed by Lg. Tokens with any unknown segment are left alone
+    #[serde(default)]
+    pub bem_composition: bool,
+    /// Separator between composed aliases when bem_composition is enabled
+    /// Defaults to "--"
+    #[serde(default)]
+    pub bem_delimiter: Option<String>,
+    /// Apply an alias token's important modifier (!Button) only to the alias's
+    /// utilities without variants, so hover:bg-red stays unbanged
+    #[serde(default)]
+    pub important_base_only: bool,
+    /// How expanded class strings are spaced (see WhitespacePolicy)
+    #[serde(default)]
+    pub whitespace: WhitespacePolicy,
+    /// Expand these attributes and move the result to another attribute,
+    /// e.g. {"twClass": "className"} turns twClass="Button" into className="px-4 py-2"
+    #[serde(default)]
+    pub rename_attributes: HashMap<String, String>,
+    /// Further attributes treated like className, e.g. ["data-class"] for web
+    /// components bound through <my-card data-class={"Button"}>
+    #[serde(default)]
+    pub class_attributes: Vec<String>,
+    /// Which of the built-in class attributes ("className", "class", "classes") are
+    /// expanded, e.g. ["className"] for a React-only project that leaves `class` alone.
+    /// Also applies to class properties of createElement props and JSX spreads.
+    /// Defaults to all three
+    #[serde(default)]
+    pub default_class_attributes: Option<Vec<String>>,
+    /// Namespaced attributes treated like className, written as "namespace:name",
+    /// e.g. ["xml:class"]
+    #[serde(default)]
+    pub namespaced_class_attributes: Vec<String>,
+    /// Also expand the `part` attribute of web components, e.g. <my-card part="Card">
+    #[serde(default)]
+    pub expand_part_attributes: bool,
+    /// Components whose sole child is a class string, e.g. ["Classes"] expands
+    /// <Classes>Button ButtonLg</Classes> and <Classes>{"Button"}</Classes>
+    #[serde(default)]
+    pub expand_children_of: Vec<String>,
+    /// Variables holding maps of class strings, e.g. ["styles"] expands every string
+    /// value in const styles = { primary: "Button", danger: "ButtonDanger" }
+    #[serde(default)]
+    pub expand_style_maps: Vec<String>,
+    /// Expand every string value inside objects asserted `as const`, the usual shape
+    /// for type-safe variant maps, e.g. { size: { lg: "ButtonLg" } } as const
+    #[serde(default)]
+    pub expand_const_objects: bool,
+    /// Expand `const` string declarations passed by name to a class attribute, e.g.
+    /// const c = "Button" for <div className={c} />. The declaration is rewritten,
+    /// so every use of the constant gets the utilities
+    #[serde(default)]
+    pub expand_const_strings: bool,
+    /// Expand string defaults of destructured class properties, e.g.
+    /// function Btn({ className = "Button" }) {} or { className: cls = "Button" }.
+    /// Properties named like a class attribute (see is_class_attr) are matched
+    #[serde(default)]
+    pub expand_default_params: bool,
+    /// Attribute declaring a variant for every alias expanded inside an element,
+    /// e.g. "data-tw-variant" turns <div data-tw-variant="dark"><p className="Button" />
+    /// into dark:px-4 dark:py-2 on the <p>. Nested scopes stack outermost first; the
+    /// attribute itself is removed. Off unless set
+    #[serde(default)]
+    pub variant_scope_attribute: Option<String>,
+    /// Marker attribute for adopting the transform element by element, e.g.
+    /// "data-tw-expand": when set, only elements carrying it are expanded and the
+    /// marker is removed. Off unless set
+    #[serde(default)]
+    pub opt_in_attribute: Option<String>,
+    /// Expand the strings returned by the callback of useMemo/useCallback (see
+    /// MEMO_HOOKS), e.g. useMemo(() => "Button ButtonLg", [])
+    #[serde(default)]
+    pub expand_memo_bodies: bool,
+    /// Functions taking class strings as arguments, e.g. ["useClasses", "clsx"]
+    /// expands useClasses("Button ButtonLg") and clsx("Card", active && "CardActive")
+    /// (strings nested in arrays and ternaries included). Alias keys of object
+    /// arguments become one key per utility: clsx({ Button: big }), unless the value
+    /// could have side effects (e.g. a call) and would run once per key
+    #[serde(default)]
+    pub class_hooks: Vec<String>,
+    /// Tags whose template literals are class strings, e.g. cx`Button ButtonLg`. Only
+    /// templates without interpolations are expanded. Calls to these names are
+    /// handled like class_hooks, so cx("Button") expands too. Defaults to ["cx"]
+    #[serde(default)]
+    pub template_tags: Option<Vec<String>>,
+    /// Expand string literals in the interpolations of styled-components templates,
+    /// e.g. styled.div`${big ? "Button" : ""}`. Only the literals of conditional and
+    /// logical expressions are rewritten; functions and other dynamic expressions are
+    /// left alone, as is the CSS text of the template
+    #[serde(default)]
+    pub expand_styled_interpolations: bool,
+    /// Functions returning class builders, e.g. ["makeClass"]: the string
+    /// arguments of makeClass("Button") are expanded at definition, including
+    /// makeClass.bind(null, "Button") and curried makeClass("Button")("ButtonLg")
+    #[serde(default)]
+    pub class_factories: Vec<String>,
+    /// Expand every string value inside cva/tv variant configs,
+    /// e.g. cva("Button", { variants: { size: { lg: "ButtonLg" } } })
+    #[serde(default)]
+    pub expand_cva: bool,
+    /// Function names treated as cva/tv when expand_cva is enabled
+    /// Defaults to ["cva", "tv"]
+    #[serde(default)]
+    pub cva_functions: Option<Vec<String>>,
+    /// Expand `className` properties of object literals passed to styling
+    /// functions, e.g. styled("div", { className: "Button" })
+    #[serde(default)]
+    pub expand_objects: bool,
+    /// Expand class properties of object literals spread into JSX attributes,
+    /// e.g. <div {...(big ? { className: "ButtonLg" } : {})} />
+    #[serde(default)]
+    pub expand_spread_objects: bool,
+    /// Expand the className property of objects assigned to defaultProps,
+    /// e.g. Button.defaultProps = { className: "Button" }
+    #[serde(default)]
+    pub expand_default_props: bool,
+    /// Expand the className property of objects passed to or returned from
+    /// styled-components .attrs calls, e.g. styled.div.attrs(() => ({ className: "Button" }))
+    #[serde(default)]
+    pub expand_styled_attrs: bool,
+    /// Functions whose second argument is an element's props object, as in
+    /// React.createElement("div", { className: "Button" }) or compiled JSX, including
+    /// props merged through PROPS_MERGE_FUNCTIONS like Object.assign({}, props, {...})
+    /// Defaults to ["React.createElement", "_jsx", "_jsxs", "h", "createElement"], the last
+    /// two for Preact/Inferno pragmas like h("div", { class: "Button" }); an empty list
+    /// disables this
+    #[serde(default)]
+    pub create_element_functions: Option<Vec<String>>,
+    /// Function names whose object arguments are expanded when expand_objects is enabled
+    /// Defaults to ["styled"]
+    #[serde(default)]
+    pub object_functions: Option<Vec<String>>,
+    /// Expand `className` properties anywhere in object literals passed to CSS-in-JS
+    /// helpers, e.g. cx({ className: "Button" }). Selectors and style values are left alone
+    #[serde(default)]
+    pub expand_css_objects: bool,
+    /// Function names whose object arguments are searched when expand_css_objects is enabled
+    /// Defaults to ["css", "cx"]
+    #[serde(default)]
+    pub css_functions: Option<Vec<String>>,
+}
+
+/// Accept alias values as space-joined strings or arrays of utilities
+fn deserialize_aliases<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AliasValue {
+        Joined(String),
+        List(Vec<String>),
+    }
+
+    let aliases = HashMap::<String, AliasValue>::deserialize(deserializer)?;
+    Ok(aliases
+        .into_iter()
+        .map(|(name, value)| match value {
+            AliasValue::Joined(utilities) => (name, utilities),
+            AliasValue::List(utilities) => (name, utilities.join(" ")),
+        })
+        .collect())
+}
+
+/// Why a Config was rejected by Config::parse or Config::validate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The plugin options aren't valid JSON or don't match the Config shape
+    Malformed(String),
+    /// An alias (or component alias) key is empty or whitespace-only
+    EmptyAliasKey,
+    /// An alias key contains whitespace, so no class token can ever match it
+    AliasKeyWithWhitespace(String),
+    /// A namespaced class attribute isn't written as "namespace:name"
+    MalformedNamespacedAttribute(String),
+    /// A default_class_attributes entry isn't one of CLASS_ATTRIBUTES
+    UnknownDefaultClassAttribute(String),
+    /// Two aliases normalize to the same key while normalize_keys is enabled
+    DuplicateNormalizedKey(String, String),
+    /// A "/pattern/" shortcut key isn't a valid regex (key, regex error)
+    InvalidShortcutPattern(String, String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Malformed(error) => write!(f, "could not read plugin options: {}", error),
+            ConfigError::EmptyAliasKey => write!(f, "alias names must not be empty"),
+            ConfigError::AliasKeyWithWhitespace(name) => {
+                write!(f, "alias name \"{}\" contains whitespace", name)
+            }
+            ConfigError::MalformedNamespacedAttribute(attribute) => write!(
+                f,
+                "namespaced class attribute \"{}\" must be written as \"namespace:name\"",
+                attribute
+            ),
+            ConfigError::UnknownDefaultClassAttribute(attribute) => write!(
+                f,
+                "default class attribute \"{}\" must be one of {}",
+                attribute,
+                CLASS_ATTRIBUTES.join(", ")
+            ),
+            ConfigError::DuplicateNormalizedKey(first, second) => write!(
+                f,
+                "aliases \"{}\" and \"{}\" are the same name when normalizeKeys is enabled",
+                first, second
+            ),
+            ConfigError::InvalidShortcutPattern(key, error) => {
+                write!(f, "shortcut \"{}\" is not a valid pattern: {}", key, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    /// Read the plugin options SWC passes as JSON. Missing options give the default
+    /// config; anything else that doesn't deserialize is an error, not a silent no-op
+    pub fn parse(json: Option<&str>) -> Result<Self, ConfigError> {
+        match json.map(str::trim) {
+            None | Some("") => Ok(Self::default()),
+            Some(json) => serde_json::from_str(json)
+                .map_err(|error| ConfigError::Malformed(error.to_string())),
+        }
+    }
+
+    /// Whether the transform can't change anything: no aliases of any kind and none
+    /// of the options that act on unexpanded code (attribute renames, marker
+    /// attributes, unknown alias fallbacks, strict mode and strict_prefix, summary
+    /// comments, reports).
+    /// process_transform then returns a valid config's program without visiting it,
+    /// so non-ASCII spaces in class strings are left as written too
+    pub fn is_no_op(&self) -> bool {
+        self.aliases.is_empty()
+            && self.component_aliases.is_empty()
+            && self.fallback_aliases.is_empty()
+            && self.rename_attributes.is_empty()
+            && self.strip_classes.is_empty()
+            && self.opt_in_attribute.is_none()
+            && self.variant_scope_attribute.is_none()
+            && self.fallback == UnknownAliasFallback::Passthrough
+            && !self.strict
+            && !(self.strict_prefix && self.alias_prefix.is_some())
+            && !self.summary_comment
+            && self.report_path.is_none()
+            && self.used_aliases_path.is_none()
+            && self.diagnostics_path.is_none()
+            && self.expansion_map_path.is_none()
+    }
+
+    /// Check the config for problems that would otherwise make the transform silently misbehave
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut names: Vec<&str> = self
+            .aliases
+            .keys()
+            .chain(self.fallback_aliases.keys())
+            .flat_map(|key| alias_key_names(key))
+            .chain(self.component_aliases.keys().map(String::as_str))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        for name in &names {
+            if name.trim().is_empty() {
+                return Err(ConfigError::EmptyAliasKey);
+            }
+            if name.contains(char::is_whitespace) {
+                return Err(ConfigError::AliasKeyWithWhitespace(name.to_string()));
+            }
+        }
+
+        if self.uno_shortcuts {
+            for name in &names {
+                if let Some(pattern) = shortcut_pattern(name) {
+                    compile_shortcut(pattern).map_err(|error| {
+                        ConfigError::InvalidShortcutPattern(name.to_string(), error.to_string())
+                    })?;
+                }
+            }
+        }
+
+        for attribute in &self.namespaced_class_attributes {
+            let well_formed = attribute.split_once(':').is_some_and(|(namespace, name)| {
+                !namespace.is_empty() && !name.is_empty() && !name.contains(':')
+            });
+            if !well_formed {
+                return Err(ConfigError::MalformedNamespacedAttribute(attribute.clone()));
+            }
+        }
+
+        for attribute in self.default_class_attributes.iter().flatten() {
+            if !CLASS_ATTRIBUTES.contains(&attribute.as_str()) {
+                return Err(ConfigError::UnknownDefaultClassAttribute(attribute.clone()));
+            }
+        }
+
+        if self.normalize_keys {
+            let mut seen: HashMap<String, &str> = HashMap::new();
+            for name in names {
+                if let Some(first) = seen.insert(normalize_alias_key(name), name) {
+                    return Err(ConfigError::DuplicateNormalizedKey(
+                        first.to_string(),
+                        name.to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Where debug mode records the aliases expanded on an element
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugMode {
+    /// A data-expand (or debug_attribute) attribute on the element
+    #[default]
+    DataAttr,
+    /// A JSX comment as the element's first child, e.g. {/* tailwind-expand: Button */}.
+    /// Self-closing elements get a closing tag. Needs source comments, so elements
+    /// without a source position get nothing
+    Comment,
+    /// Nowhere, e.g. to turn debug output off without touching debug
+    None,
+}
+
+/// Order of the alias names in data-expand
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugOrder {
+    /// Sorted by name
+    #[default]
+    Alphabetical,
+    /// In the order the aliases were expanded on the element
+    Source,
+}
+
+/// Where data-expand goes in the element's attribute list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugAttributePosition {
+    /// Before every other attribute, so a spread can still override it
+    First,
+    /// After every other attribute, except spreads following the class attribute
+    /// (`<div className="Button" {...props} />`), which can still override both
+    #[default]
+    Last,
+}
+
+/// How tokens that look like aliases but match none are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnknownAliasFallback {
+    /// Leave the token as-is
+    #[default]
+    Passthrough,
+    /// Drop the token from the class string
+    Remove,
+    /// Leave the token as-is and emit a warning
+    Keep,
+}
+
+/// Alias that expands to utilities and an inline style
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ComponentAlias {
+    /// Utilities, expanded like a plain alias
+    #[serde(default)]
+    pub class: String,
+    /// Declarations added to the element's `style` attribute when the alias is
+    /// used without variants, e.g. "color: red"
+    #[serde(default)]
+    pub style: Option<String>,
+}
+
+/// How expand_class_name spaces its output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WhitespacePolicy {
+    /// Join tokens with single spaces. Strings without aliases are kept unless
+    /// they contain non-ASCII whitespace, which is normalized
+    #[default]
+    Single,
+    /// Keep the original spacing between tokens, only normalizing non-ASCII whitespace.
+    /// Falls back to single spaces when sort, dedupe, resolve_conflicts or
+    /// group_variants changes tokens. Tokens expanding to nothing (empty aliases,
+    /// fallback: Remove) leave the spacing around them
+    Preserve,
+    /// Like Single, but strings without aliases are never touched
+    Original,
 }
 
+/// Attribute names expanded as class lists
+const CLASS_ATTRIBUTES: &[&str] = &["className", "class", "classes"];
+
+/// Calls merging props objects in compiled output, e.g. Babel's
+/// _jsx("div", _objectSpread(_objectSpread({}, props), {}, { className: "Button" }))
+const PROPS_MERGE_FUNCTIONS: &[&str] = &[
+    "Object.assign",
+    "_extends",
+    "_objectSpread",
+    "_objectSpread2",
+];
+
+/// Hooks whose callback's return value is expanded when expand_memo_bodies is on
+const MEMO_HOOKS: &[&str] = &[
+    "useMemo",
+    "useCallback",
+    "React.useMemo",
+    "React.useCallback",
+];
+
 /// Alias map: alias name -> expanded utilities
 type AliasMap = HashMap<String, String>;
 
-/// Insert important modifier after all variant prefixes.
-/// e.g., insert_important("bg-primary") -> "!bg-primary"
-/// e.g., insert_important("hover:bg-primary") -> "hover:!bg-primary"
-fn insert_important(utility: &str) -> String {
-    if let Some(last_colon) = utility.rfind(':') {
-        format!(
-            "{}!{}",
-            &utility[..last_colon + 1],
-            &utility[last_colon + 1..]
-        )
+/// Alias map with each value pre-split into its utilities
+type CompiledAliasMap = HashMap<String, Vec<String>>;
+
+/// Remove CSS `!important` from compiled alias values, either as its own token
+/// ("px-4 !important", making every utility important) or glued to one
+/// ("px-4!important"). With convert, the utilities take Tailwind's ! modifier.
+/// Returns a warning per affected alias.
+fn strip_css_important(aliases: &mut CompiledAliasMap, convert: bool) -> Vec<Diagnostic> {
+    let mut names: Vec<String> = aliases
+        .iter()
+        .filter(|(_, utilities)| utilities.iter().any(|u| u.ends_with("!important")))
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+
+    let mut diagnostics = Vec::new();
+    for name in names {
+        let Some(utilities) = aliases.get_mut(&name) else {
+            continue;
+        };
+        let all_important = utilities.iter().any(|u| u == "!important");
+        utilities.retain(|u| u != "!important");
+        for utility in utilities.iter_mut() {
+            let important = match utility.strip_suffix("!important") {
+                Some(stripped) => {
+                    *utility = stripped.to_string();
+                    true
+                }
+                None => all_important,
+            };
+            if convert && important {
+                *utility = insert_important(utility);
+            }
+        }
+        diagnostics.push(Diagnostic::warning(
+            DiagnosticKind::CssImportant,
+            format!(
+                "[tailwind-expand] Alias \"{}\" contains CSS !important, which was {}",
+                name,
+                if convert {
+                    "converted to the ! modifier"
+                } else {
+                    "removed"
+                }
+            ),
+            Span::default(),
+        ));
+    }
+    diagnostics
+}
+
+/// Remove CSS left over in alias values generated from stylesheets: comments
+/// (`/* ... */`, or `// ...` starting a token) and semicolons ending a utility, so
+/// "px-4 py-2; /* primary */" becomes "px-4 py-2". Returns a warning per affected alias.
+fn strip_css_syntax(aliases: &mut AliasMap) -> Vec<Diagnostic> {
+    let mut names = Vec::new();
+    for (name, value) in aliases.iter_mut() {
+        if let Some(cleaned) = without_css_syntax(value) {
+            *value = cleaned;
+            names.push(name.clone());
+        }
+    }
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            Diagnostic::warning(
+                DiagnosticKind::CssSyntax,
+                format!(
+                    "[tailwind-expand] Alias \"{}\" contains CSS comments or semicolons, which were removed",
+                    name
+                ),
+                Span::default(),
+            )
+        })
+        .collect()
+}
+
+/// An alias value without CSS comments and trailing semicolons, None when it has neither
+fn without_css_syntax(value: &str) -> Option<String> {
+    if !value.contains(';') && !value.contains("/*") && !value.contains("//") {
+        return None;
+    }
+
+    let mut uncommented = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("/*") {
+        uncommented.push_str(&rest[..start]);
+        uncommented.push(' ');
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    uncommented.push_str(rest);
+
+    let cleaned = uncommented
+        .lines()
+        .flat_map(|line| {
+            line.split_whitespace()
+                .take_while(|token| !token.starts_with("//"))
+        })
+        .map(|token| token.trim_end_matches(';'))
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let original = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    (cleaned != original).then_some(cleaned)
+}
+
+/// Substitute theme(key) references in a utility with values from the theme map,
+/// quotes around the key allowed. Spaces in values become underscores, as
+/// arbitrary values require. None when nothing was substituted.
+fn resolve_theme(utility: &str, theme: &HashMap<String, String>) -> Option<String> {
+    if !utility.contains("theme(") {
+        return None;
+    }
+    let mut resolved = String::with_capacity(utility.len());
+    let mut rest = utility;
+    let mut changed = false;
+    while let Some(start) = rest.find("theme(") {
+        let (before, reference) = rest.split_at(start);
+        resolved.push_str(before);
+        let Some(end) = reference.find(')') else {
+            break;
+        };
+        let key = reference["theme(".len()..end].trim_matches(|c| c == '\'' || c == '"');
+        match theme.get(key) {
+            Some(value) => {
+                resolved.push_str(&value.trim().replace(' ', "_"));
+                changed = true;
+            }
+            None => resolved.push_str(&reference[..=end]),
+        }
+        rest = &reference[end + 1..];
+    }
+    resolved.push_str(rest);
+    changed.then_some(resolved)
+}
+
+/// Split every alias value into utilities once, so expansion never re-tokenizes it.
+/// Variant groups and responsive shorthands are expanded first.
+/// Repeated utilities within a value ("px-4 px-4 py-2") are always dropped here,
+/// since a class applies once however often it's listed; the dedupe option only
+/// covers repeats across tokens of a class string.
+fn compile_aliases(aliases: AliasMap) -> CompiledAliasMap {
+    aliases
+        .into_iter()
+        .map(|(name, value)| {
+            let value = expand_variant_groups(&value).unwrap_or(value);
+            let value = expand_responsive_shorthands(&value).unwrap_or(value);
+            let mut seen = HashSet::new();
+            let utilities = value
+                .split_whitespace()
+                .filter(|utility| seen.insert(*utility))
+                .map(str::to_string)
+                .collect();
+            (name, utilities)
+        })
+        .collect()
+}
+
+/// Alias names declared by a key: "Button|Btn" declares both. Shortcut patterns
+/// ("/pattern/") are a single key even when they contain "|"
+fn alias_key_names(key: &str) -> Vec<&str> {
+    if shortcut_pattern(key).is_some() {
+        vec![key]
     } else {
-        format!("!{}", utility)
+        key.split('|').collect()
+    }
+}
+
+/// Split keys declaring several aliases into one entry per alias. Keys naming a
+/// single alias take precedence over shared keys
+fn split_alias_keys(aliases: AliasMap) -> AliasMap {
+    let mut shared = Vec::new();
+    let mut split = AliasMap::with_capacity(aliases.len());
+    for (key, value) in aliases {
+        if alias_key_names(&key).len() > 1 {
+            shared.push((key, value));
+        } else {
+            split.insert(key, value);
+        }
+    }
+    // Sorted so that overlapping shared keys resolve the same way every run
+    shared.sort();
+    for (key, value) in shared {
+        for name in alias_key_names(&key) {
+            split
+                .entry(name.to_string())
+                .or_insert_with(|| value.clone());
+        }
+    }
+    split
+}
+
+/// Pattern of a shortcut key written as "/pattern/"
+fn shortcut_pattern(key: &str) -> Option<&str> {
+    key.strip_prefix('/')?.strip_suffix('/')
+}
+
+/// Compile a shortcut pattern so that it has to match a whole token
+fn compile_shortcut(pattern: &str) -> Result<Regex, regex::Error> {
+    Regex::new(&format!("^(?:{})$", pattern))
+}
+
+/// Case-insensitive form of an alias name with hyphens and underscores removed,
+/// e.g. "button-lg", "buttonLg" and "ButtonLg" all become "buttonlg"
+fn normalize_alias_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Utility prefixes whose values are usually colors, for alias_opacity
+const COLOR_PREFIXES: &[&str] = &["bg-", "text-", "border-", "ring-"];
+
+/// Values under COLOR_PREFIXES that aren't colors (sizes and widths start with a digit)
+const NON_COLOR_VALUES: &[&str] = &[
+    "xs",
+    "sm",
+    "base",
+    "lg",
+    "xl",
+    "left",
+    "center",
+    "right",
+    "justify",
+    "start",
+    "end",
+    "wrap",
+    "nowrap",
+    "balance",
+    "pretty",
+    "ellipsis",
+    "clip",
+    "solid",
+    "dashed",
+    "dotted",
+    "double",
+    "hidden",
+    "none",
+    "collapse",
+    "separate",
+    "inset",
+    "fixed",
+    "local",
+    "scroll",
+    "auto",
+    "cover",
+    "contain",
+    "repeat",
+    "no-repeat",
+    "x",
+    "y",
+    "t",
+    "r",
+    "b",
+    "l",
+    "s",
+    "e",
+];
+
+/// Split an alias token's opacity suffix, e.g. "lg:Button/50" -> ("lg:Button", "50").
+/// The suffix is a number or an arbitrary value like [.35]
+fn split_alias_opacity(token: &str) -> Option<(&str, &str)> {
+    let (base, opacity) = token.rsplit_once('/')?;
+    let is_number = !opacity.is_empty() && opacity.bytes().all(|b| b.is_ascii_digit());
+    let is_arbitrary = opacity.len() > 2 && opacity.starts_with('[') && opacity.ends_with(']');
+    (!base.is_empty() && (is_number || is_arbitrary)).then_some((base, opacity))
+}
+
+/// Whether a base utility (no variants or important modifier) probably sets a color,
+/// e.g. bg-red-500, text-white or border-x-[#fff], but not text-lg or border-2
+fn is_color_utility(utility: &str) -> bool {
+    COLOR_PREFIXES.iter().any(|prefix| {
+        let Some(value) = utility.strip_prefix(prefix) else {
+            return false;
+        };
+        // border-x-red-500, ring-offset-red-500
+        let value = match *prefix {
+            "border-" => ["x-", "y-", "t-", "r-", "b-", "l-", "s-", "e-"]
+                .iter()
+                .find_map(|side| value.strip_prefix(side))
+                .unwrap_or(value),
+            "ring-" => value.strip_prefix("offset-").unwrap_or(value),
+            _ => value,
+        };
+        !value.is_empty()
+            && !value.starts_with(|c: char| c.is_ascii_digit())
+            && !value.contains('/')
+            && !NON_COLOR_VALUES.contains(&value)
+    })
+}
+
+/// Append an opacity modifier to the color utilities of a class string,
+/// e.g. with_opacity("hover:!bg-red-500 px-4", "50") -> "hover:!bg-red-500/50 px-4"
+fn with_opacity(utilities: &str, opacity: &str) -> String {
+    utilities
+        .split_whitespace()
+        .map(|utility| {
+            let split = last_variant_colon(utility).map_or(0, |colon_idx| colon_idx + 1);
+            let (prefix, base) = utility.split_at(split);
+            let leading = if base.starts_with('!') { "!" } else { "" };
+            let trailing = if base.len() > 1 && base.ends_with('!') {
+                "!"
+            } else {
+                ""
+            };
+            let core = &base[leading.len()..base.len() - trailing.len()];
+            if is_color_utility(core) {
+                format!("{}{}{}/{}{}", prefix, leading, core, opacity, trailing)
+            } else {
+                utility.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Insert important modifier after all variant prefixes.
+/// Already-important utilities (leading or trailing `!`) are returned unchanged.
+/// Negative utilities take the bang before the minus, as Tailwind parses `!` first.
+///
+/// ```
+/// use tailwind_expand_swc::insert_important;
+///
+/// assert_eq!(insert_important("bg-primary"), "!bg-primary");
+/// assert_eq!(insert_important("hover:bg-primary"), "hover:!bg-primary");
+/// assert_eq!(insert_important("hover:!bg-primary"), "hover:!bg-primary");
+/// assert_eq!(insert_important("bg-primary!"), "bg-primary!");
+/// // Negative utilities, including the invalid "-!mt-4" spelling
+/// assert_eq!(insert_important("lg:-mt-4"), "lg:!-mt-4");
+/// assert_eq!(insert_important("lg:-!mt-4"), "lg:!-mt-4");
+/// // Colons inside arbitrary values aren't variants
+/// assert_eq!(insert_important("supports-[display:grid]:grid"), "supports-[display:grid]:!grid");
+/// ```
+pub fn insert_important(utility: &str) -> String {
+    let split = last_variant_colon(utility).map_or(0, |colon_idx| colon_idx + 1);
+    let (prefix, base) = utility.split_at(split);
+    if base.starts_with('!') || base.ends_with('!') {
+        return utility.to_string();
+    }
+    // "-!mt-4" is not valid Tailwind; normalize to "!-mt-4"
+    if let Some(rest) = base.strip_prefix("-!") {
+        return format!("{}!-{}", prefix, rest);
     }
+    format!("{}!{}", prefix, base)
 }
 
 /// Apply variant prefix to utility, deduplicating overlapping variants.
-/// e.g., apply_variant_prefix("hover:", "hover:bg-primary") -> "hover:bg-primary"
-/// e.g., apply_variant_prefix("dark:hover:", "hover:bg-primary") -> "dark:hover:bg-primary"
-fn apply_variant_prefix(variant_prefix: &str, utility: &str) -> String {
+/// Leading variants of the utility that the prefix already contains are dropped.
+/// Segments are compared whole, so named groups like group-hover/sidebar and
+/// group-hover/other stay distinct. The prefix includes its trailing colon.
+///
+/// ```
+/// use tailwind_expand_swc::apply_variant_prefix;
+///
+/// assert_eq!(apply_variant_prefix("hover:", "bg-primary"), "hover:bg-primary");
+/// assert_eq!(apply_variant_prefix("hover:", "hover:bg-primary"), "hover:bg-primary");
+/// assert_eq!(apply_variant_prefix("dark:hover:", "hover:bg-primary"), "dark:hover:bg-primary");
+/// assert_eq!(apply_variant_prefix("lg:", "-mt-4"), "lg:-mt-4");
+/// assert_eq!(
+///     apply_variant_prefix("group-hover/sidebar:", "group-hover/other:underline"),
+///     "group-hover/sidebar:group-hover/other:underline"
+/// );
+/// assert_eq!(apply_variant_prefix("", "p-2"), "p-2");
+/// ```
+pub fn apply_variant_prefix(variant_prefix: &str, utility: &str) -> String {
+    apply_variant_prefix_with_case(variant_prefix, utility, false, &HashSet::new())
+}
+
+/// apply_variant_prefix, optionally comparing variants ASCII case-insensitively so
+/// that "HOVER:" drops the utility's own "hover:". The prefix's spelling is kept.
+/// Deduplication stops at the first of the utility's variants in `never_dedupe`.
+fn apply_variant_prefix_with_case(
+    variant_prefix: &str,
+    utility: &str,
+    ignore_case: bool,
+    never_dedupe: &HashSet<String>,
+) -> String {
     if variant_prefix.is_empty() {
         return utility.to_string();
     }
 
     // "dark:hover:" -> {"dark", "hover"}
-    let prefix_without_colon = &variant_prefix[..variant_prefix.len() - 1];
-    let prefix_variants: HashSet<&str> = prefix_without_colon.split(':').collect();
+    let prefix_variants: HashSet<Cow<'_, str>> = variant_segments(variant_prefix)
+        .map(|variant| {
+            if ignore_case {
+                Cow::Owned(variant.to_ascii_lowercase())
+            } else {
+                Cow::Borrowed(variant)
+            }
+        })
+        .collect();
 
     let mut result = utility;
 
     loop {
-        if let Some(colon_idx) = result.find(':') {
+        if let Some(colon_idx) = variant_colons(result).next() {
             let first_variant = &result[..colon_idx];
-            if prefix_variants.contains(first_variant) {
+            if never_dedupe.contains(first_variant) {
+                break;
+            }
+            let known = if ignore_case {
+                prefix_variants.contains(first_variant.to_ascii_lowercase().as_str())
+            } else {
+                prefix_variants.contains(first_variant)
+            };
+            if known {
                 result = &result[colon_idx + 1..];
                 continue;
             }
@@ -70,326 +1134,9419 @@ fn apply_variant_prefix(variant_prefix: &str, utility: &str) -> String {
     format!("{}{}", variant_prefix, result)
 }
 
-/// The main visitor that transforms className attributes
-pub struct TailwindExpandVisitor {
-    aliases: AliasMap,
-    debug: bool,
+/// Kind of problem a diagnostic reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiagnosticKind {
+    /// An alias maps to an empty or whitespace-only value
+    EmptyAlias,
+    /// The plugin config failed validation
+    InvalidConfig,
+    /// An alias token uses a variant missing from known_variants
+    UnknownVariant,
+    /// An alias-shaped token matches no alias (fallback: Keep, or strict_prefix
+    /// for tokens carrying alias_prefix)
+    UnknownAlias,
+    /// A class attribute is set to a number or boolean literal (strict)
+    NonStringClassName,
+    /// A class token is only variants with no utility, e.g. "hover:" (strict)
+    EmptyUtility,
+    /// A class string reached max_output_tokens, so some aliases weren't expanded
+    OutputLimit,
+    /// An alias value contains a CSS `!important`, which was stripped or converted
+    CssImportant,
+    /// An alias value contains CSS comments or semicolons, which were removed
+    CssSyntax,
+    /// A token uses an alias listed in deprecated_aliases
+    DeprecatedAlias,
+    /// An alias is named like a built-in utility: dropped under protect_utilities,
+    /// otherwise reported when the name is exactly a utility
+    ProtectedUtility,
+    /// An expanded class string still contains an alias (assert_fully_expanded)
+    UnexpandedAlias,
+    /// A report file (report_path and the like) couldn't be written
+    ReportWrite,
+    /// A clsx object alias key's value may have side effects, so the key wasn't split
+    ImpureClassValue,
 }
 
-impl TailwindExpandVisitor {
-    pub fn new(config: Config) -> Self {
+/// How a diagnostic is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warn,
+    /// Don't report it at all (only as a configured severity)
+    Off,
+}
+
+/// A problem found while setting up or running the transform
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    pub message: String,
+    /// Source location, DUMMY_SP for configuration problems
+    pub span: Span,
+}
+
+impl Diagnostic {
+    fn error(kind: DiagnosticKind, message: String, span: Span) -> Self {
         Self {
-            aliases: config.aliases,
-            debug: config.debug,
+            kind,
+            severity: Severity::Error,
+            message,
+            span,
         }
     }
 
-    /// Expand a className string by replacing aliases with their utilities
-    /// Returns (expanded_class_name, set_of_expanded_alias_names)
-    fn expand_class_name(&self, class_name: &str) -> (String, HashSet<String>) {
-        let mut result = Vec::new();
-        let mut expanded_aliases = HashSet::new();
+    fn warning(kind: DiagnosticKind, message: String, span: Span) -> Self {
+        Self {
+            kind,
+            severity: Severity::Warn,
+            message,
+            span,
+        }
+    }
+}
 
-        for token in class_name.split_whitespace() {
-            let (expanded, alias_name) = self.expand_token(token);
-            result.push(expanded);
-            if let Some(name) = alias_name {
-                expanded_aliases.insert(name);
-            }
+/// A diagnostic as written to diagnostics_path, with a 1-based line and column
+/// (absent for configuration problems)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DiagnosticRecord {
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+impl DiagnosticRecord {
+    /// `locate` maps a source position to its 1-based line and column
+    fn new(diagnostic: &Diagnostic, locate: impl Fn(BytePos) -> (usize, usize)) -> Self {
+        let (line, column) = if diagnostic.span.is_dummy() {
+            (None, None)
+        } else {
+            let (line, column) = locate(diagnostic.span.lo);
+            (Some(line), Some(column))
+        };
+        Self {
+            kind: diagnostic.kind,
+            severity: diagnostic.severity,
+            message: diagnostic.message.clone(),
+            line,
+            column,
         }
+    }
+}
+
+/// An alias token found in a class string and the utilities it expanded to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expansion {
+    /// The token as written, e.g. "lg:Button"
+    pub token: String,
+    /// The alias it resolved to, e.g. "Button"
+    pub alias: String,
+    pub utilities: Vec<String>,
+    /// Source location of the token, or of the whole string when it can't be pinpointed
+    pub span: Span,
+}
+
+/// An expansion as written to expansion_map_path, with the token's 1-based line and column
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ExpansionRecord {
+    pub token: String,
+    pub alias: String,
+    pub utilities: Vec<String>,
+    pub line: usize,
+    pub column: usize,
+}
 
-        (result.join(" "), expanded_aliases)
+impl ExpansionRecord {
+    /// `locate` maps a source position to its 1-based line and column
+    fn new(expansion: &Expansion, locate: impl Fn(BytePos) -> (usize, usize)) -> Self {
+        let (line, column) = locate(expansion.span.lo);
+        Self {
+            token: expansion.token.clone(),
+            alias: expansion.alias.clone(),
+            utilities: expansion.utilities.clone(),
+            line,
+            column,
+        }
     }
+}
 
-    /// Expand a single token (handles variants like lg:ButtonMd, dark:hover:Button)
-    /// Returns (expanded_string, Option<full_token_for_data_expand>)
-    fn expand_token(&self, token: &str) -> (String, Option<String>) {
-        // Check for variant prefix using last colon (e.g., dark:hover:Button -> prefix="dark:hover:", alias="Button")
-        if let Some(colon_idx) = token.rfind(':') {
-            let prefix = &token[..colon_idx + 1];
-            let mut rest = &token[colon_idx + 1..];
+/// Report diagnostics through the SWC handler (no-op when no handler is set, e.g. in tests)
+fn emit_diagnostics(diagnostics: &[Diagnostic]) {
+    if diagnostics.is_empty() || !HANDLER.is_set() {
+        return;
+    }
 
-            // Handle important modifier after variant (e.g., lg:!ButtonMd)
-            let important = rest.starts_with('!');
-            if important {
-                rest = &rest[1..];
+    HANDLER.with(|handler| {
+        for diagnostic in diagnostics {
+            match diagnostic.severity {
+                Severity::Warn => handler
+                    .struct_span_warn(diagnostic.span, &diagnostic.message)
+                    .emit(),
+                Severity::Error => handler
+                    .struct_span_err(diagnostic.span, &diagnostic.message)
+                    .emit(),
+                Severity::Off => {}
             }
+        }
+    });
+}
 
-            // Check if rest is an alias
-            if let Some(expanded) = self.aliases.get(rest) {
-                let utilities: String = expanded
+/// Source text of a template literal quasi, escaping what would end the
+/// template or start an interpolation
+fn template_raw(value: &str) -> Atom {
+    Atom::from(
+        value
+            .replace('\\', "\\\\")
+            .replace('`', "\\`")
+            .replace("${", "\\${"),
+    )
+}
+
+/// Source text of a JSX attribute string. JSX strings have no backslash escapes,
+/// so the quote is picked to fit the value and anything else that can't appear
+/// verbatim is written as an HTML entity, e.g. content-['"'] with both quotes.
+fn jsx_attr_raw(value: &str) -> Atom {
+    let quote = if value.contains('"') && !value.contains('\'') {
+        '\''
+    } else {
+        '"'
+    };
+    let mut raw = String::with_capacity(value.len() + 2);
+    raw.push(quote);
+    for (index, ch) in value.char_indices() {
+        match ch {
+            '"' if quote == '"' => raw.push_str("&quot;"),
+            // Only where the parser would otherwise decode an entity
+            '&' if is_entity_start(&value[index + 1..]) => raw.push_str("&amp;"),
+            _ => raw.push(ch),
+        }
+    }
+    raw.push(quote);
+    Atom::from(raw)
+}
+
+/// Source text of a JSX text child. Braces and angle brackets would start an
+/// expression or a tag, so they're written as HTML entities, e.g. [&>svg]:size-4
+fn jsx_text_raw(value: &str) -> Atom {
+    let mut raw = String::with_capacity(value.len());
+    for (index, ch) in value.char_indices() {
+        match ch {
+            '{' => raw.push_str("&#123;"),
+            '}' => raw.push_str("&#125;"),
+            '<' => raw.push_str("&lt;"),
+            '>' => raw.push_str("&gt;"),
+            // Only where the parser would otherwise decode an entity
+            '&' if is_entity_start(&value[index + 1..]) => raw.push_str("&amp;"),
+            _ => raw.push(ch),
+        }
+    }
+    Atom::from(raw)
+}
+
+/// Whether text after a `&` reads as an entity reference like `amp;` or `#38;`
+fn is_entity_start(rest: &str) -> bool {
+    let Some(end) = rest.find(';') else {
+        return false;
+    };
+    let name = rest[..end].strip_prefix('#').unwrap_or(&rest[..end]);
+    !name.is_empty() && name.chars().all(|ch| ch.is_ascii_alphanumeric())
+}
+
+/// Span of a class token inside a string literal, so editors can point at the token.
+/// Falls back to the whole literal when the token isn't found verbatim (e.g. it came
+/// from a variant group) or escapes make source offsets differ from the value's.
+fn token_span(str_lit: &Str, token: &str) -> Span {
+    let value = str_lit.value.as_str();
+    let span = str_lit.span;
+    // Quotes included; escapes always make the source longer than the value
+    if (span.hi.0 - span.lo.0) as usize != value.len() + 2 {
+        return span;
+    }
+    let Some(offset) = value
+        .split_whitespace()
+        .find(|candidate| *candidate == token)
+        .map(|candidate| candidate.as_ptr() as usize - value.as_ptr() as usize)
+    else {
+        return span;
+    };
+    let lo = span.lo + BytePos(1 + offset as u32);
+    Span::new(lo, lo + BytePos(token.len() as u32))
+}
+
+/// Whether a name follows the alias naming convention (PascalCase, see core's CAMEL_CASE_REGEX)
+fn is_alias_shaped(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(first) if first.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_alphanumeric())
+}
+
+/// How a single className token was handled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenOutcome {
+    /// Token matched an alias
+    Expanded {
+        /// Matched alias name, without variants or important modifier
+        alias: String,
+        /// Expanded utilities, space-separated
+        utilities: String,
+    },
+    /// Token is alias-shaped (e.g. "lg:Buton") but no such alias exists
+    Unknown {
+        /// Unmatched alias name, without variants or important modifier
+        name: String,
+    },
+    /// Plain utility or class, left as-is
+    Passthrough,
+}
+
+impl TokenOutcome {
+    fn unmatched(name: &str) -> Self {
+        if is_alias_shaped(name) {
+            TokenOutcome::Unknown {
+                name: name.to_string(),
+            }
+        } else {
+            TokenOutcome::Passthrough
+        }
+    }
+}
+
+/// Per-token breakdown of a className expansion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenExpansion {
+    /// Token as written in the className
+    pub token: String,
+    /// Expanded utilities, space-separated (the token itself when not an alias)
+    pub utilities: String,
+    /// Matched alias name, without variants or important modifier
+    pub alias: Option<String>,
+}
+
+/// Comment that disables the transform for a whole file when placed before the first statement
+const DISABLE_FILE_COMMENT: &str = "tailwind-expand-disable";
+
+/// Comment that disables expansion of the JSX element or attribute that follows it
+const DISABLE_NEXT_LINE_COMMENT: &str = "tailwind-expand-disable-next-line";
+
+/// Whether the program opts out via a leading `/* tailwind-expand-disable */` comment
+fn is_disabled_by_comment(program: &Program, comments: &dyn Comments) -> bool {
+    let first_item = match program {
+        Program::Module(module) => module.body.first().map(|item| item.span_lo()),
+        Program::Script(script) => script.body.first().map(|stmt| stmt.span_lo()),
+    };
+
+    [Some(program.span_lo()), first_item]
+        .into_iter()
+        .flatten()
+        .filter_map(|pos| comments.get_leading(pos))
+        .flatten()
+        .any(|comment| comment.text.trim() == DISABLE_FILE_COMMENT)
+}
+
+/// Collects the identifiers passed directly to a class attribute, e.g. `c` in
+/// `className={c}`, so their const declarations can be expanded
+struct ClassIdentCollector<'a> {
+    visitor: &'a TailwindExpandVisitor,
+    names: HashSet<String>,
+}
+
+impl Visit for ClassIdentCollector<'_> {
+    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
+        attr.visit_children_with(self);
+
+        if !self.visitor.is_class_attr(attr) {
+            return;
+        }
+        if let Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+            expr: JSXExpr::Expr(expr),
+            ..
+        })) = &attr.value
+        {
+            if let Expr::Ident(ident) = &**expr {
+                self.names.insert(ident.sym.to_string());
+            }
+        }
+    }
+}
+
+/// Run the visitor over a program unless the file opts out with a disable comment
+fn transform_program(program: Program, visitor: &mut TailwindExpandVisitor) -> Program {
+    if visitor
+        .comments
+        .as_deref()
+        .is_some_and(|comments| is_disabled_by_comment(&program, comments))
+    {
+        return program;
+    }
+    if visitor.expand_const_strings {
+        let mut collector = ClassIdentCollector {
+            visitor,
+            names: HashSet::new(),
+        };
+        program.visit_with(&mut collector);
+        visitor.const_class_names = collector.names;
+    }
+    let program = program.apply(visit_mut_pass(&mut *visitor));
+    if visitor.summary_comment {
+        if let Some(comments) = visitor.comments.as_deref() {
+            comments.add_trailing(
+                program.span().hi,
+                Comment {
+                    kind: CommentKind::Block,
+                    span: DUMMY_SP,
+                    text: visitor.summary_text().into(),
+                },
+            );
+        }
+    }
+    program
+}
+
+/// Why transform_source couldn't produce output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransformError {
+    /// The config failed validation
+    InvalidConfig(ConfigError),
+    /// The source isn't valid JavaScript/JSX
+    Parse(String),
+}
+
+impl std::fmt::Display for TransformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransformError::InvalidConfig(error) => write!(f, "invalid config: {}", error),
+            TransformError::Parse(message) => write!(f, "failed to parse source: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
+
+impl From<ConfigError> for TransformError {
+    fn from(error: ConfigError) -> Self {
+        TransformError::InvalidConfig(error)
+    }
+}
+
+/// Describe how a single token expands against `aliases` with the default options:
+/// the matched alias, its variants, whether it's important, and the utilities it
+/// becomes. For debugging and generated documentation.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use tailwind_expand_swc::explain;
+///
+/// let aliases = HashMap::from([("Button".to_string(), "px-4 py-2".to_string())]);
+/// assert_eq!(
+///     explain("Button", &aliases),
+///     "Button\n  alias: Button\n  variants: none\n  important: no\n  utilities: px-4 py-2"
+/// );
+/// assert_eq!(explain("p-4", &aliases), "p-4\n  not an alias, left as-is");
+/// ```
+pub fn explain(token: &str, aliases: &HashMap<String, String>) -> String {
+    let visitor = TailwindExpandVisitor::new(Config {
+        aliases: aliases.clone(),
+        ..Default::default()
+    });
+    match visitor.try_expand_token(token) {
+        TokenOutcome::Expanded { alias, utilities } => {
+            let (prefix, rest) = match last_variant_colon(token) {
+                Some(colon_idx) if alias != token => {
+                    (&token[..colon_idx + 1], &token[colon_idx + 1..])
+                }
+                _ => ("", token),
+            };
+            let important = alias != token
+                && (prefix.starts_with('!') || rest.starts_with('!') || rest.ends_with('!'));
+            let variants: Vec<&str> = variant_segments(prefix.trim_start_matches('!')).collect();
+            format!(
+                "{}\n  alias: {}\n  variants: {}\n  important: {}\n  utilities: {}",
+                token,
+                alias,
+                if variants.is_empty() {
+                    "none".to_string()
+                } else {
+                    variants.join(", ")
+                },
+                if important { "yes" } else { "no" },
+                utilities
+            )
+        }
+        TokenOutcome::Unknown { name } => {
+            format!("{}\n  unknown alias: {}, left as-is", token, name)
+        }
+        TokenOutcome::Passthrough => format!("{}\n  not an alias, left as-is", token),
+    }
+}
+
+/// Expand the class strings of a whole JavaScript/JSX file outside the plugin
+/// (e.g. from a CLI or editor integration). Comments are kept, so disable
+/// comments work as they do in the plugin.
+pub fn transform_source(source: &str, config: &Config) -> Result<String, TransformError> {
+    use swc_core::common::{comments::SingleThreadedComments, sync::Lrc, FileName, SourceMap};
+    use swc_core::ecma::parser::{parse_file_as_program, EsSyntax, Syntax};
+
+    let mut visitor = TailwindExpandVisitor::try_new(config.clone())?;
+
+    let source_map: Lrc<SourceMap> = Default::default();
+    let file = source_map.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+    let comments = SingleThreadedComments::default();
+    let mut recovered = vec![];
+    let program = parse_file_as_program(
+        &file,
+        Syntax::Es(EsSyntax {
+            jsx: true,
+            ..Default::default()
+        }),
+        Default::default(),
+        Some(&comments),
+        &mut recovered,
+    )
+    .map_err(|error| TransformError::Parse(error.kind().msg().into_owned()))?;
+    if let Some(error) = recovered.first() {
+        return Err(TransformError::Parse(error.kind().msg().into_owned()));
+    }
+
+    visitor = visitor.with_comments(comments.clone());
+    let program = transform_program(program, &mut visitor);
+    Ok(swc_core::ecma::codegen::to_code_default(
+        source_map,
+        Some(&comments),
+        &program,
+    ))
+}
+
+/// Alias usage collected while transforming a module
+#[derive(Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct UsageReport {
+    /// Alias name -> number of elements it was expanded on
+    pub aliases: BTreeMap<String, usize>,
+    /// Number of elements with at least one expanded alias
+    pub elements: usize,
+    /// Alias name -> utilities, collected when extract_components is enabled
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, String>,
+    /// Hashed class -> utilities, collected when hash_classes is enabled
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub classes: BTreeMap<String, String>,
+}
+
+/// Class name standing in for a set of expanded utilities under hash_classes.
+/// FNV-1a, so the same utilities get the same class on every build and platform.
+fn hashed_class(utilities: &str) -> String {
+    format!("tw-{:08x}", fnv1a(utilities))
+}
+
+/// 32-bit FNV-1a hash, stable across builds and platforms
+fn fnv1a(text: &str) -> u32 {
+    text.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Compile a filename glob: `**` matches across path segments, `*` and `?` within one.
+/// Relative patterns may start at any directory, so "src/*.tsx" matches "/app/src/a.tsx"
+fn compile_filename_glob(pattern: &str) -> Regex {
+    let mut regex = String::from(if pattern.starts_with('/') {
+        "^"
+    } else {
+        "(?:^|/)"
+    });
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // "**/" also matches no directory at all
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped glob is a valid regex")
+}
+
+/// Whether a module should be transformed under filename_include and filename_exclude
+fn is_filename_included(filename: &str, include: &[String], exclude: &[String]) -> bool {
+    let filename = filename.replace('\\', "/");
+    let matches = |patterns: &[String]| {
+        patterns
+            .iter()
+            .any(|pattern| compile_filename_glob(pattern).is_match(&filename))
+    };
+    (include.is_empty() || matches(include)) && !matches(exclude)
+}
+
+/// File holding a module's entry in a per-module report directory: `<dir>/<hash>.json`,
+/// named by a hash of the module filename. SWC transforms modules in parallel, so
+/// each one writes its own file instead of merging into a shared one
+fn module_report_path(dir: &str, filename: &str) -> std::path::PathBuf {
+    std::path::Path::new(dir).join(format!("{:08x}.json", fnv1a(filename)))
+}
+
+/// JSON of a module's report entry, keyed by the module filename so that the
+/// files of a report directory merge into one object
+fn module_report<T: Serialize>(filename: &str, entry: &T) -> String {
+    let report: BTreeMap<&str, &T> = [(filename, entry)].into_iter().collect();
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+/// Write a module's entry into a per-module report directory, or remove the module's
+/// file when there is no entry. The file is written under a temporary name and
+/// renamed into place, so readers never see a partial report
+fn write_module_report<T: Serialize>(
+    dir: &str,
+    filename: &str,
+    entry: Option<&T>,
+) -> std::io::Result<()> {
+    let path = module_report_path(dir, filename);
+    let Some(entry) = entry else {
+        return match std::fs::remove_file(&path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        };
+    };
+    std::fs::create_dir_all(dir)?;
+    let temp_path = path.with_extension("json.tmp");
+    std::fs::write(&temp_path, module_report(filename, entry))?;
+    std::fs::rename(&temp_path, &path)
+}
+
+/// Warn about a report that couldn't be written; the transform itself still succeeds
+fn warn_report_write(dir: &str, result: std::io::Result<()>) {
+    if let Err(error) = result {
+        emit_diagnostics(&[Diagnostic::warning(
+            DiagnosticKind::ReportWrite,
+            format!(
+                "[tailwind-expand] Could not write report to {}: {}",
+                dir, error
+            ),
+            Span::default(),
+        )]);
+    }
+}
+
+/// Utility order used by `sort`, following Tailwind's layer/property order:
+/// layout, flexbox & grid, spacing, sizing, typography, backgrounds, borders,
+/// effects, transitions & transforms, interactivity.
+/// A key matches a utility equal to it or starting with `key-`; the longest key wins.
+/// Utilities matching no key are treated as custom classes and sorted first.
+const UTILITY_ORDER: &[(&str, u16)] = &[
+    // Layout
+    ("container", 10),
+    ("sr-only", 11),
+    ("block", 20),
+    ("inline-block", 20),
+    ("inline", 20),
+    ("flex", 20),
+    ("inline-flex", 20),
+    ("grid", 20),
+    ("inline-grid", 20),
+    ("contents", 20),
+    ("hidden", 20),
+    ("static", 30),
+    ("fixed", 30),
+    ("absolute", 30),
+    ("relative", 30),
+    ("sticky", 30),
+    ("inset", 31),
+    ("top", 32),
+    ("right", 32),
+    ("bottom", 32),
+    ("left", 32),
+    ("z", 33),
+    ("overflow", 34),
+    // Flexbox & grid
+    ("flex-row", 40),
+    ("flex-col", 40),
+    ("flex-wrap", 41),
+    ("flex-nowrap", 41),
+    ("flex-1", 42),
+    ("flex-auto", 42),
+    ("flex-initial", 42),
+    ("flex-none", 42),
+    ("grow", 43),
+    ("shrink", 43),
+    ("basis", 43),
+    ("order", 44),
+    ("grid-cols", 45),
+    ("col", 45),
+    ("grid-rows", 46),
+    ("row", 46),
+    ("gap", 47),
+    ("justify", 48),
+    ("content", 48),
+    ("items", 49),
+    ("self", 49),
+    ("place", 49),
+    // Spacing
+    ("m", 50),
+    ("mx", 51),
+    ("my", 51),
+    ("mt", 52),
+    ("mr", 52),
+    ("mb", 52),
+    ("ml", 52),
+    ("space", 53),
+    ("p", 54),
+    ("px", 55),
+    ("py", 55),
+    ("pt", 56),
+    ("pr", 56),
+    ("pb", 56),
+    ("pl", 56),
+    // Sizing
+    ("size", 60),
+    ("w", 61),
+    ("min-w", 62),
+    ("max-w", 63),
+    ("h", 64),
+    ("min-h", 65),
+    ("max-h", 66),
+    // Typography
+    ("font", 70),
+    ("text", 71),
+    ("leading", 72),
+    ("tracking", 73),
+    ("underline", 74),
+    ("line-through", 74),
+    ("no-underline", 74),
+    ("uppercase", 75),
+    ("lowercase", 75),
+    ("capitalize", 75),
+    ("truncate", 76),
+    ("whitespace", 76),
+    // Backgrounds
+    ("bg", 80),
+    ("from", 81),
+    ("via", 81),
+    ("to", 81),
+    // Borders
+    ("rounded", 90),
+    ("border", 91),
+    ("divide", 92),
+    ("outline", 93),
+    ("ring", 94),
+    // Effects
+    ("shadow", 100),
+    ("opacity", 101),
+    // Transitions & transforms
+    ("transition", 110),
+    ("duration", 111),
+    ("ease", 112),
+    ("delay", 113),
+    ("animate", 114),
+    ("scale", 115),
+    ("rotate", 115),
+    ("translate", 115),
+    // Interactivity
+    ("cursor", 120),
+    ("select", 121),
+    ("pointer-events", 122),
+];
+
+/// Variant order used by `sort`: pseudo-classes, then dark mode, then breakpoints.
+/// Unknown variants sort after the known ones.
+const VARIANT_ORDER: &[&str] = &[
+    "first",
+    "last",
+    "odd",
+    "even",
+    "group-hover",
+    "peer-hover",
+    "hover",
+    "focus",
+    "focus-within",
+    "focus-visible",
+    "active",
+    "visited",
+    "disabled",
+    "dark",
+    "sm",
+    "md",
+    "lg",
+    "xl",
+    "2xl",
+    "print",
+];
+
+/// Indices of the colons separating variants, skipping colons inside
+/// arbitrary values like max-[900px], supports-[display:grid] or [@media(min-width:768px)].
+/// Quoted strings inside brackets are opaque, so content-['a:)'] stays one value.
+fn variant_colons(utility: &str) -> impl Iterator<Item = usize> + '_ {
+    let mut depth = 0usize;
+    let mut quote = None;
+    utility
+        .bytes()
+        .enumerate()
+        .filter_map(move |(index, byte)| {
+            match byte {
+                _ if quote == Some(byte) => quote = None,
+                _ if quote.is_some() => {}
+                b'\'' | b'"' if depth > 0 => quote = Some(byte),
+                b'[' | b'(' => depth += 1,
+                b']' | b')' => depth = depth.saturating_sub(1),
+                b':' if depth == 0 => return Some(index),
+                _ => {}
+            }
+            None
+        })
+}
+
+/// Variant segments of a prefix, e.g. "dark:max-[900px]:" -> ["dark", "max-[900px]"]
+fn variant_segments(variant_prefix: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut start = 0;
+    variant_colons(variant_prefix).map(move |colon_idx| {
+        let segment = &variant_prefix[start..colon_idx];
+        start = colon_idx + 1;
+        segment
+    })
+}
+
+/// Index of the colon separating a utility's variant prefix from its base utility
+fn last_variant_colon(utility: &str) -> Option<usize> {
+    variant_colons(utility).last()
+}
+
+/// Whether evaluating an expression more than once can't be observed: literals,
+/// identifiers and member chains, also negated or parenthesized
+fn is_side_effect_free(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) | Expr::Ident(_) | Expr::This(_) => true,
+        Expr::Member(member) => {
+            is_side_effect_free(&member.obj)
+                && match &member.prop {
+                    MemberProp::Computed(computed) => matches!(&*computed.expr, Expr::Lit(_)),
+                    _ => true,
+                }
+        }
+        Expr::Paren(ParenExpr { expr, .. }) => is_side_effect_free(expr),
+        Expr::Unary(unary) if unary.op != UnaryOp::Delete => is_side_effect_free(&unary.arg),
+        _ => false,
+    }
+}
+
+/// Index of the plain (non-namespaced) attribute with this name
+fn attr_index(attrs: &[JSXAttrOrSpread], name: &str) -> Option<usize> {
+    attrs.iter().position(|attr_or_spread| {
+        matches!(
+            attr_or_spread,
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(ident),
+                ..
+            }) if ident.sym == name
+        )
+    })
+}
+
+/// Whether a token is only variants, like "hover:" or "dark:hover:!"
+fn is_variant_only(token: &str) -> bool {
+    last_variant_colon(token)
+        .is_some_and(|colon_idx| token[colon_idx + 1..].trim_start_matches('!').is_empty())
+}
+
+/// Split a class string on whitespace outside brackets and parentheses, so a
+/// variant group like "hover:(bg-red text-white)" stays one token. Brackets
+/// inside quoted arbitrary values (content-[')']) don't count.
+/// Returns None when the brackets are unbalanced.
+fn split_grouped_tokens(class_name: &str) -> Option<Vec<&str>> {
+    let mut tokens = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = None;
+    for (index, c) in class_name.char_indices() {
+        match c {
+            _ if quote == Some(c) => quote = None,
+            _ if quote.is_some() => {}
+            '\'' | '"' if depth > 0 => quote = Some(c),
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.checked_sub(1)?,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    tokens.push(&class_name[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if depth != 0 {
+        return None;
+    }
+    if let Some(start) = start {
+        tokens.push(&class_name[start..]);
+    }
+    Some(tokens)
+}
+
+/// Split a variant group token into its variant prefix and inner class string,
+/// e.g. "dark:hover:(a b)" -> ("dark:hover:", "a b"). Parentheses that don't
+/// directly follow a variant colon, as in bg-[url(a.png)] or fill-(--brand), aren't groups.
+fn split_variant_group(token: &str) -> Option<(&str, &str)> {
+    let inner = token.strip_suffix(')')?;
+    let mut depth = 0usize;
+    for (index, byte) in inner.bytes().enumerate() {
+        match byte {
+            b'(' if depth == 0 && index > 0 && inner.as_bytes()[index - 1] == b':' => {
+                let group = &inner[index + 1..];
+                // The opening paren must match the closing one
+                return split_grouped_tokens(group).map(|_| (&inner[..index], group));
+            }
+            b'[' | b'(' => depth += 1,
+            b']' | b')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Push the tokens of a (balanced) class string with `prefix` applied, recursing into groups
+fn push_ungrouped(class_name: &str, prefix: &str, tokens: &mut Vec<String>, found: &mut bool) {
+    for token in split_grouped_tokens(class_name).unwrap_or_default() {
+        match split_variant_group(token) {
+            Some((variants, group)) => {
+                *found = true;
+                push_ungrouped(group, &format!("{}{}", prefix, variants), tokens, found);
+            }
+            None => tokens.push(format!("{}{}", prefix, token)),
+        }
+    }
+}
+
+/// Expand variant groups into one token per utility,
+/// e.g. "hover:(bg-red text-white) p-2" -> "hover:bg-red hover:text-white p-2".
+/// Groups may nest. Returns None when the string has no groups.
+fn expand_variant_groups(class_name: &str) -> Option<String> {
+    if !class_name.contains(":(") {
+        return None;
+    }
+    split_grouped_tokens(class_name)?;
+    let mut tokens = Vec::new();
+    let mut found = false;
+    push_ungrouped(class_name, "", &mut tokens, &mut found);
+    found.then(|| tokens.join(" "))
+}
+
+/// Expand responsive shorthands in an alias value into one utility per entry,
+/// e.g. "p-{2,md:4,lg:6} flex" -> "p-2 md:p-4 lg:p-6 flex". An entry without
+/// variants is the base utility; entries are separated by commas without spaces.
+/// Returns None when the value has no shorthand.
+fn expand_responsive_shorthands(value: &str) -> Option<String> {
+    if !value.contains('{') {
+        return None;
+    }
+    let mut found = false;
+    let mut utilities = Vec::new();
+    for token in value.split_whitespace() {
+        let shorthand = token
+            .strip_suffix('}')
+            .and_then(|rest| rest.split_once('{'))
+            .filter(|(prefix, entries)| {
+                !prefix.is_empty() && !prefix.contains('[') && !entries.contains('{')
+            });
+        let Some((prefix, entries)) = shorthand else {
+            utilities.push(token.to_string());
+            continue;
+        };
+        found = true;
+        for entry in entries.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let split = last_variant_colon(entry).map_or(0, |colon_idx| colon_idx + 1);
+            let (variants, value) = entry.split_at(split);
+            utilities.push(format!("{}{}{}", variants, prefix, value));
+        }
+    }
+    found.then(|| utilities.join(" "))
+}
+
+/// Position of a utility in UTILITY_ORDER, 0 for custom classes
+fn utility_rank(utility: &str) -> u16 {
+    let base = match last_variant_colon(utility) {
+        Some(colon_idx) => &utility[colon_idx + 1..],
+        None => utility,
+    };
+    let base = base.trim_start_matches(['!', '-']);
+
+    UTILITY_ORDER
+        .iter()
+        .filter(|(key, _)| {
+            base == *key || (base.starts_with(key) && base[key.len()..].starts_with('-'))
+        })
+        .max_by_key(|(key, _)| key.len())
+        .map_or(0, |(_, rank)| *rank)
+}
+
+/// Positions of a utility's variants in VARIANT_ORDER, empty for base utilities
+fn variant_ranks(utility: &str) -> Vec<usize> {
+    let Some(colon_idx) = last_variant_colon(utility) else {
+        return Vec::new();
+    };
+    variant_segments(&utility[..colon_idx + 1])
+        .map(|variant| {
+            VARIANT_ORDER
+                .iter()
+                .position(|known| *known == variant)
+                .unwrap_or(VARIANT_ORDER.len())
+        })
+        .collect()
+}
+
+/// Reorder a utility's known variants into VARIANT_ORDER, e.g.
+/// "dark:hover:bg-red" -> "hover:dark:bg-red". Unknown and arbitrary variants
+/// keep their position and split the known ones into separately sorted runs,
+/// since moving them could change what they select.
+fn sort_variant_segments(utility: &str) -> Cow<'_, str> {
+    let Some(colon_idx) = last_variant_colon(utility) else {
+        return Cow::Borrowed(utility);
+    };
+    let mut segments: Vec<&str> = variant_segments(&utility[..colon_idx + 1]).collect();
+    let rank = |segment: &str| VARIANT_ORDER.iter().position(|known| *known == segment);
+    for run in segments.split_mut(|segment| rank(segment).is_none()) {
+        run.sort_by_key(|segment| rank(segment));
+    }
+    let mut sorted = String::with_capacity(utility.len());
+    for segment in segments {
+        sorted.push_str(segment);
+        sorted.push(':');
+    }
+    sorted.push_str(&utility[colon_idx + 1..]);
+    if sorted == utility {
+        return Cow::Borrowed(utility);
+    }
+    Cow::Owned(sorted)
+}
+
+/// Sort a class list by variant group, then utility order. Stable for equal keys.
+fn sort_utilities(class_name: &str) -> String {
+    let mut utilities: Vec<&str> = class_name.split_whitespace().collect();
+    utilities.sort_by_cached_key(|utility| (variant_ranks(utility), utility_rank(utility)));
+    utilities.join(" ")
+}
+
+/// Stable grouping of utilities by variant prefix: base utilities first, then each
+/// prefix in order of first appearance
+fn group_utilities_by_variant(class_name: &str) -> String {
+    let mut groups: Vec<(&str, Vec<&str>)> = vec![("", Vec::new())];
+    for utility in class_name.split_whitespace() {
+        let prefix = last_variant_colon(utility).map_or("", |colon_idx| &utility[..colon_idx]);
+        match groups.iter_mut().find(|(group, _)| *group == prefix) {
+            Some((_, utilities)) => utilities.push(utility),
+            None => groups.push((prefix, vec![utility])),
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(_, utilities)| utilities)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Join per-token output pieces using the separators between the original tokens.
+/// Non-ASCII whitespace in separators becomes a regular space.
+fn join_preserving_separators(class_name: &str, pieces: &[String]) -> String {
+    let normalize = |separator: &str| -> String {
+        separator
+            .chars()
+            .map(|c| if c.is_ascii() { c } else { ' ' })
+            .collect()
+    };
+
+    let mut output = String::with_capacity(class_name.len());
+    let mut rest = class_name;
+    for piece in pieces {
+        let start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        output.push_str(&normalize(&rest[..start]));
+        output.push_str(piece);
+        rest = &rest[start..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        rest = &rest[end..];
+    }
+    output.push_str(&normalize(rest));
+    output
+}
+
+/// Collapse repeated adjacent variants of a utility, e.g.
+/// "hover:hover:bg-red" -> "hover:bg-red" (non-adjacent repeats are left to Tailwind)
+fn collapse_repeated_variants(utility: &str) -> Cow<'_, str> {
+    let Some(last_colon) = last_variant_colon(utility) else {
+        return Cow::Borrowed(utility);
+    };
+    let segments: Vec<&str> = variant_segments(&utility[..last_colon + 1]).collect();
+    if !segments.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Cow::Borrowed(utility);
+    }
+    let mut collapsed = String::with_capacity(utility.len());
+    for (index, segment) in segments.iter().enumerate() {
+        if index > 0 && segments[index - 1] == *segment {
+            continue;
+        }
+        collapsed.push_str(segment);
+        collapsed.push(':');
+    }
+    collapsed.push_str(&utility[last_colon + 1..]);
+    Cow::Owned(collapsed)
+}
+
+/// Collapse repeated adjacent variants in every utility of a class string
+fn minify_utilities(class_name: &str) -> String {
+    class_name
+        .split_whitespace()
+        .map(collapse_repeated_variants)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Remove repeated classes, keeping the first occurrence of each.
+/// Safelisted classes are never removed.
+fn dedupe_utilities(class_name: &str, safelist: &HashSet<String>) -> String {
+    let mut seen = HashSet::new();
+    class_name
+        .split_whitespace()
+        .filter(|utility| seen.insert(*utility) || safelist.contains(*utility))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Remove the classes listed in `strip`
+fn strip_class_tokens(class_name: &str, strip: &HashSet<String>) -> String {
+    class_name
+        .split_whitespace()
+        .filter(|class| !strip.contains(*class))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Utilities setting the same CSS property, so only one of each group applies.
+/// Only these groups are resolved; anything else (spacing, colors, ...) is kept as-is.
+const CONFLICT_GROUPS: &[(&str, &[&str])] = &[
+    (
+        "display",
+        &[
+            "block",
+            "inline-block",
+            "inline",
+            "flex",
+            "inline-flex",
+            "grid",
+            "inline-grid",
+            "contents",
+            "flow-root",
+            "list-item",
+            "table",
+            "inline-table",
+            "table-row",
+            "table-cell",
+            "hidden",
+        ],
+    ),
+    (
+        "position",
+        &["static", "fixed", "absolute", "relative", "sticky"],
+    ),
+    (
+        "float",
+        &[
+            "float-left",
+            "float-right",
+            "float-start",
+            "float-end",
+            "float-none",
+        ],
+    ),
+];
+
+/// Conflict group of a utility, keyed by its variants and important modifier
+fn conflict_key(utility: &str) -> Option<(&str, bool, &'static str)> {
+    let (variants, base) = match last_variant_colon(utility) {
+        Some(colon_idx) => utility.split_at(colon_idx + 1),
+        None => ("", utility),
+    };
+    let important = base.starts_with('!') || base.ends_with('!');
+    let base = base.trim_matches('!');
+    CONFLICT_GROUPS
+        .iter()
+        .find(|(_, utilities)| utilities.contains(&base))
+        .map(|(group, _)| (variants, important, *group))
+}
+
+/// Drop utilities overridden by a later one of the same conflict group, so the
+/// result is the same however often it runs: "flex p-2 grid" -> "p-2 grid".
+/// Safelisted utilities are never dropped, though they still override earlier ones.
+fn resolve_utility_conflicts(class_name: &str, safelist: &HashSet<String>) -> String {
+    let utilities: Vec<&str> = class_name.split_whitespace().collect();
+    let mut last = HashMap::new();
+    for (index, utility) in utilities.iter().enumerate() {
+        if let Some(key) = conflict_key(utility) {
+            last.insert(key, index);
+        }
+    }
+    utilities
+        .iter()
+        .enumerate()
+        .filter(|(index, utility)| {
+            safelist.contains(**utility)
+                || conflict_key(utility).is_none_or(|key| last[&key] == *index)
+        })
+        .map(|(_, utility)| *utility)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Counts of the class tokens a visitor has processed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpansionStats {
+    /// Tokens seen in class strings
+    pub tokens: usize,
+    /// Tokens that matched an alias
+    pub expanded: usize,
+    /// Alias-shaped tokens with no matching alias (e.g. a misspelled "Buton")
+    pub unknown: usize,
+    /// Alias tokens left unexpanded because max_output_tokens was reached
+    pub capped: usize,
+}
+
+impl std::ops::AddAssign for ExpansionStats {
+    fn add_assign(&mut self, other: Self) {
+        self.tokens += other.tokens;
+        self.expanded += other.expanded;
+        self.unknown += other.unknown;
+        self.capped += other.capped;
+    }
+}
+
+/// An alias token as the expansion pass wrote it out
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExpandedToken {
+    /// The token as written in the class string, e.g. "lg:Button"
+    written: String,
+    /// The token with its variant scope, as listed in data-expand
+    token: String,
+    alias: String,
+    /// Utilities in the output, space-separated
+    utilities: String,
+}
+
+/// Expanded alias tokens in the order they were first expanded, without duplicates.
+/// The set keeps inserts constant-time for class strings with hundreds of aliases.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct AliasTokens {
+    order: Vec<String>,
+    seen: HashSet<String>,
+    /// Every expanded occurrence, when debug_verbose or expansion_map_path needs them
+    expansions: Vec<ExpandedToken>,
+}
+
+impl AliasTokens {
+    fn insert(&mut self, token: String) {
+        if self.seen.insert(token.clone()) {
+            self.order.push(token);
+        }
+    }
+
+    fn insert_expanded(&mut self, expanded: ExpandedToken) {
+        self.insert(expanded.token.clone());
+        self.expansions.push(expanded);
+    }
+
+    /// Insert a token expanded outside a class string (e.g. a class: directive),
+    /// keeping its utilities for debug_verbose
+    fn insert_resolved(&mut self, token: String, utilities: &str, debug_verbose: bool) {
+        if !debug_verbose {
+            self.insert(token);
+            return;
+        }
+        // Same as TailwindExpandVisitor::alias_of
+        let rest = match last_variant_colon(&token) {
+            Some(colon_idx) => &token[colon_idx + 1..],
+            None => &token,
+        };
+        self.insert_expanded(ExpandedToken {
+            written: token.clone(),
+            alias: rest.strip_prefix('!').unwrap_or(rest).to_string(),
+            utilities: utilities.to_string(),
+            token,
+        });
+    }
+
+    /// The utilities a data-expand token was first written out as
+    fn utilities_of(&self, token: &str) -> Option<&str> {
+        self.expansions
+            .iter()
+            .find(|expanded| expanded.token == token)
+            .map(|expanded| expanded.utilities.as_str())
+    }
+
+    fn extend(&mut self, other: AliasTokens) {
+        for token in other.order {
+            self.insert(token);
+        }
+        self.expansions.extend(other.expansions);
+    }
+
+    #[cfg(test)]
+    fn contains(&self, token: &str) -> bool {
+        self.seen.contains(token)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    fn iter(&self) -> std::slice::Iter<'_, String> {
+        self.order.iter()
+    }
+}
+
+impl IntoIterator for AliasTokens {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.order.into_iter()
+    }
+}
+
+/// The main visitor that transforms className attributes
+pub struct TailwindExpandVisitor {
+    aliases: CompiledAliasMap,
+    /// UnoCSS-style shortcuts (pattern, key, utility templates), tried in order after
+    /// literal aliases (empty unless uno_shortcuts is set)
+    shortcuts: Vec<(Regex, String, Vec<String>)>,
+    /// Glob aliases like "Button*" (key, utilities), whose utilities are added to
+    /// every matching name, shortest prefix first
+    globs: Vec<(String, Vec<String>)>,
+    /// Separator of composed aliases (None unless bem_composition is set)
+    bem_delimiter: Option<String>,
+    /// Component alias name -> inline style it adds
+    component_styles: HashMap<String, String>,
+    /// Alias names never expanded
+    ignore: HashSet<String>,
+    /// Normalized alias name -> canonical alias name (empty unless normalize_keys is set)
+    normalized_aliases: HashMap<String, String>,
+    debug: bool,
+    debug_mode: DebugMode,
+    debug_attribute: String,
+    debug_order: DebugOrder,
+    debug_verbose: bool,
+    debug_attribute_position: DebugAttributePosition,
+    debug_exclude: HashSet<String>,
+    /// False in dry_run and extract_components modes, which leave class strings untouched
+    rewrite: bool,
+    extract_components: bool,
+    hash_classes: bool,
+    expand_arrays: bool,
+    keep_alias_class: bool,
+    keep_alias_for: HashSet<String>,
+    assert_fully_expanded: bool,
+    sort: bool,
+    dedupe: bool,
+    expand_first_only: bool,
+    resolve_conflicts: bool,
+    safelist: HashSet<String>,
+    strip_classes: HashSet<String>,
+    group_variants: bool,
+    collapse: bool,
+    important_variants: HashSet<String>,
+    never_dedupe_variants: HashSet<String>,
+    /// Alias values to collapse, keyed by their first utility, longest first
+    collapse_patterns: HashMap<String, Vec<(Vec<String>, String)>>,
+    dedupe_variants: bool,
+    minify: bool,
+    normalize_variant_order: bool,
+    max_output_tokens: Option<usize>,
+    known_variants: Option<HashSet<String>>,
+    lowercase_variants: bool,
+    case_insensitive_variants: bool,
+    alias_opacity: bool,
+    alias_interpolation: bool,
+    fallback: UnknownAliasFallback,
+    /// Marker for alias tokens (empty markers are ignored)
+    alias_prefix: Option<String>,
+    strict_prefix: bool,
+    strict: bool,
+    important_base_only: bool,
+    expand_spread_objects: bool,
+    expand_default_props: bool,
+    expand_const_objects: bool,
+    expand_const_strings: bool,
+    /// Identifiers used as a class attribute value, collected before the walk
+    /// when expand_const_strings is on
+    const_class_names: HashSet<String>,
+    expand_memo_bodies: bool,
+    expand_styled_attrs: bool,
+    whitespace: WhitespacePolicy,
+    rename_attributes: HashMap<String, String>,
+    class_attributes: HashSet<String>,
+    expand_default_params: bool,
+    default_class_attributes: Vec<&'static str>,
+    /// Namespaced class attributes as "namespace:name"
+    namespaced_class_attributes: HashSet<String>,
+    expand_part_attributes: bool,
+    /// Component names whose sole text child is expanded
+    expand_children_of: HashSet<String>,
+    expand_style_maps: HashSet<String>,
+    class_hooks: HashSet<String>,
+    template_tags: HashSet<String>,
+    expand_styled_interpolations: bool,
+    class_factories: HashSet<String>,
+    /// Callee names whose arguments are walked for class strings (empty when expand_cva is off)
+    cva_functions: HashSet<String>,
+    /// Callee names whose object arguments get their className expanded (empty when expand_objects is off)
+    object_functions: HashSet<String>,
+    /// Callee names whose nested objects get their className expanded (empty when
+    /// expand_css_objects is off)
+    css_functions: HashSet<String>,
+    /// Callee names whose second argument is a props object, e.g. _jsx
+    create_element_functions: HashSet<String>,
+    /// Usage counts, only collected when a report was requested or components are extracted
+    usage: Option<UsageReport>,
+    /// Problems found so far, emitted when the transform finishes
+    diagnostics: Vec<Diagnostic>,
+    severity: HashMap<DiagnosticKind, Severity>,
+    deprecated_aliases: HashMap<String, String>,
+    /// Alias tokens and their utilities, if expansion_map_path is set
+    expansions: Option<Vec<Expansion>>,
+    /// Source comments, consulted for disable comments
+    comments: Option<Box<dyn Comments>>,
+    /// Opening elements disabled by a preceding `{/* tailwind-expand-disable-next-line */}` child
+    disabled_elements: HashSet<BytePos>,
+    /// Alias names for the debug comment of each element under DebugMode::Comment,
+    /// keyed by opening element position and added once its children are visited
+    debug_comments: HashMap<BytePos, String>,
+    /// Aliases expanded outside of className string attributes (e.g. in arrays),
+    /// attributed to the JSX element currently being visited
+    tracked_aliases: AliasTokens,
+    /// Memoized expand_class_name results keyed on the variant scope, then the raw
+    /// class string. The visitor is created per module, so the cache is module-scoped.
+    cache: HashMap<String, HashMap<String, (String, AliasTokens, ExpansionStats)>>,
+    variant_scope_attribute: Option<String>,
+    opt_in_attribute: Option<String>,
+    /// Variant prefixes of the enclosing variant_scope_attribute elements, outermost
+    /// first, e.g. ["dark:", "md:"]
+    variant_scopes: Vec<String>,
+    /// Token counts accumulated over every class string this visitor expanded
+    stats: ExpansionStats,
+    /// Canonical names of every alias expanded in the program (None unless
+    /// used_aliases_path or summary_comment is set)
+    used_aliases: Option<BTreeSet<String>>,
+    summary_comment: bool,
+}
+
+impl TailwindExpandVisitor {
+    /// Validate the config, then build the visitor
+    pub fn try_new(config: Config) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Self::new(config))
+    }
+
+    pub fn new(config: Config) -> Self {
+        let mut alias_map = split_alias_keys(config.aliases);
+        let mut component_styles = HashMap::new();
+        for (name, component) in config.component_aliases {
+            if let Some(style) = component.style.filter(|style| !style.trim().is_empty()) {
+                component_styles.insert(name.clone(), style);
+            }
+            alias_map.insert(name, component.class);
+        }
+        for (name, class) in split_alias_keys(config.fallback_aliases) {
+            alias_map.entry(name).or_insert(class);
+        }
+        let mut diagnostics = strip_css_syntax(&mut alias_map);
+        let mut aliases = compile_aliases(alias_map);
+        diagnostics.extend(strip_css_important(&mut aliases, config.convert_important));
+        if !config.theme.is_empty() {
+            for utility in aliases.values_mut().flatten() {
+                if let Some(resolved) = resolve_theme(utility, &config.theme) {
+                    *utility = resolved;
+                }
+            }
+        }
+        for name in &config.important_aliases {
+            if let Some(utilities) = aliases.get_mut(name) {
+                for utility in utilities {
+                    *utility = insert_important(utility);
+                }
+            }
+        }
+        for (old, replacement) in &config.deprecated_aliases {
+            if !aliases.contains_key(old) {
+                if let Some(utilities) = aliases.get(replacement).cloned() {
+                    aliases.insert(old.clone(), utilities);
+                }
+            }
+        }
+        if config.protect_utilities {
+            let mut protected: Vec<String> = aliases
+                .keys()
+                .filter(|name| utility_rank(name) > 0)
+                .cloned()
+                .collect();
+            protected.sort();
+            for name in protected {
+                aliases.remove(&name);
+                diagnostics.push(Diagnostic::warning(
+                    DiagnosticKind::ProtectedUtility,
+                    format!(
+                        "[tailwind-expand] Alias \"{}\" is named like a Tailwind utility and is ignored",
+                        name
+                    ),
+                    Span::default(),
+                ));
+            }
+        } else {
+            let mut shadowing: Vec<&String> = aliases
+                .keys()
+                .filter(|name| UTILITY_ORDER.iter().any(|(key, _)| key == name))
+                .collect();
+            shadowing.sort();
+            for name in shadowing {
+                diagnostics.push(Diagnostic::warning(
+                    DiagnosticKind::ProtectedUtility,
+                    format!(
+                        "[tailwind-expand] Alias \"{}\" replaces the Tailwind utility of the same name; enable protectUtilities to keep the utility",
+                        name
+                    ),
+                    Span::default(),
+                ));
+            }
+        }
+
+        // Sorted so that the first matching shortcut doesn't depend on map order
+        let mut shortcuts = Vec::new();
+        if config.uno_shortcuts {
+            let mut keys: Vec<String> = aliases
+                .keys()
+                .filter(|key| shortcut_pattern(key).is_some())
+                .cloned()
+                .collect();
+            keys.sort();
+            for key in keys {
+                let utilities = aliases.remove(&key).unwrap_or_default();
+                let pattern = shortcut_pattern(&key).and_then(|p| compile_shortcut(p).ok());
+                if let Some(pattern) = pattern {
+                    shortcuts.push((pattern, key, utilities));
+                }
+            }
+        }
+
+        // Shorter prefixes first, so Button* comes before ButtonLg*
+        let mut glob_keys: Vec<String> = aliases
+            .keys()
+            .filter(|key| key.len() > 1 && key.ends_with('*'))
+            .cloned()
+            .collect();
+        glob_keys.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        let globs = glob_keys
+            .into_iter()
+            .map(|key| {
+                let utilities = aliases.remove(&key).unwrap_or_default();
+                (key, utilities)
+            })
+            .collect();
+
+        let mut normalized_aliases = HashMap::new();
+        if config.normalize_keys {
+            let mut names: Vec<&String> = aliases.keys().collect();
+            names.sort();
+            for name in names {
+                normalized_aliases
+                    .entry(normalize_alias_key(name))
+                    .or_insert_with(|| name.clone());
+            }
+        }
+
+        let mut empty_aliases: Vec<&String> = aliases
+            .iter()
+            .filter(|(_, utilities)| utilities.is_empty())
+            .map(|(name, _)| name)
+            .collect();
+        empty_aliases.sort();
+        diagnostics.extend(empty_aliases.into_iter().map(|name| {
+            Diagnostic::warning(
+                DiagnosticKind::EmptyAlias,
+                format!(
+                    "[tailwind-expand] Alias \"{}\" expands to no utilities",
+                    name
+                ),
+                Span::default(),
+            )
+        }));
+
+        let mut collapse_patterns: HashMap<String, Vec<(Vec<String>, String)>> = HashMap::new();
+        if config.collapse {
+            for (name, utilities) in &aliases {
+                if utilities.len() > 1 {
+                    collapse_patterns
+                        .entry(utilities[0].clone())
+                        .or_default()
+                        .push((utilities.clone(), name.clone()));
+                }
+            }
+            for patterns in collapse_patterns.values_mut() {
+                patterns.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.1.cmp(&b.1)));
+            }
+        }
+
+        let mut visitor = Self {
+            aliases,
+            shortcuts,
+            globs,
+            bem_delimiter: config
+                .bem_composition
+                .then(|| config.bem_delimiter.unwrap_or_else(|| "--".to_string()))
+                .filter(|delimiter| !delimiter.is_empty()),
+            component_styles,
+            ignore: config.ignore,
+            normalized_aliases,
+            debug: config.debug || config.dry_run,
+            debug_mode: config.debug_mode,
+            debug_attribute: config
+                .debug_attribute
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "data-expand".to_string()),
+            debug_order: config.debug_order,
+            debug_verbose: config.debug_verbose,
+            debug_attribute_position: config.debug_attribute_position,
+            debug_exclude: config.debug_exclude,
+            rewrite: !config.dry_run && !config.extract_components,
+            extract_components: config.extract_components,
+            hash_classes: config.hash_classes,
+            expand_arrays: config.expand_arrays,
+            keep_alias_class: config.keep_alias_class,
+            keep_alias_for: config.keep_alias_for,
+            assert_fully_expanded: config.assert_fully_expanded,
+            sort: config.sort && !config.cn_friendly,
+            dedupe: config.dedupe || config.cn_friendly,
+            expand_first_only: config.expand_first_only,
+            resolve_conflicts: config.resolve_conflicts && !config.cn_friendly,
+            safelist: config.safelist,
+            strip_classes: config.strip_classes,
+            group_variants: config.group_variants && !config.cn_friendly,
+            collapse: config.collapse,
+            important_variants: config.important_variants,
+            never_dedupe_variants: config.never_dedupe_variants,
+            collapse_patterns,
+            dedupe_variants: config.dedupe_variants.unwrap_or(true),
+            minify: config.minify,
+            normalize_variant_order: config.normalize_variant_order,
+            max_output_tokens: config.max_output_tokens,
+            known_variants: config.known_variants,
+            lowercase_variants: config.lowercase_variants,
+            case_insensitive_variants: config.case_insensitive_variants,
+            alias_opacity: config.alias_opacity,
+            alias_interpolation: config.alias_interpolation,
+            fallback: config.fallback,
+            alias_prefix: config.alias_prefix.filter(|prefix| !prefix.is_empty()),
+            strict_prefix: config.strict_prefix,
+            strict: config.strict,
+            important_base_only: config.important_base_only,
+            expand_spread_objects: config.expand_spread_objects,
+            expand_default_props: config.expand_default_props,
+            expand_const_objects: config.expand_const_objects,
+            expand_const_strings: config.expand_const_strings,
+            const_class_names: HashSet::new(),
+            expand_memo_bodies: config.expand_memo_bodies,
+            expand_styled_attrs: config.expand_styled_attrs,
+            whitespace: config.whitespace,
+            rename_attributes: config.rename_attributes,
+            class_attributes: config.class_attributes.into_iter().collect(),
+            expand_default_params: config.expand_default_params,
+            default_class_attributes: match &config.default_class_attributes {
+                Some(names) => CLASS_ATTRIBUTES
+                    .iter()
+                    .copied()
+                    .filter(|attribute| names.iter().any(|name| name == attribute))
+                    .collect(),
+                None => CLASS_ATTRIBUTES.to_vec(),
+            },
+            namespaced_class_attributes: config.namespaced_class_attributes.into_iter().collect(),
+            expand_part_attributes: config.expand_part_attributes,
+            expand_children_of: config.expand_children_of.into_iter().collect(),
+            expand_style_maps: config.expand_style_maps.into_iter().collect(),
+            class_hooks: config.class_hooks.into_iter().collect(),
+            expand_styled_interpolations: config.expand_styled_interpolations,
+            template_tags: config
+                .template_tags
+                .unwrap_or_else(|| vec!["cx".to_string()])
+                .into_iter()
+                .collect(),
+            class_factories: config.class_factories.into_iter().collect(),
+            cva_functions: if config.expand_cva {
+                config
+                    .cva_functions
+                    .unwrap_or_else(|| vec!["cva".to_string(), "tv".to_string()])
+                    .into_iter()
+                    .collect()
+            } else {
+                HashSet::new()
+            },
+            object_functions: if config.expand_objects {
+                config
+                    .object_functions
+                    .unwrap_or_else(|| vec!["styled".to_string()])
+                    .into_iter()
+                    .collect()
+            } else {
+                HashSet::new()
+            },
+            css_functions: if config.expand_css_objects {
+                config
+                    .css_functions
+                    .unwrap_or_else(|| vec!["css".to_string(), "cx".to_string()])
+                    .into_iter()
+                    .collect()
+            } else {
+                HashSet::new()
+            },
+            create_element_functions: config
+                .create_element_functions
+                .unwrap_or_else(|| {
+                    vec![
+                        "React.createElement".to_string(),
+                        "_jsx".to_string(),
+                        "_jsxs".to_string(),
+                        "h".to_string(),
+                        "createElement".to_string(),
+                    ]
+                })
+                .into_iter()
+                .collect(),
+            usage: (config.report_path.is_some()
+                || config.extract_components
+                || config.hash_classes)
+                .then(UsageReport::default),
+            diagnostics: Vec::new(),
+            severity: config.severity,
+            deprecated_aliases: config.deprecated_aliases,
+            expansions: config.expansion_map_path.is_some().then(Vec::new),
+            comments: None,
+            disabled_elements: HashSet::new(),
+            debug_comments: HashMap::new(),
+            tracked_aliases: AliasTokens::default(),
+            cache: HashMap::new(),
+            variant_scope_attribute: config.variant_scope_attribute,
+            opt_in_attribute: config.opt_in_attribute,
+            variant_scopes: Vec::new(),
+            stats: ExpansionStats::default(),
+            used_aliases: (config.used_aliases_path.is_some() || config.summary_comment)
+                .then(BTreeSet::new),
+            summary_comment: config.summary_comment,
+        };
+        visitor.report(diagnostics);
+        visitor
+    }
+
+    /// Record diagnostics at their configured severity, dropping kinds turned off
+    fn report(&mut self, diagnostics: impl IntoIterator<Item = Diagnostic>) {
+        for mut diagnostic in diagnostics {
+            match self.severity.get(&diagnostic.kind) {
+                Some(Severity::Off) => continue,
+                Some(&severity) => diagnostic.severity = severity,
+                None => {}
+            }
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Expand every alias in a class string, returning the aliases it used.
+    /// The original string is borrowed back when no token is an alias
+    /// and it has no non-ASCII whitespace to normalize.
+    /// Runs in time linear in the string's length (plus n log n when sort is enabled):
+    /// each token is resolved once by hash lookup and the output is built in one pass.
+    fn expand_class_name<'a>(&mut self, class_name: &'a str) -> (Cow<'a, str>, AliasTokens) {
+        // The same string expands differently inside a variant scope
+        let scope = self.variant_scopes.concat();
+        let cached = self
+            .cache
+            .get(&scope)
+            .and_then(|strings| strings.get(class_name));
+        if let Some((expanded, aliases, stats)) = cached {
+            self.stats += *stats;
+            let (expanded, aliases) = (expanded.clone(), aliases.clone());
+            self.record_used_aliases(&aliases);
+            return (Cow::Owned(expanded), aliases);
+        }
+
+        let mut stats = ExpansionStats::default();
+        let (expanded, mut aliases) = self.expand_class_name_uncached(class_name, &mut stats);
+        let expanded = if expanded
+            .split_whitespace()
+            .any(|class| self.strip_classes.contains(class))
+        {
+            for expanded in &mut aliases.expansions {
+                expanded.utilities = strip_class_tokens(&expanded.utilities, &self.strip_classes);
+            }
+            Cow::Owned(strip_class_tokens(&expanded, &self.strip_classes))
+        } else {
+            expanded
+        };
+        self.stats += stats;
+        self.record_used_aliases(&aliases);
+        self.record_hashed_classes(&aliases);
+        if let Cow::Owned(expanded) = &expanded {
+            self.cache.entry(scope).or_default().insert(
+                class_name.to_string(),
+                (expanded.clone(), aliases.clone(), stats),
+            );
+        }
+        (expanded, aliases)
+    }
+
+    fn expand_class_name_uncached<'a>(
+        &self,
+        class_name: &'a str,
+        stats: &mut ExpansionStats,
+    ) -> (Cow<'a, str>, AliasTokens) {
+        if self.collapse {
+            return self.collapse_class_name(class_name, stats);
+        }
+
+        // Expand variant groups first, e.g. "hover:(Button text-white)"
+        if let Some(ungrouped) = expand_variant_groups(class_name) {
+            let (expanded, aliases) = self.expand_class_name_uncached(&ungrouped, stats);
+            if aliases.is_empty() && self.whitespace == WhitespacePolicy::Original {
+                return (Cow::Borrowed(class_name), aliases);
+            }
+            return (Cow::Owned(expanded.into_owned()), aliases);
+        }
+
+        // Only start building the output once the first alias is found.
+        // Holds one piece per input token.
+        let mut result: Option<Vec<String>> = None;
+        let mut expanded_aliases = AliasTokens::default();
+        // Classes in the output so far, and whether max_output_tokens stopped expansion
+        let mut output_tokens = 0;
+        let mut capped = false;
+
+        // Aliases already expanded once, for expand_first_only
+        let mut expanded_names = HashSet::new();
+        // Alias tokens already kept by keep_alias_class, so none is emitted twice
+        let mut kept_tokens = HashSet::new();
+        let scope = self.variant_scopes.concat();
+
+        for (index, token) in class_name.split_whitespace().enumerate() {
+            stats.tokens += 1;
+            let mut outcome = self.try_expand_token(token);
+            // Only aliases take the enclosing variant scope; utilities are left as written
+            let scoped_token =
+                if !scope.is_empty() && matches!(outcome, TokenOutcome::Expanded { .. }) {
+                    let scoped_token = format!("{}{}", scope, token);
+                    outcome = self.try_expand_token(&scoped_token);
+                    Cow::Owned(scoped_token)
+                } else {
+                    Cow::Borrowed(token)
+                };
+            let outcome = match outcome {
+                TokenOutcome::Expanded { alias, .. }
+                    if self.expand_first_only && !expanded_names.insert(alias.clone()) =>
+                {
+                    TokenOutcome::Passthrough
+                }
+                outcome => outcome,
+            };
+            match outcome {
+                TokenOutcome::Expanded { alias, utilities } if !capped => {
+                    let utilities = if self.normalize_variant_order {
+                        utilities
+                            .split(' ')
+                            .map(sort_variant_segments)
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    } else {
+                        utilities
+                    };
+                    let utilities = if self.important_variants.is_empty() {
+                        utilities
+                    } else {
+                        utilities
+                            .split(' ')
+                            .map(|utility| self.apply_important_variants(utility))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    };
+                    let utilities = if self.hash_classes {
+                        hashed_class(&utilities)
+                    } else {
+                        utilities
+                    };
+                    // Glob, shortcut and composed names aren't classes anyone styles
+                    let keep = (self.keep_alias_class || self.keep_alias_for.contains(&alias))
+                        && self.aliases.contains_key(&alias);
+                    let kept = keep && kept_tokens.insert(token);
+                    let piece = if kept {
+                        format!("{} {}", token, utilities)
+                    } else {
+                        utilities
+                    };
+                    let piece_tokens = piece.split(' ').count();
+                    if self
+                        .max_output_tokens
+                        .is_some_and(|max| output_tokens + piece_tokens > max)
+                    {
+                        // Leave this and every later alias unexpanded
+                        capped = true;
+                        stats.capped += 1;
+                        output_tokens += 1;
+                        if let Some(result) = &mut result {
+                            result.push(token.to_string());
+                        }
+                        continue;
+                    }
+                    stats.expanded += 1;
+                    output_tokens += piece_tokens;
+                    // Full token for data-expand (e.g., "lg:Button" not just "Button")
+                    if self.debug_verbose || self.expansions.is_some() {
+                        let utilities = if kept {
+                            &piece[token.len() + 1..]
+                        } else {
+                            piece.as_str()
+                        };
+                        expanded_aliases.insert_expanded(ExpandedToken {
+                            written: token.to_string(),
+                            token: scoped_token.into_owned(),
+                            alias,
+                            utilities: if self.minify {
+                                minify_utilities(utilities)
+                            } else {
+                                utilities.to_string()
+                            },
+                        });
+                    } else {
+                        expanded_aliases.insert(scoped_token.into_owned());
+                    }
+                    result
+                        .get_or_insert_with(|| {
+                            class_name
+                                .split_whitespace()
+                                .take(index)
+                                .map(str::to_string)
+                                .collect()
+                        })
+                        .push(piece);
+                }
+                outcome => {
+                    if matches!(outcome, TokenOutcome::Expanded { .. }) {
+                        stats.capped += 1;
+                    }
+                    if matches!(outcome, TokenOutcome::Unknown { .. }) {
+                        stats.unknown += 1;
+                        if self.fallback == UnknownAliasFallback::Remove {
+                            // Empty pieces are skipped (or keep their spacing) when joining
+                            result
+                                .get_or_insert_with(|| {
+                                    class_name
+                                        .split_whitespace()
+                                        .take(index)
+                                        .map(str::to_string)
+                                        .collect()
+                                })
+                                .push(String::new());
+                            continue;
+                        }
+                    }
+                    output_tokens += 1;
+                    if let Some(result) = &mut result {
+                        result.push(token.to_string());
+                    }
+                }
+            }
+        }
+
+        let Some(mut result) = result else {
+            // Tailwind treats non-breaking and other Unicode spaces as part of a class,
+            // so normalize them to regular spaces even when nothing was expanded
+            let needs_normalizing = self.whitespace != WhitespacePolicy::Original
+                && class_name
+                    .chars()
+                    .any(|c| c.is_whitespace() && !c.is_ascii());
+            if !needs_normalizing {
+                return (Cow::Borrowed(class_name), expanded_aliases);
+            }
+            let tokens: Vec<String> = class_name.split_whitespace().map(str::to_string).collect();
+            let normalized = match self.whitespace {
+                WhitespacePolicy::Preserve => join_preserving_separators(class_name, &tokens),
+                _ => tokens.join(" "),
+            };
+            return (Cow::Owned(normalized), expanded_aliases);
+        };
+
+        if self.minify {
+            // Per piece, so preserved spacing between tokens survives
+            for piece in &mut result {
+                *piece = minify_utilities(piece);
+            }
+        }
+        let mut expanded = match self.whitespace {
+            WhitespacePolicy::Preserve
+                if !self.sort
+                    && !self.dedupe
+                    && !self.resolve_conflicts
+                    && !self.group_variants =>
+            {
+                join_preserving_separators(class_name, &result)
+            }
+            _ => result
+                .iter()
+                .filter(|piece| !piece.is_empty())
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        if self.dedupe {
+            expanded = dedupe_utilities(&expanded, &self.safelist);
+        }
+        if self.resolve_conflicts {
+            expanded = resolve_utility_conflicts(&expanded, &self.safelist);
+        }
+        if self.group_variants {
+            expanded = group_utilities_by_variant(&expanded);
+        }
+        if self.sort {
+            expanded = sort_utilities(&expanded);
+        }
+        (Cow::Owned(expanded), expanded_aliases)
+    }
+
+    /// Make a utility important if one of its variants is in important_variants
+    fn apply_important_variants(&self, utility: &str) -> String {
+        let forced = last_variant_colon(utility).is_some_and(|colon_idx| {
+            variant_segments(&utility[..colon_idx + 1])
+                .any(|variant| self.important_variants.contains(variant))
+        });
+        if forced {
+            insert_important(utility)
+        } else {
+            utility.to_string()
+        }
+    }
+
+    /// Replace runs of utilities matching an alias value with the alias name,
+    /// e.g. "px-4 py-2 flex" -> "Button flex" (collapse mode)
+    fn collapse_class_name<'a>(
+        &self,
+        class_name: &'a str,
+        stats: &mut ExpansionStats,
+    ) -> (Cow<'a, str>, AliasTokens) {
+        let tokens: Vec<&str> = class_name.split_whitespace().collect();
+        stats.tokens += tokens.len();
+        let mut collapsed = Vec::with_capacity(tokens.len());
+        let mut aliases = AliasTokens::default();
+        let mut index = 0;
+        while index < tokens.len() {
+            let rest = &tokens[index..];
+            let matched = self.collapse_patterns.get(rest[0]).and_then(|patterns| {
+                patterns.iter().find(|(utilities, _)| {
+                    utilities.len() <= rest.len()
+                        && utilities
+                            .iter()
+                            .zip(rest)
+                            .all(|(utility, token)| utility == token)
+                })
+            });
+            match matched {
+                Some((utilities, name)) => {
+                    collapsed.push(name.as_str());
+                    aliases.insert(name.clone());
+                    stats.expanded += 1;
+                    index += utilities.len();
+                }
+                None => {
+                    collapsed.push(rest[0]);
+                    index += 1;
+                }
+            }
+        }
+        if aliases.is_empty() {
+            return (Cow::Borrowed(class_name), aliases);
+        }
+        (Cow::Owned(collapsed.join(" ")), aliases)
+    }
+
+    /// Expand a single token (handles variants like lg:ButtonMd, dark:hover:Button)
+    /// Returns (expanded_string, Option<full_token_for_data_expand>)
+    pub fn expand_token(&self, token: &str) -> (String, Option<String>) {
+        match self.resolve_token(token) {
+            Some((utilities, alias_name)) => (utilities, Some(alias_name)),
+            // Not an alias, return as-is
+            None => (token.to_string(), None),
+        }
+    }
+
+    /// Resolve a token against the alias map
+    /// Returns Some((expanded_string, full_token_for_data_expand)) or None when not an alias
+    fn resolve_token(&self, token: &str) -> Option<(String, String)> {
+        match self.try_expand_token(token) {
+            // Return full token for data-expand (e.g., "lg:Button" not just "Button")
+            TokenOutcome::Expanded { utilities, .. } => Some((utilities, token.to_string())),
+            TokenOutcome::Unknown { .. } | TokenOutcome::Passthrough => None,
+        }
+    }
+
+    /// Expand each token of a className string, keeping the per-token breakdown
+    pub fn explain_class_name(&self, class_name: &str) -> Vec<TokenExpansion> {
+        class_name
+            .split_whitespace()
+            .map(|token| match self.try_expand_token(token) {
+                TokenOutcome::Expanded { alias, utilities } => TokenExpansion {
+                    token: token.to_string(),
+                    utilities,
+                    alias: Some(alias),
+                },
+                TokenOutcome::Unknown { .. } | TokenOutcome::Passthrough => TokenExpansion {
+                    token: token.to_string(),
+                    utilities: token.to_string(),
+                    alias: None,
+                },
+            })
+            .collect()
+    }
+
+    /// Look up an alias by name, falling back to composing it from aliases joined by
+    /// bem_delimiter. Returns the canonical alias name with its utilities.
+    fn lookup_alias<'a>(&'a self, name: &'a str) -> Option<(Cow<'a, str>, Cow<'a, [String]>)> {
+        if self.ignore.contains(name) {
+            return None;
+        }
+        if let Some(unprefixed) = self.strip_alias_prefix(name) {
+            return self.lookup_alias(unprefixed);
+        }
+        if let Some((alias, utilities)) = self.lookup_single_alias(name) {
+            return Some((Cow::Borrowed(alias), utilities));
+        }
+
+        // Button--Lg -> utilities of Button, then of Lg
+        let delimiter = self.bem_delimiter.as_deref()?;
+        if !name.contains(delimiter) {
+            return None;
+        }
+        let mut utilities = Vec::new();
+        for segment in name.split(delimiter) {
+            let (_, segment_utilities) = self.lookup_single_alias(segment)?;
+            utilities.extend(segment_utilities.iter().cloned());
+        }
+        Some((Cow::Borrowed(name), Cow::Owned(utilities)))
+    }
+
+    /// Look up a single alias by name, falling back to its normalized spelling and then
+    /// to shortcuts. Matching glob aliases add their utilities in front of the exact
+    /// alias's (or stand in for it). Returns the canonical alias name (or glob or
+    /// shortcut key) with its utilities.
+    fn lookup_single_alias(&self, name: &str) -> Option<(&str, Cow<'_, [String]>)> {
+        let exact = self.lookup_exact_alias(name);
+        let mut globs = self
+            .globs
+            .iter()
+            .filter(|(key, _)| name.starts_with(&key[..key.len() - 1]))
+            .peekable();
+        let Some((first_glob, _)) = globs.peek() else {
+            return exact;
+        };
+
+        let alias = exact
+            .as_ref()
+            .map_or(first_glob.as_str(), |(alias, _)| alias);
+        let mut utilities: Vec<String> = globs
+            .flat_map(|(_, utilities)| utilities.iter().cloned())
+            .collect();
+        if let Some((_, exact_utilities)) = &exact {
+            utilities.extend(exact_utilities.iter().cloned());
+        }
+        Some((alias, Cow::Owned(utilities)))
+    }
+
+    /// Look up an alias by its exact or normalized name, then by shortcut
+    fn lookup_exact_alias(&self, name: &str) -> Option<(&str, Cow<'_, [String]>)> {
+        let entry = match self.aliases.get_key_value(name) {
+            Some(entry) => Some(entry),
+            None if !self.normalized_aliases.is_empty() => self
+                .normalized_aliases
+                .get(&normalize_alias_key(name))
+                .and_then(|canonical| self.aliases.get_key_value(canonical)),
+            None => None,
+        };
+        if let Some((name, utilities)) = entry {
+            return Some((name.as_str(), Cow::Borrowed(utilities.as_slice())));
+        }
+
+        self.shortcuts.iter().find_map(|(pattern, key, templates)| {
+            let captures = pattern.captures(name)?;
+            let utilities = templates
+                .iter()
+                .map(|template| {
+                    let mut utility = String::new();
+                    captures.expand(template, &mut utility);
+                    utility
+                })
+                .collect();
+            Some((key.as_str(), Cow::Owned(utilities)))
+        })
+    }
+
+    /// Lowercase the recognized variants of a prefix when lowercase_variants is enabled,
+    /// e.g. "Hover:Dark:" -> "hover:dark:"
+    fn normalize_variant_case<'a>(&self, prefix: &'a str) -> Cow<'a, str> {
+        if !self.lowercase_variants || !prefix.chars().any(|c| c.is_ascii_uppercase()) {
+            return Cow::Borrowed(prefix);
+        }
+        let is_known = |variant: &str| match &self.known_variants {
+            Some(known_variants) => known_variants.contains(variant),
+            None => VARIANT_ORDER.contains(&variant),
+        };
+        let normalized = variant_segments(prefix)
+            .map(|segment| {
+                let lowercase = segment.to_ascii_lowercase();
+                if is_known(&lowercase) {
+                    lowercase
+                } else {
+                    segment.to_string()
+                }
+            })
+            .fold(String::new(), |mut normalized, segment| {
+                normalized.push_str(&segment);
+                normalized.push(':');
+                normalized
+            });
+        Cow::Owned(normalized)
+    }
+
+    /// Expand a single token, reporting why it was or wasn't expanded.
+    ///
+    /// Overlapping aliases resolve in a fixed order, the first match winning:
+    /// 1. the full token as an alias name (a literal `hover:Button` or `!Button` alias)
+    /// 2. the part after the last variant, with the variants applied (`hover:` + `Button`)
+    /// 3. the token without a leading `!`, with the important modifier applied
+    ///
+    /// A bang in front of the whole variant chain (`!hover:Button`) is moved
+    /// after the variants, so it expands like `hover:!Button` to `hover:!px-4`.
+    pub fn try_expand_token(&self, token: &str) -> TokenOutcome {
+        // Exact keys win, including ones that contain colons (e.g., a literal hover:Button alias)
+        if let Some((alias, expanded)) = self.lookup_alias(token) {
+            return TokenOutcome::Expanded {
+                alias: alias.to_string(),
+                utilities: expanded.join(" "),
+            };
+        }
+
+        // "hover:" or "dark:hover:!" has no utility to expand
+        if is_variant_only(token) {
+            return TokenOutcome::Passthrough;
+        }
+
+        // Check for variant prefix using last colon (e.g., dark:hover:Button -> prefix="dark:hover:", alias="Button")
+        if let Some(colon_idx) = last_variant_colon(token) {
+            let mut prefix = &token[..colon_idx + 1];
+            let mut rest = &token[colon_idx + 1..];
+
+            // Handle important modifier after variant (e.g., lg:!ButtonMd)
+            let mut important = rest.starts_with('!');
+            if important {
+                rest = &rest[1..];
+            }
+            // ...or before the whole variant chain (e.g., !lg:ButtonMd)
+            if let Some(variants) = prefix.strip_prefix('!') {
+                prefix = variants;
+                important = true;
+            }
+
+            // Check if rest is an alias
+            if let Some((alias, expanded)) = self.lookup_alias(rest) {
+                let prefix = self.normalize_variant_case(prefix);
+                let utilities: String = expanded
+                    .iter()
+                    .map(|u| {
+                        let prefixed = if self.dedupe_variants {
+                            apply_variant_prefix_with_case(
+                                &prefix,
+                                u,
+                                self.case_insensitive_variants,
+                                &self.never_dedupe_variants,
+                            )
+                        } else {
+                            format!("{}{}", prefix, u)
+                        };
+                        if important && self.bangs(u) {
+                            insert_important(&prefixed)
+                        } else {
+                            prefixed
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                return TokenOutcome::Expanded {
+                    alias: alias.to_string(),
+                    utilities,
+                };
+            }
+        }
+
+        // Check for important modifier (e.g., !Button)
+        if let Some(rest) = token.strip_prefix('!') {
+            if let Some((alias, expanded)) = self.lookup_alias(rest) {
+                let utilities: String = expanded
+                    .iter()
+                    .map(|u| {
+                        if self.bangs(u) {
+                            insert_important(u)
+                        } else {
+                            u.to_string()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                return TokenOutcome::Expanded {
+                    alias: alias.to_string(),
+                    utilities,
+                };
+            }
+        }
+
+        // [&>li]:{{Button}} -> [&>li]:px-4 [&>li]:py-2
+        if self.alias_interpolation {
+            if let Some(outcome) = self.expand_interpolation(token) {
+                return outcome;
+            }
+        }
+
+        // Button/50 -> Button's utilities with /50 on its colors
+        if self.alias_opacity {
+            if let Some((base, opacity)) = split_alias_opacity(token) {
+                if let TokenOutcome::Expanded { alias, utilities } = self.try_expand_token(base) {
+                    return TokenOutcome::Expanded {
+                        alias,
+                        utilities: with_opacity(&utilities, opacity),
+                    };
+                }
+            }
+        }
+
+        let name = self.alias_of(token);
+        if self.ignore.contains(name) {
+            return TokenOutcome::Passthrough;
+        }
+        // Marked tokens are aliases whatever their shape
+        if self.strip_alias_prefix(name).is_some() {
+            return TokenOutcome::Unknown {
+                name: name.to_string(),
+            };
+        }
+        TokenOutcome::unmatched(name)
+    }
+
+    /// Expand a token with one {{Name}} reference, substituting each of the alias's
+    /// utilities. A variant prefix in front of the reference is deduplicated like
+    /// a regular hover:Button token. None when the token has no reference.
+    fn expand_interpolation(&self, token: &str) -> Option<TokenOutcome> {
+        let (before, rest) = token.split_once("{{")?;
+        let (name, after) = rest.split_once("}}")?;
+        if name.is_empty() || after.contains("{{") {
+            return None;
+        }
+        let Some((alias, expanded)) = self.lookup_alias(name) else {
+            return Some(TokenOutcome::Unknown {
+                name: name.to_string(),
+            });
+        };
+        let utilities = expanded
+            .iter()
+            .map(|u| {
+                if self.dedupe_variants && before.ends_with(':') {
+                    format!("{}{}", apply_variant_prefix(before, u), after)
+                } else {
+                    format!("{}{}{}", before, u, after)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(TokenOutcome::Expanded {
+            alias: alias.to_string(),
+            utilities,
+        })
+    }
+
+    /// Whether an alias utility takes the token's important modifier
+    fn bangs(&self, utility: &str) -> bool {
+        !self.important_base_only || last_variant_colon(utility).is_none()
+    }
+
+    /// Expand a string literal in place, returning the aliases it used
+    fn expand_str(&mut self, str_lit: &mut Str) -> AliasTokens {
+        let val = str_lit.value.as_str();
+        let capped = self.stats.capped;
+        let (expanded, aliases) = self.expand_class_name(val);
+        if self.stats.capped > capped {
+            self.report([Diagnostic::warning(
+                DiagnosticKind::OutputLimit,
+                format!(
+                    "[tailwind-expand] \"{}\" expands to more than {} classes; {} aliases were left unexpanded",
+                    val,
+                    self.max_output_tokens.unwrap_or_default(),
+                    self.stats.capped - capped
+                ),
+                str_lit.span,
+            )]);
+        }
+        self.check_variants(&aliases, str_lit);
+        self.check_deprecated_aliases(&aliases, str_lit);
+        self.check_unknown_aliases(str_lit);
+        if self.strict {
+            self.check_empty_utilities(str_lit);
+        }
+        if self.assert_fully_expanded && !aliases.is_empty() {
+            self.check_fully_expanded(&expanded, str_lit);
+        }
+        if !aliases.is_empty() {
+            self.record_expansions(str_lit, &aliases);
+        }
+        if !self.rewrite {
+            return aliases;
+        }
+        if let Cow::Owned(expanded) = expanded {
+            if expanded != val {
+                str_lit.value = Atom::from(expanded);
+                str_lit.raw = None;
+            }
+        }
+        aliases
+    }
+
+    /// Report tokens of the expanded class string that still match an alias
+    fn check_fully_expanded(&mut self, expanded: &str, str_lit: &Str) {
+        let mut leaked = Vec::new();
+        for token in expanded.split_whitespace() {
+            if let TokenOutcome::Expanded { alias, .. } = self.try_expand_token(token) {
+                let kept = self.keep_alias_class || self.keep_alias_for.contains(&alias);
+                if !kept && !leaked.contains(&alias) {
+                    leaked.push(alias);
+                }
+            }
+        }
+        let diagnostics: Vec<Diagnostic> = leaked
+            .into_iter()
+            .map(|alias| {
+                Diagnostic::warning(
+                    DiagnosticKind::UnexpandedAlias,
+                    format!(
+                        "[tailwind-expand] \"{}\" still contains alias \"{}\" after expansion; check for aliases expanding to each other",
+                        str_lit.value, alias
+                    ),
+                    str_lit.span,
+                )
+            })
+            .collect();
+        self.report(diagnostics);
+    }
+
+    /// The name without alias_prefix, if it carries it
+    fn strip_alias_prefix<'a>(&self, name: &'a str) -> Option<&'a str> {
+        name.strip_prefix(self.alias_prefix.as_deref()?)
+    }
+
+    /// Report alias-shaped tokens that match no alias: errors for marked tokens
+    /// under strict_prefix, warnings for the rest under fallback: Keep
+    fn check_unknown_aliases(&mut self, str_lit: &Str) {
+        let warn = self.fallback == UnknownAliasFallback::Keep;
+        if !warn && !self.strict_prefix {
+            return;
+        }
+
+        let class_name = str_lit.value.as_str();
+        let ungrouped = expand_variant_groups(class_name);
+        let mut diagnostics = Vec::new();
+        for token in ungrouped
+            .as_deref()
+            .unwrap_or(class_name)
+            .split_whitespace()
+        {
+            let TokenOutcome::Unknown { name } = self.try_expand_token(token) else {
+                continue;
+            };
+            let message = format!("[tailwind-expand] Unknown alias \"{}\"", token);
+            let span = token_span(str_lit, token);
+            if self.strict_prefix && self.strip_alias_prefix(&name).is_some() {
+                diagnostics.push(Diagnostic::error(
+                    DiagnosticKind::UnknownAlias,
+                    message,
+                    span,
+                ));
+            } else if warn {
+                diagnostics.push(Diagnostic::warning(
+                    DiagnosticKind::UnknownAlias,
+                    message,
+                    span,
+                ));
+            }
+        }
+        self.report(diagnostics);
+    }
+
+    /// Record each alias token of a class string with the utilities it was written
+    /// out as (expansion_map_path)
+    fn record_expansions(&mut self, str_lit: &Str, tokens: &AliasTokens) {
+        let Some(recorded) = &mut self.expansions else {
+            return;
+        };
+        recorded.extend(tokens.expansions.iter().map(|expanded| {
+            Expansion {
+                token: expanded.written.clone(),
+                alias: expanded.alias.clone(),
+                utilities: expanded
+                    .utilities
                     .split_whitespace()
-                    .map(|u| {
-                        let prefixed = apply_variant_prefix(prefix, u);
-                        if important {
-                            insert_important(&prefixed)
-                        } else {
-                            prefixed
+                    .map(str::to_string)
+                    .collect(),
+                span: token_span(str_lit, &expanded.written),
+            }
+        }));
+    }
+
+    /// Warn about each expanded token whose alias is in deprecated_aliases
+    fn check_deprecated_aliases(&mut self, tokens: &AliasTokens, str_lit: &Str) {
+        if self.deprecated_aliases.is_empty() {
+            return;
+        }
+
+        let mut warnings = Vec::new();
+        for token in tokens.iter() {
+            let TokenOutcome::Expanded { alias, .. } = self.try_expand_token(token) else {
+                continue;
+            };
+            if let Some(replacement) = self.deprecated_aliases.get(&alias) {
+                warnings.push(Diagnostic::warning(
+                    DiagnosticKind::DeprecatedAlias,
+                    format!(
+                        "[tailwind-expand] Alias \"{}\" in \"{}\" is deprecated, use \"{}\" instead",
+                        alias, token, replacement
+                    ),
+                    token_span(str_lit, token),
+                ));
+            }
+        }
+        self.report(warnings);
+    }
+
+    /// Warn about variants in front of expanded aliases that aren't in known_variants
+    fn check_variants(&mut self, tokens: &AliasTokens, str_lit: &Str) {
+        let Some(known_variants) = &self.known_variants else {
+            return;
+        };
+
+        let mut warnings = Vec::new();
+        for token in tokens.iter() {
+            // Skip a bang in front of the variants (e.g., !hover:Button)
+            let variants = token.strip_prefix('!').unwrap_or(token);
+            let Some(colon_idx) = last_variant_colon(variants) else {
+                continue;
+            };
+            for segment in variant_segments(&variants[..colon_idx + 1]) {
+                // Arbitrary variants can't be checked against a list
+                if segment.contains('[') {
+                    continue;
+                }
+                // Named groups and peers, e.g. group-hover/sidebar
+                let variant = segment.split_once('/').map_or(segment, |(name, _)| name);
+                let known = known_variants.contains(variant)
+                    || ((self.lowercase_variants || self.case_insensitive_variants)
+                        && known_variants.contains(&variant.to_ascii_lowercase()));
+                if !known {
+                    warnings.push(Diagnostic::warning(
+                        DiagnosticKind::UnknownVariant,
+                        format!(
+                            "[tailwind-expand] Unknown variant \"{}\" in \"{}\"",
+                            variant, token
+                        ),
+                        token_span(str_lit, token),
+                    ));
+                }
+            }
+        }
+        self.report(warnings);
+    }
+
+    /// Warn about tokens with variants but no utility, e.g. a stray "hover:"
+    fn check_empty_utilities(&mut self, str_lit: &Str) {
+        let warnings: Vec<Diagnostic> = str_lit
+            .value
+            .split_whitespace()
+            .filter(|token| is_variant_only(token))
+            .map(|token| {
+                Diagnostic::warning(
+                    DiagnosticKind::EmptyUtility,
+                    format!(
+                        "[tailwind-expand] \"{}\" has variants but no utility and is left as-is",
+                        token
+                    ),
+                    token_span(str_lit, token),
+                )
+            })
+            .collect();
+        self.report(warnings);
+    }
+
+    /// Warn about a class attribute set to a number or boolean literal, e.g.
+    /// className={0} left over from a `count && "Badge"` refactor
+    fn check_non_string_class(&mut self, attr_span: Span, expr: &Expr) {
+        let (kind, span) = match expr {
+            Expr::Lit(Lit::Num(number)) => ("number", number.span),
+            Expr::Lit(Lit::Bool(boolean)) => ("boolean", boolean.span),
+            _ => return,
+        };
+        let span = if span.is_dummy() { attr_span } else { span };
+        self.report([Diagnostic::warning(
+            DiagnosticKind::NonStringClassName,
+            format!(
+                "[tailwind-expand] Class attribute is a {} literal, not a string, and is left as-is",
+                kind
+            ),
+            span,
+        )]);
+    }
+
+    /// Expand string literals in a class expression, looking through
+    /// parentheses, conditionals (a ? "X" : "Y") and logical operands (cond && "X").
+    /// Aliases are recorded in tracked_aliases.
+    fn expand_class_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Lit(Lit::Str(str_lit)) => {
+                let aliases = self.expand_str(str_lit);
+                self.tracked_aliases.extend(aliases);
+            }
+            Expr::Paren(ParenExpr { expr, .. }) => self.expand_class_expr(expr),
+            Expr::Cond(CondExpr { cons, alt, .. }) => {
+                self.expand_class_expr(cons);
+                self.expand_class_expr(alt);
+            }
+            Expr::Bin(BinExpr {
+                op: BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing,
+                left,
+                right,
+                ..
+            }) => {
+                self.expand_class_expr(left);
+                self.expand_class_expr(right);
+            }
+            Expr::Bin(BinExpr {
+                op: BinaryOp::Add, ..
+            }) => self.expand_concat_operand(expr, false, false),
+            // clsx(["Button", active && "Active"]); with expand_arrays every array
+            // literal is already expanded on its own
+            Expr::Array(ArrayLit { elems, .. }) if !self.expand_arrays => {
+                for element in elems.iter_mut().flatten() {
+                    if element.spread.is_none() {
+                        self.expand_class_expr(&mut element.expr);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Expand the string operands of a `+` concatenation, e.g. "Button " + size.
+    /// `glued_before`/`glued_after` tell whether another operand directly precedes or
+    /// follows this one; a token touching it with no space in between is only part of a
+    /// class built at runtime, so "Button" + suffix is left alone
+    fn expand_concat_operand(&mut self, expr: &mut Expr, glued_before: bool, glued_after: bool) {
+        match expr {
+            Expr::Bin(BinExpr {
+                op: BinaryOp::Add,
+                left,
+                right,
+                ..
+            }) => {
+                self.expand_concat_operand(left, glued_before, true);
+                self.expand_concat_operand(right, true, glued_after);
+            }
+            Expr::Paren(ParenExpr { expr, .. }) => {
+                self.expand_concat_operand(expr, glued_before, glued_after)
+            }
+            Expr::Cond(CondExpr { cons, alt, .. }) => {
+                self.expand_concat_operand(cons, glued_before, glued_after);
+                self.expand_concat_operand(alt, glued_before, glued_after);
+            }
+            Expr::Lit(Lit::Str(str_lit)) => {
+                let value = str_lit.value.to_string();
+                let mut start = 0;
+                let mut end = value.len();
+                if glued_before && !value.starts_with(char::is_whitespace) {
+                    start = value.find(char::is_whitespace).unwrap_or(end);
+                }
+                if glued_after && !value.ends_with(char::is_whitespace) {
+                    end = value
+                        .char_indices()
+                        .rev()
+                        .find(|(_, c)| c.is_whitespace())
+                        .map_or(0, |(index, c)| index + c.len_utf8());
+                }
+                if start >= end {
+                    return;
+                }
+                // The spaces next to the other operands are kept as written
+                let middle = &value[start..end];
+                let core_start = start + (middle.len() - middle.trim_start().len());
+                let core_end = end - (middle.len() - middle.trim_end().len());
+                if core_start >= core_end {
+                    return;
+                }
+
+                let span = if (str_lit.span.hi.0 - str_lit.span.lo.0) as usize == value.len() + 2 {
+                    let lo = str_lit.span.lo + BytePos(core_start as u32);
+                    Span::new(lo, lo + BytePos((core_end - core_start + 2) as u32))
+                } else {
+                    str_lit.span
+                };
+                let mut core = Str {
+                    span,
+                    value: Atom::from(&value[core_start..core_end]),
+                    raw: None,
+                };
+                let aliases = self.expand_str(&mut core);
+                self.tracked_aliases.extend(aliases);
+                if core.value != value[core_start..core_end] {
+                    str_lit.value = Atom::from(format!(
+                        "{}{}{}",
+                        &value[..core_start],
+                        core.value,
+                        &value[core_end..]
+                    ));
+                    str_lit.raw = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Give the visitor access to source comments so disable comments are honored
+    pub fn with_comments(mut self, comments: impl Comments + 'static) -> Self {
+        self.comments = Some(Box::new(comments));
+        self
+    }
+
+    /// Whether a `tailwind-expand-disable-next-line` comment directly precedes `pos`
+    fn is_disabled_at(&self, pos: BytePos) -> bool {
+        let Some(comments) = &self.comments else {
+            return false;
+        };
+        !pos.is_dummy()
+            && comments.get_leading(pos).is_some_and(|leading| {
+                leading
+                    .iter()
+                    .any(|comment| comment.text.trim() == DISABLE_NEXT_LINE_COMMENT)
+            })
+    }
+
+    /// Whether a JSX child is `{/* tailwind-expand-disable-next-line */}`
+    fn is_disable_child(&self, child: &JSXElementChild) -> bool {
+        let (Some(comments), JSXElementChild::JSXExprContainer(container)) =
+            (&self.comments, child)
+        else {
+            return false;
+        };
+        if !matches!(container.expr, JSXExpr::JSXEmptyExpr(_)) || container.span.is_dummy() {
+            return false;
+        }
+
+        // The comment trails the opening brace
+        comments
+            .get_trailing(container.span.lo + BytePos(1))
+            .is_some_and(|trailing| {
+                trailing
+                    .iter()
+                    .any(|comment| comment.text.trim() == DISABLE_NEXT_LINE_COMMENT)
+            })
+    }
+
+    /// Token counts accumulated so far
+    pub fn stats(&self) -> ExpansionStats {
+        self.stats
+    }
+
+    /// Diagnostics collected so far
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Usage counts collected so far, if a report was requested
+    pub fn usage(&self) -> Option<&UsageReport> {
+        self.usage.as_ref()
+    }
+
+    /// Alias tokens expanded so far with their utilities, if expansion_map_path is set
+    pub fn expansions(&self) -> Option<&[Expansion]> {
+        self.expansions.as_deref()
+    }
+
+    /// Names of the aliases expanded so far, if used_aliases_path or summary_comment is set
+    pub fn used_aliases(&self) -> Option<&BTreeSet<String>> {
+        self.used_aliases.as_ref()
+    }
+
+    /// Text of the summary comment, e.g. " tailwind-expand: 12 tokens, 2 aliases: Button, Card "
+    fn summary_text(&self) -> String {
+        let aliases = self.used_aliases.as_ref().map_or(0, BTreeSet::len);
+        let mut text = format!(
+            " tailwind-expand: {} tokens, {} aliases",
+            self.stats.tokens, aliases
+        );
+        if let Some(used) = self.used_aliases.as_ref().filter(|used| !used.is_empty()) {
+            text.push_str(": ");
+            text.push_str(
+                &used
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+        text.push(' ');
+        text
+    }
+
+    /// Record the utilities behind each expanded token's hashed class in the usage report
+    fn record_hashed_classes(&mut self, tokens: &AliasTokens) {
+        if !self.hash_classes {
+            return;
+        }
+        let Some(mut usage) = self.usage.take() else {
+            return;
+        };
+        for token in tokens.iter() {
+            if let TokenOutcome::Expanded { utilities, .. } = self.try_expand_token(token) {
+                usage.classes.insert(hashed_class(&utilities), utilities);
+            }
+        }
+        self.usage = Some(usage);
+    }
+
+    /// Add the canonical names of the aliases behind expanded tokens to used_aliases
+    fn record_used_aliases(&mut self, tokens: &AliasTokens) {
+        let Some(mut used) = self.used_aliases.take() else {
+            return;
+        };
+        for token in tokens.iter() {
+            let name = self.alias_of(token);
+            let canonical = self
+                .lookup_alias(name)
+                .map_or(Cow::Borrowed(name), |(alias, _)| alias);
+            if !used.contains(canonical.as_ref()) {
+                used.insert(canonical.into_owned());
+            }
+        }
+        self.used_aliases = Some(used);
+    }
+
+    /// Alias name referenced by a tracked token (e.g. "lg:!Button" -> "Button")
+    fn alias_of<'a>(&self, token: &'a str) -> &'a str {
+        let rest = match last_variant_colon(token) {
+            Some(colon_idx) => &token[colon_idx + 1..],
+            None => token,
+        };
+        rest.strip_prefix('!').unwrap_or(rest)
+    }
+
+    /// Count one element's expanded aliases towards the usage report
+    fn record_usage(&mut self, tokens: &AliasTokens) {
+        if tokens.is_empty() {
+            return;
+        }
+        let Some(mut usage) = self.usage.take() else {
+            return;
+        };
+
+        usage.elements += 1;
+        let aliases: HashSet<&str> = tokens.iter().map(|token| self.alias_of(token)).collect();
+        for alias in aliases {
+            *usage.aliases.entry(alias.to_string()).or_default() += 1;
+            if self.extract_components {
+                if let Some((name, utilities)) = self.lookup_alias(alias) {
+                    usage
+                        .components
+                        .entry(name.to_string())
+                        .or_insert_with(|| utilities.join(" "));
+                }
+            }
+        }
+        self.usage = Some(usage);
+    }
+
+    /// Expand `class:Alias={cond}` directives into one `class:utility={cond}`
+    /// directive per expanded utility, returning the aliases used
+    fn expand_class_directives(&self, attrs: &mut Vec<JSXAttrOrSpread>) -> AliasTokens {
+        let mut expanded_aliases = AliasTokens::default();
+        let has_directives = attrs.iter().any(|attr| {
+            matches!(attr, JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::JSXNamespacedName(namespaced),
+                ..
+            }) if namespaced.ns.sym == "class")
+        });
+        if !has_directives {
+            return expanded_aliases;
+        }
+
+        let mut result = Vec::with_capacity(attrs.len());
+
+        for attr_or_spread in attrs.drain(..) {
+            if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span,
+                name: JSXAttrName::JSXNamespacedName(namespaced),
+                value,
+            }) = &attr_or_spread
+            {
+                if namespaced.ns.sym == "class" {
+                    if let Some((utilities, alias_name)) =
+                        self.resolve_token(namespaced.name.sym.as_str())
+                    {
+                        expanded_aliases.insert_resolved(
+                            alias_name,
+                            &utilities,
+                            self.debug_verbose,
+                        );
+                        if !self.rewrite {
+                            result.push(attr_or_spread);
+                            continue;
+                        }
+                        for utility in utilities.split_whitespace() {
+                            result.push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+                                span: *span,
+                                name: JSXAttrName::JSXNamespacedName(JSXNamespacedName {
+                                    span: namespaced.span,
+                                    ns: namespaced.ns.clone(),
+                                    name: swc_core::ecma::ast::IdentName {
+                                        span: namespaced.name.span,
+                                        sym: Atom::from(utility),
+                                    },
+                                }),
+                                value: value.clone(),
+                            }));
+                        }
+                        continue;
+                    }
+                }
+            }
+            result.push(attr_or_spread);
+        }
+
+        *attrs = result;
+        expanded_aliases
+    }
+
+    /// Expand a class hook argument: strings as in className expressions, plus
+    /// clsx-style objects whose keys are class names, also inside arrays
+    fn expand_class_hook_arg(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Object(object) => self.expand_class_keys(object),
+            Expr::Array(ArrayLit { elems, .. }) => {
+                for element in elems.iter_mut().flatten() {
+                    if element.spread.is_some() {
+                        continue;
+                    }
+                    match &*element.expr {
+                        Expr::Object(_) | Expr::Array(_) => {
+                            self.expand_class_hook_arg(&mut element.expr)
+                        }
+                        // Already expanded as an array literal
+                        _ if self.expand_arrays => {}
+                        _ => self.expand_class_expr(&mut element.expr),
+                    }
+                }
+            }
+            _ => self.expand_class_expr(expr),
+        }
+    }
+
+    /// Expand alias keys of a clsx-style object into one key per utility, each
+    /// with a copy of the original value: { Button: big } -> { "px-4": big, "py-2": big }.
+    /// Keys whose value could have side effects are left alone with a warning.
+    fn expand_class_keys(&mut self, object: &mut ObjectLit) {
+        let mut props = Vec::with_capacity(object.props.len());
+        for prop_or_spread in object.props.drain(..) {
+            let PropOrSpread::Prop(prop) = &prop_or_spread else {
+                props.push(prop_or_spread);
+                continue;
+            };
+            let Prop::KeyValue(key_value) = &**prop else {
+                props.push(prop_or_spread);
+                continue;
+            };
+            let key = match &key_value.key {
+                PropName::Ident(ident) => Some(ident.sym.as_str()),
+                PropName::Str(str_lit) => Some(str_lit.value.as_str()),
+                _ => None,
+            };
+            let Some((utilities, alias_name)) = key.and_then(|key| self.resolve_token(key)) else {
+                props.push(prop_or_spread);
+                continue;
+            };
+            if !is_side_effect_free(&key_value.value) {
+                self.report([Diagnostic::warning(
+                    DiagnosticKind::ImpureClassValue,
+                    format!(
+                        "[tailwind-expand] \"{}\" wasn't expanded: its value would run once per utility",
+                        alias_name
+                    ),
+                    key_value.key.span(),
+                )]);
+                props.push(prop_or_spread);
+                continue;
+            }
+            self.tracked_aliases
+                .insert_resolved(alias_name, &utilities, self.debug_verbose);
+            if !self.rewrite {
+                props.push(prop_or_spread);
+                continue;
+            }
+            let span = key_value.key.span();
+            for utility in utilities.split_whitespace() {
+                props.push(PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+                    key: PropName::Str(Str {
+                        span,
+                        value: Atom::from(utility),
+                        raw: None,
+                    }),
+                    value: key_value.value.clone(),
+                }))));
+            }
+        }
+        object.props = props;
+    }
+
+    /// Expand string values nested anywhere in arrays and object property values.
+    /// Object keys are never touched.
+    fn expand_nested_class_values(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Array(array) => {
+                for element in array.elems.iter_mut().flatten() {
+                    self.expand_nested_class_values(&mut element.expr);
+                }
+            }
+            Expr::Object(object) => {
+                for prop in &mut object.props {
+                    if let PropOrSpread::Prop(prop) = prop {
+                        if let Prop::KeyValue(key_value) = &mut **prop {
+                            self.expand_nested_class_values(&mut key_value.value);
+                        }
+                    }
+                }
+            }
+            Expr::Paren(ParenExpr { expr, .. }) => self.expand_nested_class_values(expr),
+            _ => self.expand_class_expr(expr),
+        }
+    }
+
+    /// Expand the properties named in `keys` of an object literal, leaving other properties alone
+    fn expand_class_property(&mut self, expr: &mut Expr, keys: &[&str]) {
+        let Expr::Object(object) = expr else {
+            return;
+        };
+        for prop in &mut object.props {
+            if let PropOrSpread::Prop(prop) = prop {
+                if let Prop::KeyValue(key_value) = &mut **prop {
+                    let key = match &key_value.key {
+                        PropName::Ident(ident) => Some(ident.sym.as_str()),
+                        PropName::Str(key) => Some(key.value.as_str()),
+                        _ => None,
+                    };
+                    if key.is_some_and(|key| keys.contains(&key)) {
+                        self.expand_class_expr(&mut key_value.value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Expand the class properties of an element's props: an object literal or the
+    /// object literals merged by Object.assign and similar helpers (see
+    /// PROPS_MERGE_FUNCTIONS), e.g. Object.assign({}, props, { className: "Button" })
+    fn expand_props_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Object(_) => {
+                let keys = self.default_class_attributes.clone();
+                self.expand_class_property(expr, &keys)
+            }
+            Expr::Paren(ParenExpr { expr, .. }) => self.expand_props_expr(expr),
+            Expr::Call(call) => {
+                let is_merge = Self::callee_name(call)
+                    .is_some_and(|name| PROPS_MERGE_FUNCTIONS.contains(&&*name));
+                if is_merge {
+                    for arg in &mut call.args {
+                        if arg.spread.is_none() {
+                            self.expand_props_expr(&mut arg.expr);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Expand the `className` property of an object literal, or of the object
+    /// literals returned by a function, e.g. `() => ({ className: "Button" })` or
+    /// `function () { return { className: "Button" }; }`
+    fn expand_returned_class_property(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Object(_) => self.expand_class_property(expr, &["className"]),
+            Expr::Paren(ParenExpr { expr, .. }) => self.expand_returned_class_property(expr),
+            Expr::Arrow(arrow) => match &mut *arrow.body {
+                BlockStmtOrExpr::Expr(body) => self.expand_returned_class_property(body),
+                BlockStmtOrExpr::BlockStmt(block) => self.expand_returned_class_properties(block),
+            },
+            Expr::Fn(FnExpr { function, .. }) => {
+                if let Some(block) = &mut function.body {
+                    self.expand_returned_class_properties(block);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Expand the `className` property of objects returned by a function body's
+    /// top-level return statements
+    fn expand_returned_class_properties(&mut self, block: &mut BlockStmt) {
+        for stmt in &mut block.stmts {
+            if let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = stmt {
+                self.expand_returned_class_property(arg);
+            }
+        }
+    }
+
+    /// Expand the class strings a callback returns, e.g. `() => "Button"` or
+    /// `function () { return active ? "Button" : "ButtonLg"; }`
+    fn expand_returned_class_string(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Paren(ParenExpr { expr, .. }) => self.expand_returned_class_string(expr),
+            Expr::Arrow(arrow) => match &mut *arrow.body {
+                BlockStmtOrExpr::Expr(body) => self.expand_class_expr(body),
+                BlockStmtOrExpr::BlockStmt(block) => self.expand_returned_class_strings(block),
+            },
+            Expr::Fn(FnExpr { function, .. }) => {
+                if let Some(block) = &mut function.body {
+                    self.expand_returned_class_strings(block);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Expand the class strings of a function body's top-level return statements
+    fn expand_returned_class_strings(&mut self, block: &mut BlockStmt) {
+        for stmt in &mut block.stmts {
+            if let Stmt::Return(ReturnStmt { arg: Some(arg), .. }) = stmt {
+                self.expand_class_expr(arg);
+            }
+        }
+    }
+
+    /// Expand `className` properties of an object literal and of the objects nested
+    /// in its other properties (e.g. under selectors), leaving every other value alone
+    fn expand_nested_class_properties(&mut self, expr: &mut Expr) {
+        let Expr::Object(object) = expr else {
+            return;
+        };
+        for prop in &mut object.props {
+            if let PropOrSpread::Prop(prop) = prop {
+                if let Prop::KeyValue(key_value) = &mut **prop {
+                    let is_class_name = match &key_value.key {
+                        PropName::Ident(ident) => ident.sym == "className",
+                        PropName::Str(key) => key.value == "className",
+                        _ => false,
+                    };
+                    if is_class_name {
+                        self.expand_class_expr(&mut key_value.value);
+                    } else {
+                        self.expand_nested_class_properties(&mut key_value.value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Expand class properties of object literals in a JSX spread,
+    /// looking through parentheses, conditionals and logical operands
+    fn expand_spread_classes(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Object(_) => {
+                let keys = self.default_class_attributes.clone();
+                self.expand_class_property(expr, &keys)
+            }
+            Expr::Paren(ParenExpr { expr, .. }) => self.expand_spread_classes(expr),
+            Expr::Cond(CondExpr { cons, alt, .. }) => {
+                self.expand_spread_classes(cons);
+                self.expand_spread_classes(alt);
+            }
+            Expr::Bin(BinExpr {
+                op: BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing,
+                left,
+                right,
+                ..
+            }) => {
+                self.expand_spread_classes(left);
+                self.expand_spread_classes(right);
+            }
+            _ => {}
+        }
+    }
+
+    /// Name of an identifier or `object.property` callee,
+    /// e.g. "cva" in cva(...) or "React.createElement" in React.createElement(...)
+    fn callee_name(call: &CallExpr) -> Option<Cow<'_, str>> {
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+        Self::expr_name(callee)
+    }
+
+    /// "name" or "obj.prop" of an identifier or member expression
+    fn expr_name(expr: &Expr) -> Option<Cow<'_, str>> {
+        match expr {
+            Expr::Ident(ident) => Some(Cow::Borrowed(ident.sym.as_str())),
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) => match &**obj {
+                Expr::Ident(obj) => Some(Cow::Owned(format!("{}.{}", obj.sym, prop.sym))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether a template tag is a styled-components factory: styled.div, styled(Button)
+    /// or a chain on one of those like styled.div.attrs({...})
+    fn is_styled_tag(tag: &Expr) -> bool {
+        match tag {
+            Expr::Ident(ident) => ident.sym == "styled",
+            Expr::Member(MemberExpr { obj, .. }) => Self::is_styled_tag(obj),
+            Expr::Call(CallExpr {
+                callee: Callee::Expr(callee),
+                ..
+            }) => Self::is_styled_tag(callee),
+            _ => false,
+        }
+    }
+
+    /// Number of leading arguments to skip when call builds classes through a
+    /// class_factories entry: 0 for makeClass(...) and makeClass(...)(...),
+    /// 1 for the thisArg of makeClass.bind(thisArg, ...)
+    fn class_factory_skip(&self, call: &CallExpr) -> Option<usize> {
+        if self.class_factories.is_empty() {
+            return None;
+        }
+        if let Some(name) = Self::callee_name(call) {
+            if self.class_factories.contains(&*name) {
+                return Some(0);
+            }
+            if let Some(factory) = name.strip_suffix(".bind") {
+                if self.class_factories.contains(factory) {
+                    return Some(1);
+                }
+            }
+        }
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+        match &**callee {
+            Expr::Call(inner) => self.class_factory_skip(inner).map(|_| 0),
+            _ => None,
+        }
+    }
+
+    /// Expand the sole child of a component listed in expand_children_of,
+    /// either a text node or a string expression
+    fn expand_class_children(&mut self, element: &mut JSXElement) {
+        let JSXElementName::Ident(name) = &element.opening.name else {
+            return;
+        };
+        if !self.expand_children_of.contains(name.sym.as_str()) {
+            return;
+        }
+
+        let mut children = element.children.iter_mut().filter(|child| {
+            !matches!(child, JSXElementChild::JSXText(text) if text.value.trim().is_empty())
+        });
+        let (Some(child), None) = (children.next(), children.next()) else {
+            return;
+        };
+
+        let aliases = match child {
+            JSXElementChild::JSXText(text) => {
+                let (expanded, aliases) = self.expand_class_name(text.value.as_str());
+                if let Cow::Owned(expanded) = expanded {
+                    if self.rewrite {
+                        text.raw = jsx_text_raw(&expanded);
+                        text.value = Atom::from(expanded);
+                    }
+                }
+                aliases
+            }
+            JSXElementChild::JSXExprContainer(container) => {
+                let JSXExpr::Expr(expr) = &mut container.expr else {
+                    return;
+                };
+                let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+                self.expand_class_expr(expr);
+                std::mem::replace(&mut self.tracked_aliases, outer_aliases)
+            }
+            _ => return,
+        };
+        self.record_usage(&aliases);
+    }
+
+    /// Target name for an attribute listed in rename_attributes
+    fn renamed_attr(&self, attr: &JSXAttr) -> Option<&str> {
+        match &attr.name {
+            JSXAttrName::Ident(ident) => self
+                .rename_attributes
+                .get(ident.sym.as_str())
+                .map(String::as_str),
+            _ => None,
+        }
+    }
+
+    /// Add the inline styles of component aliases used without variants,
+    /// appending to an existing string `style` attribute
+    fn apply_component_styles(
+        &self,
+        attrs: &mut Vec<JSXAttrOrSpread>,
+        tokens: &AliasTokens,
+        span: Span,
+    ) {
+        if self.component_styles.is_empty() {
+            return;
+        }
+
+        let mut styles: Vec<&str> = tokens
+            .iter()
+            .filter(|token| last_variant_colon(token).is_none())
+            .filter_map(|token| self.lookup_alias(token.trim_start_matches('!')))
+            .filter_map(|(alias, _)| self.component_styles.get(alias.as_ref()))
+            .map(|style| style.trim().trim_end_matches(';'))
+            .collect();
+        if styles.is_empty() {
+            return;
+        }
+        styles.sort();
+        styles.dedup();
+        let styles = styles.join("; ");
+
+        let existing = attrs.iter_mut().find_map(|attr| match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                name: JSXAttrName::Ident(ident),
+                value,
+                ..
+            }) if ident.sym == "style" => Some(value),
+            _ => None,
+        });
+        match existing {
+            Some(Some(JSXAttrValue::Lit(Lit::Str(style_lit)))) => {
+                let existing = style_lit.value.trim().trim_end_matches(';');
+                let merged = if existing.is_empty() {
+                    styles
+                } else {
+                    format!("{}; {}", existing, styles)
+                };
+                style_lit.value = Atom::from(merged);
+                style_lit.raw = None;
+            }
+            // Expression styles (e.g. style={{ ... }}) are left alone
+            Some(_) => {}
+            None => attrs.push(JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span,
+                name: JSXAttrName::Ident(swc_core::ecma::ast::IdentName {
+                    span,
+                    sym: Atom::from("style"),
+                }),
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str {
+                    span,
+                    value: Atom::from(styles),
+                    raw: None,
+                }))),
+            })),
+        }
+    }
+
+    /// Move renamed attributes to their target name, merging string values into
+    /// an existing string target attribute instead of duplicating it
+    fn apply_attribute_renames(&self, attrs: &mut Vec<JSXAttrOrSpread>) {
+        if self.rename_attributes.is_empty() {
+            return;
+        }
+
+        let mut index = 0;
+        while index < attrs.len() {
+            let JSXAttrOrSpread::JSXAttr(attr) = &attrs[index] else {
+                index += 1;
+                continue;
+            };
+            let Some(target) = self.renamed_attr(attr).map(Atom::from) else {
+                index += 1;
+                continue;
+            };
+
+            let existing = attrs.iter().position(|other| {
+                matches!(other, JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(ident),
+                    value: Some(JSXAttrValue::Lit(Lit::Str(_))),
+                    ..
+                }) if ident.sym == target)
+            });
+
+            let JSXAttrOrSpread::JSXAttr(attr) = &mut attrs[index] else {
+                unreachable!();
+            };
+            if let (Some(existing), Some(JSXAttrValue::Lit(Lit::Str(renamed)))) =
+                (existing, &attr.value)
+            {
+                let renamed = renamed.value.clone();
+                attrs.remove(index);
+                let existing = if existing > index {
+                    existing - 1
+                } else {
+                    existing
+                };
+                if let JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    value: Some(JSXAttrValue::Lit(Lit::Str(target_lit))),
+                    ..
+                }) = &mut attrs[existing]
+                {
+                    let merged = format!("{} {}", target_lit.value, renamed);
+                    target_lit.value = Atom::from(merged.trim());
+                    target_lit.raw = Some(jsx_attr_raw(&target_lit.value));
+                }
+                continue;
+            }
+
+            if let JSXAttrName::Ident(ident) = &mut attr.name {
+                ident.sym = target;
+            }
+            index += 1;
+        }
+    }
+
+    /// Add the element's pending DebugMode::Comment comment as its first child,
+    /// giving self-closing elements a closing tag
+    fn add_debug_comment(&mut self, element: &mut JSXElement) {
+        let Some(alias_names) = self.debug_comments.remove(&element.opening.span.lo) else {
+            return;
+        };
+        let Some(comments) = self.comments.as_deref() else {
+            return;
+        };
+        // The container's comments are printed from just inside its opening brace
+        let span = Span::new(element.opening.span.lo, element.opening.span.lo);
+        comments.add_trailing(
+            span.lo + BytePos(1),
+            Comment {
+                kind: CommentKind::Block,
+                span: DUMMY_SP,
+                text: format!(" tailwind-expand: {} ", alias_names).into(),
+            },
+        );
+        element.children.insert(
+            0,
+            JSXElementChild::JSXExprContainer(JSXExprContainer {
+                span,
+                expr: JSXExpr::JSXEmptyExpr(JSXEmptyExpr { span }),
+            }),
+        );
+        if element.opening.self_closing {
+            element.opening.self_closing = false;
+            element.closing = Some(JSXClosingElement {
+                span,
+                name: element.opening.name.clone(),
+            });
+        }
+    }
+
+    /// Index for a trailing data-expand attribute: the end of the attributes, or
+    /// before the first spread after the class attribute, so that a later
+    /// {...props} overriding className also overrides its data-expand
+    fn data_expand_index(&self, attrs: &[JSXAttrOrSpread]) -> usize {
+        let class_index = attrs.iter().rposition(|attr_or_spread| {
+            matches!(attr_or_spread, JSXAttrOrSpread::JSXAttr(attr) if self.is_class_attr(attr))
+        });
+        let Some(class_index) = class_index else {
+            return attrs.len();
+        };
+        attrs[class_index..]
+            .iter()
+            .position(|attr_or_spread| matches!(attr_or_spread, JSXAttrOrSpread::SpreadElement(_)))
+            .map_or(attrs.len(), |offset| class_index + offset)
+    }
+
+    /// The variant prefix an element declares through variant_scope_attribute, e.g.
+    /// "dark:" for data-tw-variant="dark". The attribute is removed when rewriting
+    fn take_variant_scope(&self, element: &mut JSXOpeningElement) -> Option<String> {
+        let index = attr_index(&element.attrs, self.variant_scope_attribute.as_deref()?)?;
+        let JSXAttrOrSpread::JSXAttr(JSXAttr {
+            value: Some(JSXAttrValue::Lit(Lit::Str(value))),
+            ..
+        }) = &element.attrs[index]
+        else {
+            return None;
+        };
+        let variant = value.value.trim().trim_end_matches(':');
+        if variant.is_empty() {
+            return None;
+        }
+        let scope = format!("{}:", variant);
+        if self.rewrite {
+            element.attrs.remove(index);
+        }
+        Some(scope)
+    }
+
+    /// Whether an element carries the opt_in_attribute marker, removing it when rewriting
+    fn take_opt_in_marker(&self, element: &mut JSXOpeningElement) -> bool {
+        let Some(attribute) = self.opt_in_attribute.as_deref() else {
+            return true;
+        };
+        let Some(index) = attr_index(&element.attrs, attribute) else {
+            return false;
+        };
+        if self.rewrite {
+            element.attrs.remove(index);
+        }
+        true
+    }
+
+    fn is_class_attr_name(&self, name: &str) -> bool {
+        self.default_class_attributes.contains(&name)
+            || self.class_attributes.contains(name)
+            || (self.expand_part_attributes && name == "part")
+    }
+
+    /// Check if an attribute is a className-like attribute
+    fn is_class_attr(&self, attr: &JSXAttr) -> bool {
+        match &attr.name {
+            JSXAttrName::Ident(ident) => self.is_class_attr_name(ident.sym.as_str()),
+            JSXAttrName::JSXNamespacedName(namespaced) => {
+                !self.namespaced_class_attributes.is_empty()
+                    && self
+                        .namespaced_class_attributes
+                        .contains(&format!("{}:{}", namespaced.ns.sym, namespaced.name.sym))
+            }
+        }
+    }
+}
+
+impl VisitMut for TailwindExpandVisitor {
+    fn visit_mut_jsx_opening_element(&mut self, element: &mut JSXOpeningElement) {
+        // Elements preceded by a disable-next-line comment keep their classes, including
+        // those built by expressions in their attributes, so they aren't visited at all
+        if self.disabled_elements.remove(&element.span.lo) || self.is_disabled_at(element.span.lo) {
+            return;
+        }
+        // Under opt_in_attribute, unmarked elements are left alone the same way
+        if !self.take_opt_in_marker(element) {
+            return;
+        }
+
+        // Visit children first, collecting aliases expanded inside attribute expressions.
+        // Disabled attributes are skipped along with their expressions
+        let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+        element.name.visit_mut_with(self);
+        for attr_or_spread in &mut element.attrs {
+            let disabled = matches!(
+                attr_or_spread,
+                JSXAttrOrSpread::JSXAttr(attr) if self.is_disabled_at(attr.span.lo)
+            );
+            if !disabled {
+                attr_or_spread.visit_mut_with(self);
+            }
+        }
+        element.type_args.visit_mut_with(self);
+        let mut expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+
+        expanded_aliases.extend(self.expand_class_directives(&mut element.attrs));
+
+        if self.expand_spread_objects {
+            let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+            for attr_or_spread in &mut element.attrs {
+                if let JSXAttrOrSpread::SpreadElement(spread) = attr_or_spread {
+                    self.expand_spread_classes(&mut spread.expr);
+                }
+            }
+            expanded_aliases.extend(std::mem::replace(&mut self.tracked_aliases, outer_aliases));
+        }
+
+        // Find and transform className attribute
+        for attr_or_spread in &mut element.attrs {
+            if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
+                if self.is_disabled_at(attr.span.lo) {
+                    continue;
+                }
+                if self.is_class_attr(attr) || self.renamed_attr(attr).is_some() {
+                    let attr_span = attr.span;
+                    // MUI-style classes={{ root: "Button" }} map slots to class strings
+                    let slots =
+                        matches!(&attr.name, JSXAttrName::Ident(ident) if ident.sym == "classes");
+                    match &mut attr.value {
+                        Some(JSXAttrValue::Lit(Lit::Str(str_lit))) => {
+                            let aliases = self.expand_str(str_lit);
+                            if str_lit.raw.is_none() {
+                                str_lit.raw = Some(jsx_attr_raw(&str_lit.value));
+                            }
+                            // Keep source positions for source maps
+                            if !aliases.is_empty() && self.rewrite && str_lit.span.is_dummy() {
+                                str_lit.span = attr_span;
+                            }
+                            expanded_aliases.extend(aliases);
+                        }
+                        // className={props.className || "Button"}, className={big ? "ButtonLg" : "Button"}
+                        Some(JSXAttrValue::JSXExprContainer(container)) => {
+                            if let JSXExpr::Expr(expr) = &mut container.expr {
+                                if self.strict {
+                                    self.check_non_string_class(attr_span, expr);
+                                }
+                                let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+                                if slots {
+                                    self.expand_nested_class_values(expr);
+                                } else {
+                                    self.expand_class_expr(expr);
+                                }
+                                expanded_aliases.extend(std::mem::replace(
+                                    &mut self.tracked_aliases,
+                                    outer_aliases,
+                                ));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if self.rewrite {
+            self.apply_attribute_renames(&mut element.attrs);
+            self.apply_component_styles(&mut element.attrs, &expanded_aliases, element.span);
+        }
+        self.record_usage(&expanded_aliases);
+
+        // Add data-expand attribute if debug mode and aliases were expanded
+        let mut alias_names: Vec<_> = expanded_aliases
+            .iter()
+            .filter(|token| !self.debug_exclude.contains(self.alias_of(token)))
+            .cloned()
+            .collect();
+        if self.debug && self.debug_mode != DebugMode::None && !alias_names.is_empty() {
+            if self.debug_order == DebugOrder::Alphabetical {
+                alias_names.sort();
+            }
+            let debug_value = if self.debug_verbose {
+                alias_names
+                    .iter()
+                    .map(|token| match expanded_aliases.utilities_of(token) {
+                        Some(utilities) => format!("{}={}", token, utilities),
+                        None => token.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            } else {
+                alias_names.join(" ")
+            };
+
+            if self.debug_mode == DebugMode::Comment {
+                if self.comments.is_some() && !element.span.is_dummy() {
+                    self.debug_comments.insert(element.span.lo, debug_value);
+                }
+                return;
+            }
+
+            // Injected nodes point at the element they annotate
+            let span = element.span;
+            let data_expand_attr = JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span,
+                name: JSXAttrName::Ident(swc_core::ecma::ast::IdentName {
+                    span,
+                    sym: Atom::from(self.debug_attribute.as_str()),
+                }),
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str {
+                    span,
+                    value: Atom::from(debug_value),
+                    raw: None,
+                }))),
+            });
+
+            // Other attributes keep their relative order either way
+            match self.debug_attribute_position {
+                DebugAttributePosition::First => element.attrs.insert(0, data_expand_attr),
+                DebugAttributePosition::Last => {
+                    let index = self.data_expand_index(&element.attrs);
+                    element.attrs.insert(index, data_expand_attr);
+                }
+            }
+        }
+    }
+
+    fn visit_mut_jsx_element(&mut self, element: &mut JSXElement) {
+        if self.comments.is_some() {
+            // {/* tailwind-expand-disable-next-line */} disables the next element child
+            let mut disable_next = false;
+            for child in &element.children {
+                match child {
+                    _ if self.is_disable_child(child) => disable_next = true,
+                    JSXElementChild::JSXElement(next) if disable_next => {
+                        self.disabled_elements.insert(next.opening.span.lo);
+                        disable_next = false;
+                    }
+                    JSXElementChild::JSXText(text) if text.value.trim().is_empty() => {}
+                    _ => disable_next = false,
+                }
+            }
+        }
+
+        // The scope applies to the element's descendants, not its own classes
+        let scope = self.take_variant_scope(&mut element.opening);
+        element.opening.visit_mut_with(self);
+        if let Some(scope) = &scope {
+            self.variant_scopes.push(scope.clone());
+        }
+        element.children.visit_mut_with(self);
+        element.closing.visit_mut_with(self);
+        self.expand_class_children(element);
+        if scope.is_some() {
+            self.variant_scopes.pop();
+        }
+        self.add_debug_comment(element);
+    }
+
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.visit_mut_children_with(self);
+
+        let is_cva =
+            Self::callee_name(call).is_some_and(|name| self.cva_functions.contains(&*name));
+        if is_cva {
+            for arg in &mut call.args {
+                if arg.spread.is_none() {
+                    self.expand_nested_class_values(&mut arg.expr);
+                }
+            }
+        }
+
+        let is_styling =
+            Self::callee_name(call).is_some_and(|name| self.object_functions.contains(&*name));
+        if is_styling {
+            for arg in &mut call.args {
+                if arg.spread.is_none() {
+                    self.expand_class_property(&mut arg.expr, &["className"]);
+                }
+            }
+        }
+
+        let is_css =
+            Self::callee_name(call).is_some_and(|name| self.css_functions.contains(&*name));
+        if is_css {
+            for arg in &mut call.args {
+                if arg.spread.is_none() {
+                    self.expand_nested_class_properties(&mut arg.expr);
+                }
+            }
+        }
+
+        let is_class_hook = Self::callee_name(call).is_some_and(|name| {
+            self.class_hooks.contains(&*name) || self.template_tags.contains(&*name)
+        });
+        if is_class_hook {
+            let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+            for arg in &mut call.args {
+                if arg.spread.is_none() {
+                    self.expand_class_hook_arg(&mut arg.expr);
+                }
+            }
+            let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+            self.record_usage(&expanded_aliases);
+        }
+
+        let is_memo_hook = self.expand_memo_bodies
+            && Self::callee_name(call).is_some_and(|name| MEMO_HOOKS.contains(&&*name));
+        if is_memo_hook {
+            if let Some(callback) = call.args.first_mut().filter(|arg| arg.spread.is_none()) {
+                let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+                self.expand_returned_class_string(&mut callback.expr);
+                let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+                self.record_usage(&expanded_aliases);
+            }
+        }
+
+        if let Some(skip) = self.class_factory_skip(call) {
+            let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+            for arg in call.args.iter_mut().skip(skip) {
+                if arg.spread.is_none() {
+                    self.expand_class_expr(&mut arg.expr);
+                }
+            }
+            let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+            self.record_usage(&expanded_aliases);
+        }
+
+        // styled.div.attrs(...), styled(Button).attrs(...)
+        let is_styled_attrs = self.expand_styled_attrs
+            && matches!(
+                &call.callee,
+                Callee::Expr(callee) if matches!(
+                    &**callee,
+                    Expr::Member(MemberExpr { prop: MemberProp::Ident(prop), .. })
+                        if prop.sym == "attrs"
+                )
+            );
+        if is_styled_attrs {
+            for arg in &mut call.args {
+                if arg.spread.is_none() {
+                    self.expand_returned_class_property(&mut arg.expr);
+                }
+            }
+        }
+
+        let is_create_element = Self::callee_name(call)
+            .is_some_and(|name| self.create_element_functions.contains(&*name));
+        if is_create_element {
+            if let Some(props) = call.args.get_mut(1).filter(|arg| arg.spread.is_none()) {
+                let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+                self.expand_props_expr(&mut props.expr);
+                let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+                self.record_usage(&expanded_aliases);
+            }
+        }
+    }
+
+    fn visit_mut_tagged_tpl(&mut self, tagged: &mut TaggedTpl) {
+        tagged.visit_mut_children_with(self);
+
+        if self.expand_styled_interpolations && Self::is_styled_tag(&tagged.tag) {
+            let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+            for expr in &mut tagged.tpl.exprs {
+                self.expand_class_expr(expr);
+            }
+            let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+            self.record_usage(&expanded_aliases);
+            return;
+        }
+
+        // cx`Button`, when cx is in template_tags
+        let is_template_tag =
+            Self::expr_name(&tagged.tag).is_some_and(|name| self.template_tags.contains(&*name));
+        if !is_template_tag || !tagged.tpl.exprs.is_empty() {
+            return;
+        }
+        let [quasi] = tagged.tpl.quasis.as_mut_slice() else {
+            return;
+        };
+        // Escapes would make the source differ from the class string. Line breaks
+        // don't: CRLF and CR are cooked to LF, which split_whitespace drops anyway
+        let Some(cooked) = quasi.cooked.clone() else {
+            return;
+        };
+        if quasi.raw.replace("\r\n", "\n").replace('\r', "\n") != *cooked {
+            return;
+        }
+        let mut str_lit = Str {
+            span: quasi.span,
+            value: cooked.clone(),
+            raw: None,
+        };
+        let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+        let aliases = self.expand_str(&mut str_lit);
+        self.tracked_aliases.extend(aliases);
+        let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+        self.record_usage(&expanded_aliases);
+        if str_lit.value != cooked {
+            quasi.raw = template_raw(&str_lit.value);
+            quasi.cooked = Some(str_lit.value);
+        }
+    }
+
+    fn visit_mut_object_pat(&mut self, pat: &mut ObjectPat) {
+        pat.visit_mut_children_with(self);
+
+        if !self.expand_default_params {
+            return;
+        }
+        let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+        for prop in &mut pat.props {
+            let default = match prop {
+                // { className = "Button" }
+                ObjectPatProp::Assign(assign) if self.is_class_attr_name(&assign.key.sym) => {
+                    assign.value.as_deref_mut()
+                }
+                // { className: cls = "Button" }
+                ObjectPatProp::KeyValue(key_value) => {
+                    let key = match &key_value.key {
+                        PropName::Ident(ident) => Some(ident.sym.as_str()),
+                        PropName::Str(key) => Some(key.value.as_str()),
+                        _ => None,
+                    };
+                    match &mut *key_value.value {
+                        Pat::Assign(assign)
+                            if key.is_some_and(|key| self.is_class_attr_name(key)) =>
+                        {
+                            Some(&mut *assign.right)
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some(default) = default {
+                self.expand_class_expr(default);
+            }
+        }
+        let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+        self.record_usage(&expanded_aliases);
+    }
+
+    fn visit_mut_var_decl(&mut self, decl: &mut VarDecl) {
+        decl.visit_mut_children_with(self);
+
+        // const c = "Button", when c is used as className={c}
+        if decl.kind != VarDeclKind::Const || self.const_class_names.is_empty() {
+            return;
+        }
+        for declarator in &mut decl.decls {
+            let Pat::Ident(binding) = &declarator.name else {
+                continue;
+            };
+            if !self.const_class_names.contains(binding.id.sym.as_str()) {
+                continue;
+            }
+            if let Some(init) = &mut declarator.init {
+                if matches!(&**init, Expr::Lit(Lit::Str(_))) {
+                    let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+                    self.expand_class_expr(init);
+                    let expanded_aliases =
+                        std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+                    self.record_usage(&expanded_aliases);
+                }
+            }
+        }
+    }
+
+    fn visit_mut_var_declarator(&mut self, declarator: &mut VarDeclarator) {
+        declarator.visit_mut_children_with(self);
+
+        // const styles = { primary: "Button" }, when "styles" is in expand_style_maps
+        let Pat::Ident(binding) = &declarator.name else {
+            return;
+        };
+        if !self.expand_style_maps.contains(binding.id.sym.as_str()) {
+            return;
+        }
+        if let Some(init) = &mut declarator.init {
+            if matches!(&**init, Expr::Object(_)) {
+                let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+                self.expand_nested_class_values(init);
+                let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+                self.record_usage(&expanded_aliases);
+            }
+        }
+    }
+
+    fn visit_mut_ts_const_assertion(&mut self, assertion: &mut TsConstAssertion) {
+        assertion.visit_mut_children_with(self);
+
+        if !self.expand_const_objects {
+            return;
+        }
+
+        let mut expr = &mut *assertion.expr;
+        while let Expr::Paren(ParenExpr { expr: inner, .. }) = expr {
+            expr = inner;
+        }
+        if matches!(expr, Expr::Object(_)) {
+            let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+            self.expand_nested_class_values(expr);
+            let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+            self.record_usage(&expanded_aliases);
+        }
+    }
+
+    fn visit_mut_assign_expr(&mut self, assign: &mut AssignExpr) {
+        assign.visit_mut_children_with(self);
+
+        if !self.expand_default_props {
+            return;
+        }
+
+        // Component.defaultProps = { className: "Button" }
+        let is_default_props = matches!(
+            &assign.left,
+            AssignTarget::Simple(SimpleAssignTarget::Member(MemberExpr {
+                prop: MemberProp::Ident(prop),
+                ..
+            })) if prop.sym == "defaultProps"
+        );
+        if is_default_props {
+            let outer_aliases = std::mem::take(&mut self.tracked_aliases);
+            self.expand_class_property(&mut assign.right, &["className"]);
+            let expanded_aliases = std::mem::replace(&mut self.tracked_aliases, outer_aliases);
+            self.record_usage(&expanded_aliases);
+        }
+    }
+
+    fn visit_mut_array_lit(&mut self, array: &mut ArrayLit) {
+        array.visit_mut_children_with(self);
+
+        if !self.expand_arrays {
+            return;
+        }
+
+        for element in array.elems.iter_mut().flatten() {
+            if element.spread.is_none() {
+                self.expand_class_expr(&mut element.expr);
+            }
+        }
+    }
+}
+
+/// diagnostics_path of a config that doesn't deserialize as a whole, so its
+/// error can still be written there
+fn fallback_diagnostics_path(json: &str) -> Option<String> {
+    let config: serde_json::Value = serde_json::from_str(json).ok()?;
+    Some(config.get("diagnosticsPath")?.as_str()?.to_string())
+}
+
+#[plugin_transform]
+pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let filename = metadata.get_context(&TransformPluginMetadataContextKind::Filename);
+    let module_filename = filename.as_deref().unwrap_or_default();
+    let locate = |pos| {
+        let loc = metadata.source_map.lookup_char_pos(pos);
+        (loc.line, loc.col_display + 1)
+    };
+    let report_diagnostics = |diagnostics: &[Diagnostic], diagnostics_path: Option<&str>| {
+        emit_diagnostics(diagnostics);
+        if let Some(dir) = diagnostics_path {
+            let records: Vec<DiagnosticRecord> = diagnostics
+                .iter()
+                .map(|diagnostic| DiagnosticRecord::new(diagnostic, locate))
+                .collect();
+            // Modules without diagnostics only clear what an earlier build wrote
+            let entry = (!records.is_empty()).then_some(&records);
+            warn_report_write(dir, write_module_report(dir, module_filename, entry));
+        }
+    };
+
+    let raw_config = metadata.get_transform_plugin_config();
+    let config = match Config::parse(raw_config.as_deref()) {
+        Ok(config) => config,
+        Err(error) => {
+            report_diagnostics(
+                &[Diagnostic::error(
+                    DiagnosticKind::InvalidConfig,
+                    format!("[tailwind-expand] Invalid config: {}", error),
+                    Span::default(),
+                )],
+                raw_config
+                    .as_deref()
+                    .and_then(fallback_diagnostics_path)
+                    .as_deref(),
+            );
+            return program;
+        }
+    };
+    // An invalid config falls through so try_new reports the error
+    if config.is_no_op() && config.validate().is_ok() {
+        return program;
+    }
+    if let Some(filename) = &filename {
+        if !is_filename_included(filename, &config.filename_include, &config.filename_exclude) {
+            return program;
+        }
+    }
+    let report_path = config.report_path.clone();
+    let used_aliases_path = config.used_aliases_path.clone();
+    let diagnostics_path = config.diagnostics_path.clone();
+    let expansion_map_path = config.expansion_map_path.clone();
+
+    let mut visitor = match TailwindExpandVisitor::try_new(config) {
+        Ok(visitor) => visitor,
+        Err(error) => {
+            report_diagnostics(
+                &[Diagnostic::error(
+                    DiagnosticKind::InvalidConfig,
+                    format!("[tailwind-expand] Invalid config: {}", error),
+                    Span::default(),
+                )],
+                diagnostics_path.as_deref(),
+            );
+            return program;
+        }
+    };
+    if let Some(comments) = metadata.comments {
+        visitor = visitor.with_comments(comments);
+    }
+    let program = transform_program(program, &mut visitor);
+    report_diagnostics(visitor.diagnostics(), diagnostics_path.as_deref());
+
+    if let (Some(dir), Some(expansions)) = (expansion_map_path, visitor.expansions()) {
+        let records: Vec<ExpansionRecord> = expansions
+            .iter()
+            .map(|expansion| ExpansionRecord::new(expansion, locate))
+            .collect();
+        warn_report_write(
+            &dir,
+            write_module_report(&dir, module_filename, Some(&records)),
+        );
+    }
+    if let (Some(dir), Some(usage)) = (report_path, visitor.usage()) {
+        warn_report_write(
+            &dir,
+            write_module_report(&dir, module_filename, Some(usage)),
+        );
+    }
+    if let (Some(dir), Some(used)) = (used_aliases_path, visitor.used_aliases()) {
+        warn_report_write(&dir, write_module_report(&dir, module_filename, Some(used)));
+    }
+
+    program
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_core::common::{BytePos, Span};
+    use swc_core::ecma::ast::{Ident, IdentName, JSXElementName};
+
+    fn create_visitor(aliases: AliasMap, debug: bool) -> TailwindExpandVisitor {
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            debug,
+            ..Default::default()
+        })
+    }
+
+    /// Build `<div className="...">` with the given element and attribute spans
+    fn class_name_element(
+        class_name: &str,
+        element_span: Span,
+        attr_span: Span,
+    ) -> JSXOpeningElement {
+        JSXOpeningElement {
+            name: JSXElementName::Ident(Ident::new_no_ctxt(Atom::from("div"), DUMMY_SP)),
+            span: element_span,
+            attrs: vec![JSXAttrOrSpread::JSXAttr(JSXAttr {
+                span: attr_span,
+                name: JSXAttrName::Ident(IdentName::new(Atom::from("className"), DUMMY_SP)),
+                value: Some(JSXAttrValue::Lit(Lit::Str(Str {
+                    span: Span::new(attr_span.lo + BytePos(10), attr_span.hi),
+                    value: Atom::from(class_name),
+                    raw: None,
+                }))),
+            })],
+            self_closing: true,
+            type_args: None,
+        }
+    }
+
+    fn attr_str(attr: &JSXAttrOrSpread) -> &Str {
+        match attr {
+            JSXAttrOrSpread::JSXAttr(JSXAttr {
+                value: Some(JSXAttrValue::Lit(Lit::Str(str_lit))),
+                ..
+            }) => str_lit,
+            _ => panic!("expected a string attribute"),
+        }
+    }
+
+    #[test]
+    fn test_expand_token_direct_alias() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, alias) = visitor.expand_token("Button");
+        assert_eq!(expanded, "px-4 py-2");
+        assert_eq!(alias, Some("Button".to_string()));
+    }
+
+    #[test]
+    fn test_expand_token_with_variant() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("ButtonMd".to_string(), "h-10 px-4".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, token) = visitor.expand_token("lg:ButtonMd");
+        assert_eq!(expanded, "lg:h-10 lg:px-4");
+        // Returns full token for data-expand
+        assert_eq!(token, Some("lg:ButtonMd".to_string()));
+    }
+
+    #[test]
+    fn test_expand_token_with_important() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, token) = visitor.expand_token("!Button");
+        assert_eq!(expanded, "!px-4 !py-2");
+        // Returns full token for data-expand
+        assert_eq!(token, Some("!Button".to_string()));
+    }
+
+    #[test]
+    fn test_important_with_variant_utilities() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("!ButtonMain");
+        assert_eq!(expanded, "!bg-amber-500 hover:!bg-amber-600");
+    }
+
+    #[test]
+    fn test_expand_class_name() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonMd".to_string(), "h-10".to_string());
+
+        let mut visitor = create_visitor(aliases, false);
+        let (expanded, tokens) = visitor.expand_class_name("Button lg:ButtonMd text-white");
+        assert_eq!(expanded, "px-4 py-2 lg:h-10 text-white");
+        // Returns full tokens for data-expand
+        assert!(tokens.contains("Button"));
+        assert!(tokens.contains("lg:ButtonMd"));
+    }
+
+    #[test]
+    fn test_config_with_aliases() {
+        let config = Config {
+            aliases: [
+                ("Button".to_string(), "px-4 py-2".to_string()),
+                ("ButtonMd".to_string(), "h-10".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            debug: false,
+            ..Default::default()
+        };
+
+        let visitor = TailwindExpandVisitor::new(config);
+        let (expanded, _) = visitor.expand_token("Button");
+        assert_eq!(expanded, "px-4 py-2");
+    }
+
+    #[test]
+    fn test_variant_deduplication_same_variant() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("hover:ButtonMain");
+        assert_eq!(expanded, "hover:bg-amber-500 hover:bg-amber-600");
+    }
+
+    #[test]
+    fn test_variant_deduplication_dark() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonGitHub".to_string(),
+            "text-slate-950 dark:text-white".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("dark:ButtonGitHub");
+        assert_eq!(expanded, "dark:text-slate-950 dark:text-white");
+    }
+
+    #[test]
+    fn test_variant_deduplication_chained() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("dark:hover:ButtonMain");
+        assert_eq!(expanded, "dark:hover:bg-amber-500 dark:hover:bg-amber-600");
+    }
+
+    #[test]
+    fn test_variant_stacking_different_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("dark:ButtonMain");
+        assert_eq!(expanded, "dark:bg-amber-500 dark:hover:bg-amber-600");
+    }
+
+    #[test]
+    fn test_variant_with_important_modifier() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("hover:!ButtonMain");
+        assert_eq!(expanded, "hover:!bg-amber-500 hover:!bg-amber-600");
+    }
+
+    #[test]
+    fn test_important_before_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, token) = visitor.expand_token("!hover:Button");
+        assert_eq!(expanded, "hover:!px-4 hover:!py-2");
+        assert_eq!(token, Some("!hover:Button".to_string()));
+
+        let (expanded, _) = visitor.expand_token("!lg:Button");
+        assert_eq!(expanded, "lg:!px-4 lg:!py-2");
+
+        let (expanded, _) = visitor.expand_token("!dark:hover:ButtonMain");
+        assert_eq!(
+            expanded,
+            "dark:hover:!bg-amber-500 dark:hover:!bg-amber-600"
+        );
+    }
+
+    #[test]
+    fn test_debug_mode_tracks_aliases() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonMd".to_string(), "h-10".to_string());
+
+        let mut visitor = create_visitor(aliases, true);
+        let (expanded, tracked_aliases) = visitor.expand_class_name("Button ButtonMd");
+        assert_eq!(expanded, "px-4 py-2 h-10");
+        assert!(tracked_aliases.contains("Button"));
+        assert!(tracked_aliases.contains("ButtonMd"));
+    }
+
+    #[test]
+    fn test_cached_expansion_matches_uncached() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonMd".to_string(), "h-10".to_string());
+
+        let mut visitor = create_visitor(aliases, true);
+        let first = visitor.expand_class_name("Button lg:ButtonMd");
+        assert!(visitor.cache[""].contains_key("Button lg:ButtonMd"));
+
+        let second = visitor.expand_class_name("Button lg:ButtonMd");
+        assert_eq!(first, second);
+        assert_eq!(second.0, "px-4 py-2 lg:h-10");
+        // Cached path still reports the aliases for data-expand
+        assert!(second.1.contains("Button"));
+        assert!(second.1.contains("lg:ButtonMd"));
+    }
+
+    #[test]
+    fn test_cache_keeps_variant_scopes_apart() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut visitor = create_visitor(aliases, false);
+        visitor.variant_scopes.push("lg:".to_string());
+        assert_eq!(visitor.expand_class_name("Button").0, "lg:px-4 lg:py-2");
+        visitor.variant_scopes.pop();
+        assert_eq!(visitor.expand_class_name("lg: Button").0, "lg: px-4 py-2");
+        assert_eq!(visitor.expand_class_name("Button").0, "px-4 py-2");
+    }
+
+    #[test]
+    fn test_no_alias_class_name_is_borrowed() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut visitor = create_visitor(aliases, false);
+        let (expanded, tokens) = visitor.expand_class_name("flex items-center px-2");
+        assert!(matches!(expanded, Cow::Borrowed(_)));
+        assert_eq!(expanded, "flex items-center px-2");
+        assert!(tokens.is_empty());
+        // Plain strings are not worth caching
+        assert!(visitor.cache.is_empty());
+    }
+
+    #[test]
+    fn test_alias_after_plain_tokens_keeps_order() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_class_name("flex items-center Button text-white");
+        assert!(matches!(expanded, Cow::Owned(_)));
+        assert_eq!(expanded, "flex items-center px-4 py-2 text-white");
+    }
+
+    #[test]
+    fn test_alias_values_are_precompiled() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4  py-2 ".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.aliases.get("Button"),
+            Some(&vec!["px-4".to_string(), "py-2".to_string()])
+        );
+        assert_eq!(visitor.expand_token("Button").0, "px-4 py-2");
+        assert_eq!(visitor.expand_token("lg:Button").0, "lg:px-4 lg:py-2");
+        assert_eq!(visitor.expand_token("!Button").0, "!px-4 !py-2");
+    }
+
+    #[test]
+    fn test_rewritten_class_name_keeps_span() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let element_span = Span::new(BytePos(1), BytePos(30));
+        let attr_span = Span::new(BytePos(6), BytePos(26));
+        let mut element = class_name_element("Button", element_span, attr_span);
+
+        let mut visitor = create_visitor(aliases, true);
+        element.visit_mut_with(&mut visitor);
+
+        let class_name = attr_str(&element.attrs[0]);
+        assert_eq!(class_name.value.as_str(), "px-4 py-2");
+        assert!(!class_name.span.is_dummy());
+        assert_eq!(class_name.span, Span::new(BytePos(16), BytePos(26)));
+
+        let data_expand = attr_str(&element.attrs[1]);
+        assert_eq!(data_expand.value.as_str(), "Button");
+        assert_eq!(data_expand.span, element_span);
+    }
+
+    #[test]
+    fn test_container_query_variant_deduplication() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonResponsive".to_string(),
+            "@container p-2 @md:p-4".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("@md:ButtonResponsive");
+        assert_eq!(expanded, "@md:@container @md:p-2 @md:p-4");
+    }
+
+    #[test]
+    fn test_container_query_variant_stacking() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonResponsive".to_string(),
+            "p-2 @md:p-4 @lg/sidebar:p-6".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_token("dark:@md:ButtonResponsive");
+        assert_eq!(
+            expanded,
+            "dark:@md:p-2 dark:@md:p-4 dark:@md:@lg/sidebar:p-6"
+        );
+        assert_eq!(
+            apply_variant_prefix("@lg/sidebar:", "@lg/sidebar:p-6"),
+            "@lg/sidebar:p-6"
+        );
+    }
+
+    fn str_expr(value: &str) -> Expr {
+        Expr::Lit(Lit::Str(Str {
+            span: DUMMY_SP,
+            value: Atom::from(value),
+            raw: None,
+        }))
+    }
+
+    fn expr_str(expr: &Expr) -> &str {
+        match expr {
+            Expr::Lit(Lit::Str(str_lit)) => str_lit.value.as_str(),
+            _ => panic!("expected a string literal"),
+        }
+    }
+
+    fn array_of(elems: Vec<Expr>) -> ArrayLit {
+        ArrayLit {
+            span: DUMMY_SP,
+            elems: elems
+                .into_iter()
+                .map(|expr| {
+                    Some(swc_core::ecma::ast::ExprOrSpread {
+                        spread: None,
+                        expr: Box::new(expr),
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn array_visitor(expand_arrays: bool) -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            expand_arrays,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_expand_arrays_plain_strings() {
+        let mut array = array_of(vec![str_expr("Button"), str_expr("text-white")]);
+
+        let mut visitor = array_visitor(true);
+        array.visit_mut_with(&mut visitor);
+
+        let elems: Vec<_> = array
+            .elems
+            .iter()
+            .flatten()
+            .map(|e| expr_str(&e.expr))
+            .collect();
+        assert_eq!(elems, vec!["px-4 py-2", "text-white"]);
+        assert!(visitor.tracked_aliases.contains("Button"));
+    }
+
+    #[test]
+    fn test_expand_arrays_conditional_element() {
+        let conditional = Expr::Bin(BinExpr {
+            span: DUMMY_SP,
+            op: BinaryOp::LogicalAnd,
+            left: Box::new(Expr::Ident(Ident::new_no_ctxt(Atom::from("big"), DUMMY_SP))),
+            right: Box::new(str_expr("ButtonLg")),
+        });
+        let mut array = array_of(vec![str_expr("Button"), conditional]);
+
+        let mut visitor = array_visitor(true);
+        array.visit_mut_with(&mut visitor);
+
+        let Expr::Bin(bin) = &*array.elems[1].as_ref().unwrap().expr else {
+            panic!("expected a logical expression");
+        };
+        assert!(matches!(&*bin.left, Expr::Ident(ident) if ident.sym == "big"));
+        assert_eq!(expr_str(&bin.right), "h-12");
+        assert!(visitor.tracked_aliases.contains("ButtonLg"));
+    }
+
+    #[test]
+    fn test_expand_arrays_disabled_by_default() {
+        let mut array = array_of(vec![str_expr("Button")]);
+
+        let mut visitor = array_visitor(false);
+        array.visit_mut_with(&mut visitor);
+
+        assert_eq!(expr_str(&array.elems[0].as_ref().unwrap().expr), "Button");
+    }
+
+    #[test]
+    fn test_expand_arrays_join() {
+        let source = "export const A = ()=><div className={[\"Button\", big && \"ButtonLg\"].join(\" \")}/>;\n";
+        let config = Config {
+            expand_arrays: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "export const A = ()=><div className={[\n        \"px-4 py-2\",\n        big && \"h-12 px-6\"\n    ].join(\" \")}/>;\n"
+        );
+        // Left alone unless expand_arrays is on
+        assert!(transform_jsx(source, source_config(false)).contains("\"ButtonLg\""));
+    }
+
+    fn report_visitor() -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            report_path: Some("usage.json".to_string()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_usage_report_counts_aliases_per_element() {
+        let mut visitor = report_visitor();
+        for class_name in ["Button ButtonLg", "lg:Button !Button", "flex", "ButtonLg"] {
+            let mut element = class_name_element(class_name, DUMMY_SP, DUMMY_SP);
+            element.visit_mut_with(&mut visitor);
+        }
+
+        let usage = visitor.usage().unwrap();
+        assert_eq!(usage.elements, 3);
+        // lg:Button and !Button on the same element count once
+        assert_eq!(usage.aliases.get("Button"), Some(&2));
+        assert_eq!(usage.aliases.get("ButtonLg"), Some(&2));
+    }
+
+    #[test]
+    fn test_usage_report_not_collected_without_path() {
+        let mut visitor = create_visitor(
+            [("Button".to_string(), "px-4".to_string())]
+                .into_iter()
+                .collect(),
+            false,
+        );
+        let mut element = class_name_element("Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+
+        assert!(visitor.usage().is_none());
+    }
+
+    #[test]
+    fn test_module_report_files() {
+        let report = UsageReport {
+            aliases: [("Button".to_string(), 2)].into_iter().collect(),
+            elements: 2,
+            ..Default::default()
+        };
+        let parsed: BTreeMap<String, UsageReport> =
+            serde_json::from_str(&module_report("b.tsx", &report)).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed["b.tsx"], report);
+
+        let dir =
+            std::env::temp_dir().join(format!("tailwind-expand-report-{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+        write_module_report(dir, "a.tsx", Some(&report)).unwrap();
+        write_module_report(dir, "b.tsx", Some(&report)).unwrap();
+        // Each module has its own file, so parallel modules can't overwrite each other
+        let mut files: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        let mut expected = vec![
+            format!("{:08x}.json", fnv1a("a.tsx")),
+            format!("{:08x}.json", fnv1a("b.tsx")),
+        ];
+        expected.sort();
+        assert_eq!(files, expected);
+        let written = std::fs::read_to_string(module_report_path(dir, "a.tsx")).unwrap();
+        assert_eq!(written, module_report("a.tsx", &report));
+
+        // No entry removes the module's file, and a missing file is fine
+        write_module_report::<UsageReport>(dir, "a.tsx", None).unwrap();
+        write_module_report::<UsageReport>(dir, "a.tsx", None).unwrap();
+        assert!(!module_report_path(dir, "a.tsx").exists());
+        assert!(module_report_path(dir, "b.tsx").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_alias_warning() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4".to_string());
+        aliases.insert("Empty".to_string(), "".to_string());
+        aliases.insert("Blank".to_string(), " \n\t".to_string());
+
+        let visitor = create_visitor(aliases, true);
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::EmptyAlias && d.severity == Severity::Warn));
+        assert!(diagnostics[0].message.contains("\"Blank\""));
+        assert!(diagnostics[1].message.contains("\"Empty\""));
+    }
+
+    #[test]
+    fn test_empty_alias_expands_to_nothing_but_is_tracked() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Empty".to_string(), "".to_string());
+
+        let visitor = create_visitor(aliases, true);
+        let (expanded, token) = visitor.expand_token("Empty");
+        assert_eq!(expanded.split_whitespace().count(), 0);
+        assert_eq!(token, Some("Empty".to_string()));
+    }
+
+    fn class_directive(name: &str, condition: &str) -> JSXAttrOrSpread {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            span: DUMMY_SP,
+            name: JSXAttrName::JSXNamespacedName(JSXNamespacedName {
+                span: DUMMY_SP,
+                ns: IdentName::new(Atom::from("class"), DUMMY_SP),
+                name: IdentName::new(Atom::from(name), DUMMY_SP),
+            }),
+            value: Some(JSXAttrValue::JSXExprContainer(
+                swc_core::ecma::ast::JSXExprContainer {
+                    span: DUMMY_SP,
+                    expr: swc_core::ecma::ast::JSXExpr::Expr(Box::new(Expr::Ident(
+                        Ident::new_no_ctxt(Atom::from(condition), DUMMY_SP),
+                    ))),
+                },
+            )),
+        })
+    }
+
+    fn directive_names(element: &JSXOpeningElement) -> Vec<String> {
+        element
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::JSXNamespacedName(namespaced),
+                    ..
+                }) => Some(format!("{}:{}", namespaced.ns.sym, namespaced.name.sym)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_class_directive_single_utility() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Hidden".to_string(), "hidden".to_string());
+
+        let mut element = class_name_element("flex", DUMMY_SP, DUMMY_SP);
+        element.attrs = vec![class_directive("Hidden", "collapsed")];
+
+        let mut visitor = create_visitor(aliases, false);
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(directive_names(&element), vec!["class:hidden"]);
+    }
+
+    #[test]
+    fn test_class_directive_multi_utility() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut element = class_name_element("flex", DUMMY_SP, DUMMY_SP);
+        element.attrs = vec![
+            class_directive("Button", "active"),
+            class_directive("underline", "active"),
+        ];
+
+        let mut visitor = create_visitor(aliases, true);
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            directive_names(&element),
+            vec!["class:px-4", "class:py-2", "class:underline"]
+        );
+        // Each expanded directive keeps the original condition
+        let JSXAttrOrSpread::JSXAttr(second) = &element.attrs[1] else {
+            panic!("expected an attribute");
+        };
+        assert!(matches!(
+            &second.value,
+            Some(JSXAttrValue::JSXExprContainer(container))
+                if matches!(&container.expr, swc_core::ecma::ast::JSXExpr::Expr(expr)
+                    if matches!(&**expr, Expr::Ident(ident) if ident.sym == "active"))
+        ));
+        // Tracked for data-expand
+        assert_eq!(
+            attr_str(element.attrs.last().unwrap()).value.as_str(),
+            "Button"
+        );
+    }
+
+    #[test]
+    fn test_multiline_alias_value() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Card".to_string(),
+            "\n  rounded-lg\n\tp-4\r\n  hover:shadow-md\n".to_string(),
+        );
+
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("Card").0,
+            "rounded-lg p-4 hover:shadow-md"
+        );
+        assert_eq!(
+            visitor.expand_token("md:Card").0,
+            "md:rounded-lg md:p-4 md:hover:shadow-md"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:!Card").0,
+            "hover:!rounded-lg hover:!p-4 hover:!shadow-md"
+        );
+
+        let (expanded, _) = visitor.expand_class_name("Card  flex");
+        assert_eq!(expanded, "rounded-lg p-4 hover:shadow-md flex");
+        assert!(expanded.split(' ').all(|token| !token.is_empty()));
+    }
+
+    #[test]
+    fn test_keep_alias_class() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonMd".to_string(), "h-10".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            keep_alias_class: true,
+            ..Default::default()
+        });
+        let (expanded, _) = visitor.expand_class_name("Button text-white");
+        assert_eq!(expanded, "Button px-4 py-2 text-white");
+        let (expanded, _) = visitor.expand_class_name("flex lg:ButtonMd");
+        assert_eq!(expanded, "flex lg:ButtonMd lg:h-10");
+        let (expanded, _) = visitor.expand_class_name("lg:Button !ButtonMd lg:Button");
+        assert_eq!(
+            expanded,
+            "lg:Button lg:px-4 lg:py-2 !ButtonMd !h-10 lg:px-4 lg:py-2"
+        );
+    }
+
+    #[test]
+    fn test_keep_alias_class_literal_aliases_only() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button*".to_string(), "inline-flex".to_string());
+        aliases.insert("/btn-(\\w+)/".to_string(), "bg-$1-500".to_string());
+        aliases.insert("Card".to_string(), "p-4".to_string());
+        aliases.insert("Lg".to_string(), "text-lg".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            keep_alias_class: true,
+            uno_shortcuts: true,
+            bem_composition: true,
+            ..Default::default()
+        });
+        let (expanded, _) = visitor.expand_class_name("ButtonSm btn-blue Card--Lg Card");
+        assert_eq!(expanded, "inline-flex bg-blue-500 p-4 text-lg Card p-4");
+    }
+
+    #[test]
+    fn test_keep_alias_for() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("Card".to_string(), "p-4 rounded".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            keep_alias_for: ["Card".to_string()].into_iter().collect(),
+            ..Default::default()
+        });
+        let (expanded, _) = visitor.expand_class_name("Card Button md:Card");
+        assert_eq!(
+            expanded,
+            "Card p-4 rounded px-4 py-2 md:Card md:p-4 md:rounded"
+        );
+        let (expanded, _) = visitor.expand_class_name("Card text-white Card");
+        assert_eq!(expanded, "Card p-4 rounded text-white p-4 rounded");
+    }
+
+    #[test]
+    fn test_sort_utilities_order() {
+        assert_eq!(
+            sort_utilities("text-white hover:bg-blue-600 px-4 lg:p-6 flex card bg-blue-500 items-center dark:hover:bg-blue-700 dark:text-black"),
+            "card flex items-center px-4 text-white bg-blue-500 hover:bg-blue-600 dark:text-black dark:hover:bg-blue-700 lg:p-6"
+        );
+    }
+
+    #[test]
+    fn test_sort_utility_rank_prefix_matching() {
+        assert!(utility_rank("flex") < utility_rank("flex-col"));
+        assert!(utility_rank("-mt-4") < utility_rank("p-4"));
+        assert_eq!(utility_rank("!px-4"), utility_rank("px-2"));
+        assert_eq!(utility_rank("min-w-0"), 62);
+        assert_eq!(utility_rank("Button"), 0);
+    }
+
+    #[test]
+    fn test_variant_ranks_arbitrary_variants() {
+        let unknown = VARIANT_ORDER.len();
+        let hover = VARIANT_ORDER.iter().position(|v| *v == "hover").unwrap();
+        assert_eq!(variant_ranks("px-4"), Vec::<usize>::new());
+        assert_eq!(variant_ranks("data-[state=open]:px-4"), vec![unknown]);
+        assert_eq!(variant_ranks("[&:hover]:px-4"), vec![unknown]);
+        assert_eq!(
+            variant_ranks("supports-[display:grid]:hover:grid"),
+            vec![unknown, hover]
+        );
+        assert_eq!(
+            sort_utilities("[&:hover]:underline hover:bg-red-500 px-4"),
+            "px-4 hover:bg-red-500 [&:hover]:underline"
+        );
+    }
+
+    #[test]
+    fn test_sort_option_reorders_expanded_class_names() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Button".to_string(),
+            "hover:bg-amber-600 bg-amber-500 px-4 inline-flex".to_string(),
+        );
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            sort: true,
+            ..Default::default()
+        });
+        let (expanded, _) = visitor.expand_class_name("text-sm Button");
+        assert_eq!(
+            expanded,
+            "inline-flex px-4 text-sm bg-amber-500 hover:bg-amber-600"
+        );
+        // Strings without aliases are left alone
+        let (expanded, _) = visitor.expand_class_name("text-sm flex");
+        assert_eq!(expanded, "text-sm flex");
+    }
+
+    fn string_attr(name: &str, value: &str) -> JSXAttrOrSpread {
+        JSXAttrOrSpread::JSXAttr(JSXAttr {
+            span: DUMMY_SP,
+            name: JSXAttrName::Ident(IdentName::new(Atom::from(name), DUMMY_SP)),
+            value: Some(JSXAttrValue::Lit(Lit::Str(Str {
+                span: DUMMY_SP,
+                value: Atom::from(value),
+                raw: None,
+            }))),
+        })
+    }
+
+    fn attr_pairs(element: &JSXOpeningElement) -> Vec<(String, String)> {
+        element
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(JSXAttr {
+                    name: JSXAttrName::Ident(ident),
+                    value: Some(JSXAttrValue::Lit(Lit::Str(str_lit))),
+                    ..
+                }) => Some((ident.sym.to_string(), str_lit.value.to_string())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn rename_visitor(debug: bool) -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            debug,
+            rename_attributes: [("twClass".to_string(), "className".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_rename_attribute_expands_into_target() {
+        let mut element = class_name_element("", DUMMY_SP, DUMMY_SP);
+        element.attrs = vec![
+            string_attr("id", "save"),
+            string_attr("twClass", "Button ButtonLg"),
+        ];
+
+        let mut visitor = rename_visitor(false);
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                ("id".to_string(), "save".to_string()),
+                ("className".to_string(), "px-4 py-2 h-12".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rename_attribute_merges_into_existing_target_with_debug() {
+        let mut element = class_name_element("", DUMMY_SP, DUMMY_SP);
+        element.attrs = vec![
+            string_attr("twClass", "Button"),
+            string_attr("className", "ButtonLg text-white"),
+        ];
+
+        let mut visitor = rename_visitor(true);
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                (
+                    "className".to_string(),
+                    "h-12 text-white px-4 py-2".to_string()
+                ),
+                ("data-expand".to_string(), "Button ButtonLg".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_important_is_idempotent() {
+        assert_eq!(insert_important("!px-4"), "!px-4");
+        assert_eq!(insert_important("hover:!px-4"), "hover:!px-4");
+        assert_eq!(insert_important("px-4!"), "px-4!");
+        assert_eq!(insert_important(&insert_important("lg:px-4")), "lg:!px-4");
+    }
+
+    #[test]
+    fn test_important_alias_with_important_utilities() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "!px-4 py-2".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(visitor.expand_token("!Button").0, "!px-4 !py-2");
+        assert_eq!(
+            visitor.expand_token("hover:Button").0,
+            "hover:!px-4 hover:py-2"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:!Button").0,
+            "hover:!px-4 hover:!py-2"
+        );
+    }
+
+    #[test]
+    fn test_try_expand_token_outcomes() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.try_expand_token("lg:!Button"),
+            TokenOutcome::Expanded {
+                alias: "Button".to_string(),
+                utilities: "lg:!px-4 lg:!py-2".to_string(),
+            }
+        );
+        assert_eq!(
+            visitor.try_expand_token("Buton"),
+            TokenOutcome::Unknown {
+                name: "Buton".to_string()
+            }
+        );
+        assert_eq!(
+            visitor.try_expand_token("hover:!Buton"),
+            TokenOutcome::Unknown {
+                name: "Buton".to_string()
+            }
+        );
+        assert_eq!(visitor.try_expand_token("px-4"), TokenOutcome::Passthrough);
+        assert_eq!(
+            visitor.try_expand_token("hover:bg-red-500"),
+            TokenOutcome::Passthrough
+        );
+        assert_eq!(visitor.try_expand_token("!mt-2"), TokenOutcome::Passthrough);
+    }
+
+    #[test]
+    fn test_expand_token_matches_try_expand_token() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(visitor.expand_token("Buton"), ("Buton".to_string(), None));
+        assert_eq!(
+            visitor.expand_token("dark:Button"),
+            (
+                "dark:px-4 dark:py-2".to_string(),
+                Some("dark:Button".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_important_negative_utilities() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "NegativeAlias".to_string(),
+            "-mt-4 -translate-x-1/2".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("NegativeAlias").0,
+            "-mt-4 -translate-x-1/2"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:NegativeAlias").0,
+            "lg:-mt-4 lg:-translate-x-1/2"
+        );
+        assert_eq!(
+            visitor.expand_token("!NegativeAlias").0,
+            "!-mt-4 !-translate-x-1/2"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:!NegativeAlias").0,
+            "lg:!-mt-4 lg:!-translate-x-1/2"
+        );
+        assert_eq!(insert_important("-!mt-4"), "!-mt-4");
+        assert_eq!(insert_important("hover:-!mt-4"), "hover:!-mt-4");
+    }
+
+    fn cva_call(callee: &str, args: Vec<Expr>) -> CallExpr {
+        CallExpr {
+            span: DUMMY_SP,
+            ctxt: Default::default(),
+            callee: Callee::Expr(Box::new(Expr::Ident(Ident::new_no_ctxt(
+                Atom::from(callee),
+                DUMMY_SP,
+            )))),
+            args: args
+                .into_iter()
+                .map(|expr| swc_core::ecma::ast::ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(expr),
+                })
+                .collect(),
+            type_args: None,
+        }
+    }
+
+    fn object_of(props: Vec<(&str, Expr)>) -> Expr {
+        Expr::Object(swc_core::ecma::ast::ObjectLit {
+            span: DUMMY_SP,
+            props: props
+                .into_iter()
+                .map(|(key, value)| {
+                    PropOrSpread::Prop(Box::new(Prop::KeyValue(
+                        swc_core::ecma::ast::KeyValueProp {
+                            key: swc_core::ecma::ast::PropName::Ident(IdentName::new(
+                                Atom::from(key),
+                                DUMMY_SP,
+                            )),
+                            value: Box::new(value),
+                        },
+                    )))
+                })
+                .collect(),
+        })
+    }
+
+    /// Look up a nested object value by key path
+    fn object_value<'a>(expr: &'a Expr, path: &[&str]) -> &'a Expr {
+        let Some((first, rest)) = path.split_first() else {
+            return expr;
+        };
+        let Expr::Object(object) = expr else {
+            panic!("expected an object");
+        };
+        let value = object
+            .props
+            .iter()
+            .find_map(|prop| match prop {
+                PropOrSpread::Prop(prop) => match &**prop {
+                    Prop::KeyValue(kv) => match &kv.key {
+                        swc_core::ecma::ast::PropName::Ident(ident) if ident.sym == *first => {
+                            Some(&*kv.value)
                         }
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            })
+            .expect("missing key");
+        object_value(value, rest)
+    }
+
+    fn cva_visitor(expand_cva: bool) -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+        aliases.insert("lg".to_string(), "should-not-expand-keys".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            expand_cva,
+            ..Default::default()
+        })
+    }
+
+    fn cva_config() -> Expr {
+        object_of(vec![(
+            "variants",
+            object_of(vec![(
+                "size",
+                object_of(vec![
+                    ("lg", str_expr("ButtonLg")),
+                    ("sm", str_expr("text-sm")),
+                ]),
+            )]),
+        )])
+    }
+
+    #[test]
+    fn test_expand_cva_base_and_nested_variant() {
+        let mut call = cva_call("cva", vec![str_expr("Button"), cva_config()]);
+
+        let mut visitor = cva_visitor(true);
+        call.visit_mut_with(&mut visitor);
+
+        assert_eq!(expr_str(&call.args[0].expr), "px-4 py-2");
+        let config = &*call.args[1].expr;
+        assert_eq!(
+            expr_str(object_value(config, &["variants", "size", "lg"])),
+            "h-12"
+        );
+        assert_eq!(
+            expr_str(object_value(config, &["variants", "size", "sm"])),
+            "text-sm"
+        );
+    }
+
+    #[test]
+    fn test_expand_cva_only_configured_callees() {
+        let mut tv = cva_call("tv", vec![str_expr("Button")]);
+        let mut other = cva_call("other", vec![str_expr("Button")]);
+
+        let mut visitor = cva_visitor(true);
+        tv.visit_mut_with(&mut visitor);
+        other.visit_mut_with(&mut visitor);
+        assert_eq!(expr_str(&tv.args[0].expr), "px-4 py-2");
+        assert_eq!(expr_str(&other.args[0].expr), "Button");
+
+        let mut disabled = cva_call("cva", vec![str_expr("Button")]);
+        disabled.visit_mut_with(&mut cva_visitor(false));
+        assert_eq!(expr_str(&disabled.args[0].expr), "Button");
+    }
+
+    /// Build a module whose only statement is `<div className="..." />`
+    fn jsx_program(class_name: &str) -> Program {
+        let span = Span::new(BytePos(30), BytePos(60));
+        Program::Module(swc_core::ecma::ast::Module {
+            span,
+            body: vec![swc_core::ecma::ast::ModuleItem::Stmt(
+                swc_core::ecma::ast::Stmt::Expr(swc_core::ecma::ast::ExprStmt {
+                    span,
+                    expr: Box::new(Expr::JSXElement(Box::new(
+                        swc_core::ecma::ast::JSXElement {
+                            span,
+                            opening: class_name_element(class_name, span, DUMMY_SP),
+                            children: vec![],
+                            closing: None,
+                        },
+                    ))),
+                }),
+            )],
+            shebang: None,
+        })
+    }
+
+    fn program_class_name(program: &Program) -> String {
+        let Program::Module(module) = program else {
+            panic!("expected a module");
+        };
+        let swc_core::ecma::ast::ModuleItem::Stmt(swc_core::ecma::ast::Stmt::Expr(stmt)) =
+            &module.body[0]
+        else {
+            panic!("expected an expression statement");
+        };
+        let Expr::JSXElement(element) = &*stmt.expr else {
+            panic!("expected a JSX element");
+        };
+        attr_str(&element.opening.attrs[0]).value.to_string()
+    }
+
+    fn block_comment(text: &str) -> swc_core::common::comments::Comment {
+        swc_core::common::comments::Comment {
+            kind: swc_core::common::comments::CommentKind::Block,
+            span: DUMMY_SP,
+            text: Atom::from(text),
+        }
+    }
+
+    #[test]
+    fn test_disable_comment_skips_file() {
+        let comments = swc_core::common::comments::SingleThreadedComments::default();
+        comments.add_leading(BytePos(30), block_comment(" tailwind-expand-disable "));
+
+        let mut visitor = create_visitor(
+            [("Button".to_string(), "px-4".to_string())]
+                .into_iter()
+                .collect(),
+            false,
+        )
+        .with_comments(comments);
+        let program = transform_program(jsx_program("Button"), &mut visitor);
+        assert_eq!(program_class_name(&program), "Button");
+        assert_eq!(program, jsx_program("Button"));
+    }
+
+    #[test]
+    fn test_file_without_disable_comment_expands() {
+        let comments = swc_core::common::comments::SingleThreadedComments::default();
+        comments.add_leading(BytePos(30), block_comment(" tailwind-expand-disabled "));
+        comments.add_leading(BytePos(30), block_comment(" eslint-disable "));
+
+        let mut visitor = create_visitor(
+            [("Button".to_string(), "px-4".to_string())]
+                .into_iter()
+                .collect(),
+            false,
+        )
+        .with_comments(comments);
+        let program = transform_program(jsx_program("Button"), &mut visitor);
+        assert_eq!(program_class_name(&program), "px-4");
+    }
+
+    fn jsx_element(opening: JSXOpeningElement, children: Vec<JSXElementChild>) -> JSXElement {
+        JSXElement {
+            span: opening.span,
+            opening,
+            children,
+            closing: None,
+        }
+    }
+
+    fn child_class_name(element: &JSXElement, index: usize) -> String {
+        let JSXElementChild::JSXElement(child) = &element.children[index] else {
+            panic!("expected an element child");
+        };
+        attr_str(&child.opening.attrs[0]).value.to_string()
+    }
+
+    fn disable_visitor(
+        comments: swc_core::common::comments::SingleThreadedComments,
+    ) -> TailwindExpandVisitor {
+        create_visitor(
+            [("Button".to_string(), "px-4".to_string())]
+                .into_iter()
+                .collect(),
+            false,
+        )
+        .with_comments(comments)
+    }
+
+    #[test]
+    fn test_disable_next_line_before_element() {
+        let comments = swc_core::common::comments::SingleThreadedComments::default();
+        comments.add_leading(
+            BytePos(10),
+            block_comment(" tailwind-expand-disable-next-line "),
+        );
+
+        let mut disabled = class_name_element(
+            "Button",
+            Span::new(BytePos(10), BytePos(30)),
+            Span::new(BytePos(15), BytePos(28)),
+        );
+        let mut sibling = class_name_element(
+            "Button",
+            Span::new(BytePos(40), BytePos(60)),
+            Span::new(BytePos(45), BytePos(58)),
+        );
+
+        let mut visitor = disable_visitor(comments);
+        disabled.visit_mut_with(&mut visitor);
+        sibling.visit_mut_with(&mut visitor);
+        assert_eq!(attr_str(&disabled.attrs[0]).value.as_str(), "Button");
+        assert_eq!(attr_str(&sibling.attrs[0]).value.as_str(), "px-4");
+    }
+
+    #[test]
+    fn test_disable_next_line_jsx_comment_child() {
+        let comments = swc_core::common::comments::SingleThreadedComments::default();
+        // {/* tailwind-expand-disable-next-line */} at 20..65
+        comments.add_trailing(
+            BytePos(21),
+            block_comment(" tailwind-expand-disable-next-line "),
+        );
+
+        let comment_child =
+            JSXElementChild::JSXExprContainer(swc_core::ecma::ast::JSXExprContainer {
+                span: Span::new(BytePos(20), BytePos(65)),
+                expr: JSXExpr::JSXEmptyExpr(swc_core::ecma::ast::JSXEmptyExpr {
+                    span: Span::new(BytePos(64), BytePos(64)),
+                }),
+            });
+        let whitespace = JSXElementChild::JSXText(swc_core::ecma::ast::JSXText {
+            span: Span::new(BytePos(65), BytePos(68)),
+            value: Atom::from("\n  "),
+            raw: Atom::from("\n  "),
+        });
+        let child = |lo: u32| {
+            JSXElementChild::JSXElement(Box::new(jsx_element(
+                class_name_element(
+                    "Button",
+                    Span::new(BytePos(lo), BytePos(lo + 20)),
+                    Span::new(BytePos(lo + 5), BytePos(lo + 18)),
+                ),
+                vec![],
+            )))
+        };
+        let mut parent = jsx_element(
+            class_name_element(
+                "Button",
+                Span::new(BytePos(1), BytePos(19)),
+                Span::new(BytePos(5), BytePos(18)),
+            ),
+            vec![comment_child, whitespace, child(68), child(90)],
+        );
+
+        let mut visitor = disable_visitor(comments);
+        parent.visit_mut_with(&mut visitor);
+
+        assert_eq!(attr_str(&parent.opening.attrs[0]).value.as_str(), "px-4");
+        assert_eq!(child_class_name(&parent, 2), "Button");
+        assert_eq!(child_class_name(&parent, 3), "px-4");
+    }
+
+    #[test]
+    fn test_disable_next_line_before_attribute() {
+        let comments = swc_core::common::comments::SingleThreadedComments::default();
+        comments.add_leading(
+            BytePos(15),
+            block_comment(" tailwind-expand-disable-next-line "),
+        );
+
+        let mut element = class_name_element(
+            "Button",
+            Span::new(BytePos(10), BytePos(40)),
+            Span::new(BytePos(15), BytePos(35)),
+        );
+
+        let mut visitor = disable_visitor(comments);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(attr_str(&element.attrs[0]).value.as_str(), "Button");
+    }
+
+    fn styled_visitor(expand_objects: bool) -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            expand_objects,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_expand_objects_class_name_property() {
+        let mut call = cva_call(
+            "styled",
+            vec![
+                str_expr("Button"),
+                object_of(vec![
+                    ("className", str_expr("Button mt-2")),
+                    ("title", str_expr("Button")),
+                ]),
+            ],
+        );
+
+        let mut visitor = styled_visitor(true);
+        call.visit_mut_with(&mut visitor);
+
+        assert_eq!(expr_str(&call.args[0].expr), "Button");
+        let options = &*call.args[1].expr;
+        assert_eq!(
+            expr_str(object_value(options, &["className"])),
+            "px-4 py-2 mt-2"
+        );
+        assert_eq!(expr_str(object_value(options, &["title"])), "Button");
+    }
+
+    #[test]
+    fn test_expand_objects_disabled_or_other_callee() {
+        let options = || object_of(vec![("className", str_expr("Button"))]);
+
+        let mut disabled = cva_call("styled", vec![options()]);
+        disabled.visit_mut_with(&mut styled_visitor(false));
+        assert_eq!(
+            expr_str(object_value(&disabled.args[0].expr, &["className"])),
+            "Button"
+        );
+
+        let mut unrelated = cva_call("configure", vec![options()]);
+        unrelated.visit_mut_with(&mut styled_visitor(true));
+        assert_eq!(
+            expr_str(object_value(&unrelated.args[0].expr, &["className"])),
+            "Button"
+        );
+    }
+
+    #[test]
+    fn test_repeated_alias_dedupe_off_keeps_both() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut visitor = create_visitor(aliases, true);
+        let mut element = class_name_element("Button Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                ("className".to_string(), "px-4 py-2 px-4 py-2".to_string()),
+                ("data-expand".to_string(), "Button".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repeated_alias_dedupe_on_collapses() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            debug: true,
+            dedupe: true,
+            ..Default::default()
+        });
+        let mut element = class_name_element("Button px-4 Button mt-2", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                ("className".to_string(), "px-4 py-2 mt-2".to_string()),
+                ("data-expand".to_string(), "Button".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exact_key_with_variant_segment() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("hover:Button".to_string(), "underline".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("hover:Button"),
+            ("underline".to_string(), Some("hover:Button".to_string()))
+        );
+        // Other variants still fall back to stripping
+        assert_eq!(
+            visitor.expand_token("focus:Button"),
+            (
+                "focus:px-4 focus:py-2".to_string(),
+                Some("focus:Button".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn test_apply_variant_prefix_max_variants() {
+        assert_eq!(
+            apply_variant_prefix("max-lg:", "max-lg:flex"),
+            "max-lg:flex"
+        );
+        assert_eq!(
+            apply_variant_prefix("max-[900px]:", "max-[900px]:flex"),
+            "max-[900px]:flex"
+        );
+        assert_eq!(
+            apply_variant_prefix("max-lg:", "max-[900px]:flex"),
+            "max-lg:max-[900px]:flex"
+        );
+        assert_eq!(
+            apply_variant_prefix("dark:max-lg:", "max-lg:hover:flex"),
+            "dark:max-lg:hover:flex"
+        );
+    }
+
+    #[test]
+    fn test_bracketed_variants_are_opaque() {
+        assert_eq!(
+            apply_variant_prefix("supports-[display:grid]:", "supports-[display:grid]:grid"),
+            "supports-[display:grid]:grid"
+        );
+        assert_eq!(apply_variant_prefix("[&:hover]:", "grid"), "[&:hover]:grid");
+        assert_eq!(last_variant_colon("[&:hover]:flex"), Some(9));
+        assert_eq!(last_variant_colon("bg-[url(a:b)]"), None);
+        assert_eq!(insert_important("[&:hover]:flex"), "[&:hover]:!flex");
+    }
+
+    #[test]
+    fn test_arbitrary_variant_with_important() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 md:py-2".to_string());
+        let visitor = create_visitor(aliases, false);
+
+        assert_eq!(
+            visitor.expand_token("[&:hover]:Button").0,
+            "[&:hover]:px-4 [&:hover]:md:py-2"
+        );
+        assert_eq!(
+            visitor.expand_token("[&:hover]:!Button").0,
+            "[&:hover]:!px-4 [&:hover]:md:!py-2"
+        );
+        assert_eq!(
+            visitor.expand_token("![&:hover]:Button").0,
+            "[&:hover]:!px-4 [&:hover]:md:!py-2"
+        );
+        assert_eq!(
+            visitor.expand_token("[&:hover]:[&_a:focus]:!Button").0,
+            "[&:hover]:[&_a:focus]:!px-4 [&:hover]:[&_a:focus]:md:!py-2"
+        );
+    }
+
+    #[test]
+    fn test_explain_class_name_breakdown() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        let expansion = |token: &str, utilities: &str, alias: Option<&str>| TokenExpansion {
+            token: token.to_string(),
+            utilities: utilities.to_string(),
+            alias: alias.map(str::to_string),
+        };
+        assert_eq!(
+            visitor.explain_class_name("Button  text-sm lg:!ButtonLg Unknown"),
+            vec![
+                expansion("Button", "px-4 py-2", Some("Button")),
+                expansion("text-sm", "text-sm", None),
+                expansion("lg:!ButtonLg", "lg:!h-12", Some("ButtonLg")),
+                expansion("Unknown", "Unknown", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_namespaced_class_attribute() {
+        let source = "export const A = ()=><svg xml:class=\"Button\"/>;\n";
+
+        // Ignored unless configured
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            namespaced_class_attributes: vec!["xml:class".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "export const A = ()=><svg xml:class=\"px-4 py-2\"/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_non_breaking_spaces_are_normalized() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+
+        let mut visitor = create_visitor(aliases, false);
+        let (expanded, tracked) = visitor.expand_class_name("Button\u{00A0}text-sm");
+        assert_eq!(expanded, "px-4 py-2 text-sm");
+        assert!(tracked.contains("Button"));
+
+        // Normalized even without aliases
+        let (expanded, tracked) = visitor.expand_class_name("flex\u{00A0}text-sm\u{2009}mt-2");
+        assert_eq!(expanded, "flex text-sm mt-2");
+        assert!(tracked.is_empty());
+
+        // ASCII-only strings are still borrowed back untouched
+        let (expanded, _) = visitor.expand_class_name("flex  text-sm");
+        assert!(matches!(expanded, Cow::Borrowed("flex  text-sm")));
+    }
+
+    #[test]
+    fn test_dry_run_annotates_without_rewriting() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            dry_run: true,
+            ..Default::default()
+        });
+        let mut element = class_name_element("Button lg:ButtonLg text-sm", DUMMY_SP, DUMMY_SP);
+        element.attrs.push(class_directive("ButtonLg", "big"));
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                (
+                    "className".to_string(),
+                    "Button lg:ButtonLg text-sm".to_string()
+                ),
+                (
+                    "data-expand".to_string(),
+                    "Button ButtonLg lg:ButtonLg".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            directive_names(&element),
+            vec!["class:ButtonLg".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_normalize_keys_resolves_spellings() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("ButtonLg".to_string(), "h-12 px-6".to_string());
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            normalize_keys: true,
+            ..Default::default()
+        });
+        for token in ["ButtonLg", "buttonLg", "button-lg", "button_lg"] {
+            assert_eq!(
+                visitor.try_expand_token(token),
+                TokenOutcome::Expanded {
+                    alias: "ButtonLg".to_string(),
+                    utilities: "h-12 px-6".to_string(),
+                },
+                "{token}"
+            );
+        }
+        assert_eq!(
+            visitor.expand_token("md:button-lg").0,
+            "md:h-12 md:px-6".to_string()
+        );
+
+        // Off by default
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.try_expand_token("button-lg"),
+            TokenOutcome::Passthrough
+        );
+    }
+
+    #[test]
+    fn test_normalize_keys_collisions() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+        aliases.insert("button-lg".to_string(), "h-14".to_string());
+        aliases.insert("Buttonlg".to_string(), "h-16".to_string());
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            normalize_keys: true,
+            ..Default::default()
+        });
+        // Exact keys win
+        assert_eq!(visitor.expand_token("button-lg").0, "h-14");
+        assert_eq!(visitor.expand_token("ButtonLg").0, "h-12");
+        // Otherwise the alphabetically first alias wins
+        assert_eq!(visitor.expand_token("buttonLg").0, "h-12");
+    }
+
+    #[test]
+    fn test_extract_components_keeps_aliases_and_collects_definitions() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+        aliases.insert("Card".to_string(), "rounded p-6".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            extract_components: true,
+            ..Default::default()
+        });
+        for class_name in ["Button lg:ButtonLg", "!Button text-sm"] {
+            let mut element = class_name_element(class_name, DUMMY_SP, DUMMY_SP);
+            element.visit_mut_with(&mut visitor);
+            assert_eq!(attr_str(&element.attrs[0]).value.as_str(), class_name);
+        }
+
+        let usage = visitor.usage().unwrap();
+        assert_eq!(
+            usage.components,
+            [
+                ("Button".to_string(), "px-4 py-2".to_string()),
+                ("ButtonLg".to_string(), "h-12".to_string()),
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert_eq!(usage.aliases.get("Button"), Some(&2));
+    }
+
+    #[test]
+    fn test_usage_report_components_are_optional_in_json() {
+        let report: UsageReport =
+            serde_json::from_str(r#"{"aliases": {"Button": 1}, "elements": 1}"#).unwrap();
+        assert!(report.components.is_empty());
+        assert!(!serde_json::to_string(&report)
+            .unwrap()
+            .contains("components"));
+    }
+
+    #[test]
+    fn test_data_and_aria_variants_dedupe() {
+        assert_eq!(
+            apply_variant_prefix("data-[state=open]:", "data-[state=open]:bg-red-500"),
+            "data-[state=open]:bg-red-500"
+        );
+        assert_eq!(
+            apply_variant_prefix("aria-expanded:", "aria-expanded:block"),
+            "aria-expanded:block"
+        );
+        assert_eq!(
+            apply_variant_prefix("data-[state=closed]:", "data-[state=open]:flex"),
+            "data-[state=closed]:data-[state=open]:flex"
+        );
+
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonOpen".to_string(),
+            "data-[state=open]:bg-red-500 ring-2".to_string(),
+        );
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("data-[state=open]:ButtonOpen"),
+            (
+                "data-[state=open]:bg-red-500 data-[state=open]:ring-2".to_string(),
+                Some("data-[state=open]:ButtonOpen".to_string())
+            )
+        );
+        assert_eq!(
+            visitor.expand_token("data-[state=a:b]:ButtonOpen").0,
+            "data-[state=a:b]:data-[state=open]:bg-red-500 data-[state=a:b]:ring-2"
+        );
+    }
+
+    fn whitespace_visitor(whitespace: WhitespacePolicy) -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            whitespace,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_whitespace_single() {
+        let mut visitor = whitespace_visitor(WhitespacePolicy::Single);
+        let (expanded, _) = visitor.expand_class_name(" Button  text-sm ");
+        assert_eq!(expanded, "px-4 py-2 text-sm");
+        let (expanded, _) = visitor.expand_class_name("flex  text-sm");
+        assert_eq!(expanded, "flex  text-sm");
+        let (expanded, _) = visitor.expand_class_name("flex\u{00A0}\u{00A0}text-sm");
+        assert_eq!(expanded, "flex text-sm");
+    }
+
+    #[test]
+    fn test_whitespace_preserve() {
+        let mut visitor = whitespace_visitor(WhitespacePolicy::Preserve);
+        let (expanded, _) = visitor.expand_class_name(" Button  text-sm\tmt-2 ");
+        assert_eq!(expanded, " px-4 py-2  text-sm\tmt-2 ");
+        let (expanded, _) = visitor.expand_class_name("flex\u{00A0}\u{00A0}text-sm");
+        assert_eq!(expanded, "flex  text-sm");
+    }
+
+    #[test]
+    fn test_whitespace_original() {
+        let mut visitor = whitespace_visitor(WhitespacePolicy::Original);
+        let (expanded, _) = visitor.expand_class_name("Button  text-sm");
+        assert_eq!(expanded, "px-4 py-2 text-sm");
+        let (expanded, _) = visitor.expand_class_name("flex\u{00A0}\u{00A0}text-sm");
+        assert!(matches!(
+            expanded,
+            Cow::Borrowed("flex\u{00A0}\u{00A0}text-sm")
+        ));
+    }
+
+    #[test]
+    fn test_whitespace_policy_deserializes() {
+        let config: Config = serde_json::from_str(r#"{"whitespace": "preserve"}"#).unwrap();
+        assert_eq!(config.whitespace, WhitespacePolicy::Preserve);
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.whitespace, WhitespacePolicy::Single);
+    }
+
+    fn spread_attr(expr: Expr) -> JSXAttrOrSpread {
+        JSXAttrOrSpread::SpreadElement(swc_core::ecma::ast::SpreadElement {
+            dot3_token: DUMMY_SP,
+            expr: Box::new(expr),
+        })
+    }
+
+    fn spread_expr(element: &JSXOpeningElement) -> &Expr {
+        match &element.attrs[0] {
+            JSXAttrOrSpread::SpreadElement(spread) => &spread.expr,
+            _ => panic!("expected a spread attribute"),
+        }
+    }
+
+    #[test]
+    fn test_expand_spread_objects_conditional() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("ButtonLg".to_string(), "h-12 px-6".to_string());
+
+        // {...(big ? { className: "ButtonLg", title: "ButtonLg" } : {})}
+        let spread = || {
+            spread_attr(Expr::Paren(ParenExpr {
+                span: DUMMY_SP,
+                expr: Box::new(Expr::Cond(CondExpr {
+                    span: DUMMY_SP,
+                    test: Box::new(Expr::Ident(Ident::new_no_ctxt(Atom::from("big"), DUMMY_SP))),
+                    cons: Box::new(object_of(vec![
+                        ("className", str_expr("ButtonLg")),
+                        ("title", str_expr("ButtonLg")),
+                    ])),
+                    alt: Box::new(object_of(vec![])),
+                })),
+            }))
+        };
+        let consequent = |element: &JSXOpeningElement| -> Expr {
+            let Expr::Paren(ParenExpr { expr, .. }) = spread_expr(element) else {
+                panic!("expected parentheses");
+            };
+            let Expr::Cond(cond) = &**expr else {
+                panic!("expected a conditional");
+            };
+            (*cond.cons).clone()
+        };
+
+        let mut element = class_name_element("", DUMMY_SP, DUMMY_SP);
+        element.attrs = vec![spread()];
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            debug: true,
+            expand_spread_objects: true,
+            ..Default::default()
+        });
+        element.visit_mut_with(&mut visitor);
+
+        let cons = consequent(&element);
+        assert_eq!(expr_str(object_value(&cons, &["className"])), "h-12 px-6");
+        assert_eq!(expr_str(object_value(&cons, &["title"])), "ButtonLg");
+        assert_eq!(
+            attr_pairs(&element),
+            vec![("data-expand".to_string(), "ButtonLg".to_string())]
+        );
+
+        // Off by default
+        let mut element = class_name_element("", DUMMY_SP, DUMMY_SP);
+        element.attrs = vec![spread()];
+        element.visit_mut_with(&mut create_visitor(aliases, false));
+        let cons = consequent(&element);
+        assert_eq!(expr_str(object_value(&cons, &["className"])), "ButtonLg");
+    }
+
+    fn component_visitor() -> TailwindExpandVisitor {
+        let mut component_aliases = HashMap::new();
+        component_aliases.insert(
+            "Hero".to_string(),
+            ComponentAlias {
+                class: "px-4 py-2".to_string(),
+                style: Some("background-image: url(/hero.png);".to_string()),
+            },
+        );
+        TailwindExpandVisitor::new(Config {
+            aliases: [("Button".to_string(), "h-12".to_string())]
+                .into_iter()
+                .collect(),
+            component_aliases,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_component_alias_adds_class_and_style() {
+        let mut element = class_name_element("Hero Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut component_visitor());
+
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                ("className".to_string(), "px-4 py-2 h-12".to_string()),
+                (
+                    "style".to_string(),
+                    "background-image: url(/hero.png)".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_component_alias_merges_style_and_skips_variants() {
+        let mut element = class_name_element("Hero", DUMMY_SP, DUMMY_SP);
+        element.attrs.push(string_attr("style", "color: red;"));
+        element.visit_mut_with(&mut component_visitor());
+        assert_eq!(
+            attr_pairs(&element)[1],
+            (
+                "style".to_string(),
+                "color: red; background-image: url(/hero.png)".to_string()
+            )
+        );
+
+        let mut element = class_name_element("md:Hero", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut component_visitor());
+        assert_eq!(
+            attr_pairs(&element),
+            vec![("className".to_string(), "md:px-4 md:py-2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_component_aliases_deserialize() {
+        let config: Config = serde_json::from_str(
+            r#"{"componentAliases": {"Hero": {"class": "px-4", "style": "color: red"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.component_aliases["Hero"],
+            ComponentAlias {
+                class: "px-4".to_string(),
+                style: Some("color: red".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_debug_order() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+
+        for (debug_order, expected) in [
+            (DebugOrder::Alphabetical, "Button ButtonLg"),
+            (DebugOrder::Source, "ButtonLg Button"),
+        ] {
+            let mut visitor = TailwindExpandVisitor::new(Config {
+                aliases: aliases.clone(),
+                debug: true,
+                debug_order,
+                ..Default::default()
+            });
+            let mut element = class_name_element("ButtonLg Button", DUMMY_SP, DUMMY_SP);
+            element.visit_mut_with(&mut visitor);
+
+            assert_eq!(
+                attr_pairs(&element),
+                vec![
+                    ("className".to_string(), "h-12 px-4 py-2".to_string()),
+                    ("data-expand".to_string(), expected.to_string()),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_opacity_modifiers_survive_variants_and_important() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "OpacityAlias".to_string(),
+            "bg-red-500/50 text-white/[.35]".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("!OpacityAlias").0,
+            "!bg-red-500/50 !text-white/[.35]"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:OpacityAlias").0,
+            "hover:bg-red-500/50 hover:text-white/[.35]"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:!OpacityAlias").0,
+            "hover:!bg-red-500/50 hover:!text-white/[.35]"
+        );
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert_eq!(Config::default().validate(), Ok(()));
+
+        let config = Config {
+            aliases: [(" ".to_string(), "px-4".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::EmptyAliasKey));
+        assert!(TailwindExpandVisitor::try_new(config).is_err());
+
+        let config = Config {
+            aliases: [("Button Lg".to_string(), "h-12".to_string())]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::AliasKeyWithWhitespace("Button Lg".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_malformed_separator() {
+        for attribute in ["xmlclass", ":class", "xml:", "a:b:c"] {
+            let config = Config {
+                namespaced_class_attributes: vec![attribute.to_string()],
+                ..Default::default()
+            };
+            assert_eq!(
+                config.validate(),
+                Err(ConfigError::MalformedNamespacedAttribute(
+                    attribute.to_string()
+                )),
+                "{attribute}"
+            );
+        }
+
+        let config = Config {
+            namespaced_class_attributes: vec!["xml:class".to_string()],
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_duplicate_normalized_keys() {
+        let mut config = Config {
+            aliases: [
+                ("ButtonLg".to_string(), "h-12".to_string()),
+                ("button-lg".to_string(), "h-14".to_string()),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.normalize_keys = true;
+        let error = config.validate().unwrap_err();
+        assert_eq!(
+            error,
+            ConfigError::DuplicateNormalizedKey("ButtonLg".to_string(), "button-lg".to_string())
+        );
+        assert!(error.to_string().contains("normalizeKeys"));
+    }
+
+    #[test]
+    fn test_arbitrary_values_with_colons() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "BrandText".to_string(),
+            "text-[color:var(--brand)] hover:[color:var(--brand-hover)]".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("lg:BrandText").0,
+            "lg:text-[color:var(--brand)] lg:hover:[color:var(--brand-hover)]"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:!BrandText").0,
+            "hover:!text-[color:var(--brand)] hover:![color:var(--brand-hover)]"
+        );
+        assert_eq!(
+            utility_rank("lg:text-[color:var(--brand)]"),
+            utility_rank("text-white")
+        );
+    }
+
+    fn create_element_visitor() -> TailwindExpandVisitor {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        TailwindExpandVisitor::new(Config {
+            aliases,
+            report_path: Some("usage.json".to_string()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_expand_jsx_runtime_calls() {
+        let mut visitor = create_element_visitor();
+        for callee in ["_jsx", "_jsxs"] {
+            let mut call = cva_call(
+                callee,
+                vec![
+                    str_expr("div"),
+                    object_of(vec![
+                        ("className", str_expr("Button mt-2")),
+                        ("title", str_expr("Button")),
+                    ]),
+                ],
+            );
+            call.visit_mut_with(&mut visitor);
+
+            assert_eq!(
+                expr_str(object_value(&call.args[1].expr, &["className"])),
+                "px-4 py-2 mt-2"
+            );
+            assert_eq!(
+                expr_str(object_value(&call.args[1].expr, &["title"])),
+                "Button"
+            );
+        }
+        assert_eq!(visitor.usage().unwrap().elements, 2);
+    }
+
+    #[test]
+    fn test_expand_react_create_element() {
+        let source = "React.createElement(\"div\", {\n    className: \"Button\"\n});\n_jsx(\"div\", {\n    className: \"Button\"\n});\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "React.createElement(\"div\", {\n    className: \"px-4 py-2\"\n});\n_jsx(\"div\", {\n    className: \"px-4 py-2\"\n});\n"
+        );
+
+        // Only the configured functions are expanded
+        let config = Config {
+            create_element_functions: Some(vec!["React.createElement".to_string()]),
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "React.createElement(\"div\", {\n    className: \"px-4 py-2\"\n});\n_jsx(\"div\", {\n    className: \"Button\"\n});\n"
+        );
+    }
+
+    #[test]
+    fn test_named_group_and_peer_variants() {
+        assert_eq!(
+            apply_variant_prefix("group-hover/sidebar:", "group-hover/sidebar:flex"),
+            "group-hover/sidebar:flex"
+        );
+        assert_eq!(
+            apply_variant_prefix("group-hover/sidebar:", "group-hover/other:flex"),
+            "group-hover/sidebar:group-hover/other:flex"
+        );
+        assert_eq!(
+            apply_variant_prefix(
+                "group-[.is-open:not(.a)]/nav:",
+                "group-[.is-open:not(.a)]/nav:block"
+            ),
+            "group-[.is-open:not(.a)]/nav:block"
+        );
+
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "DrawerItem".to_string(),
+            "peer-checked/drawer:block group-hover/sidebar:underline".to_string(),
+        );
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("peer-checked/drawer:DrawerItem").0,
+            "peer-checked/drawer:block peer-checked/drawer:group-hover/sidebar:underline"
+        );
+        assert_eq!(
+            visitor.expand_token("group-hover/sidebar:DrawerItem").0,
+            "group-hover/sidebar:peer-checked/drawer:block group-hover/sidebar:underline"
+        );
+    }
+
+    #[test]
+    fn test_dedupe_variants_option() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonGitHub".to_string(),
+            "bg-black dark:text-white".to_string(),
+        );
+
+        let visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(
+            visitor.expand_token("dark:ButtonGitHub").0,
+            "dark:bg-black dark:text-white"
+        );
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            dedupe_variants: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_token("dark:ButtonGitHub").0,
+            "dark:bg-black dark:dark:text-white"
+        );
+    }
+
+    #[test]
+    fn test_expansion_stats() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12".to_string());
+
+        let mut visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(visitor.stats(), ExpansionStats::default());
+
+        visitor.expand_class_name("Button lg:ButtonLg text-sm Buton hover:Card flex");
+        assert_eq!(
+            visitor.stats(),
+            ExpansionStats {
+                tokens: 6,
+                expanded: 2,
+                unknown: 2,
+                capped: 0,
+            }
+        );
+
+        // Cached strings still count
+        visitor.expand_class_name("Button lg:ButtonLg text-sm Buton hover:Card flex");
+        assert_eq!(visitor.stats().tokens, 12);
+        assert_eq!(visitor.stats().expanded, 4);
+
+        // Counters are per visitor
+        assert_eq!(
+            create_visitor(aliases, false).stats(),
+            ExpansionStats::default()
+        );
+    }
+
+    /// Parse JSX source, run the transform over it and print the result, so
+    /// end-to-end tests are written as input/output pairs
+    fn transform_jsx(source: &str, config: Config) -> String {
+        transform_source(source, &config).expect("transform failed")
+    }
+
+    /// Like transform_jsx, for TSX source
+    fn transform_tsx(source: &str, config: Config) -> String {
+        use swc_core::common::{sync::Lrc, FileName, SourceMap};
+        use swc_core::ecma::parser::{parse_file_as_program, Syntax, TsSyntax};
+
+        let source_map: Lrc<SourceMap> = Default::default();
+        let file = source_map.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+        let program = parse_file_as_program(
+            &file,
+            Syntax::Typescript(TsSyntax {
+                tsx: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            None,
+            &mut vec![],
+        )
+        .expect("failed to parse test source");
+        let program = transform_program(program, &mut TailwindExpandVisitor::new(config));
+        swc_core::ecma::codegen::to_code_default(source_map, None, &program)
+    }
+
+    /// Parse JSX source into a module, for tests that inspect the visitor afterwards
+    fn parse_module(source: &str) -> swc_core::ecma::ast::Module {
+        use swc_core::common::{sync::Lrc, FileName, SourceMap};
+        use swc_core::ecma::parser::{parse_file_as_module, EsSyntax, Syntax};
+
+        let source_map: Lrc<SourceMap> = Default::default();
+        let file = source_map.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+        parse_file_as_module(
+            &file,
+            Syntax::Es(EsSyntax {
+                jsx: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            None,
+            &mut vec![],
+        )
+        .expect("failed to parse source")
+    }
+
+    fn source_config(debug: bool) -> Config {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12 px-6".to_string());
+        Config {
+            aliases,
+            debug,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transform_source_self_closing_element() {
+        assert_eq!(
+            transform_jsx(
+                r#"const a = <Button className="ButtonLg" disabled />;"#,
+                source_config(false)
+            ),
+            "const a = <Button className=\"h-12 px-6\" disabled/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_transform_source_nested_fragments() {
+        let output = transform_jsx(
+            r#"const a = <><div className="Button"><><span className="lg:ButtonLg" /></></div></>;"#,
+            source_config(false),
+        );
+        assert_eq!(
+            output,
+            "const a = <><div className=\"px-4 py-2\"><><span className=\"lg:h-12 lg:px-6\"/></></div></>;\n"
+        );
+    }
+
+    #[test]
+    fn test_transform_source_nested_debug() {
+        let output = transform_jsx(
+            r#"const a = <section className="Button text-sm"><Card className="ButtonLg" title="x" /></section>;"#,
+            source_config(true),
+        );
+        assert_eq!(
+            output,
+            "const a = <section className=\"px-4 py-2 text-sm\" data-expand=\"Button\"><Card className=\"h-12 px-6\" title=\"x\" data-expand=\"ButtonLg\"/></section>;\n"
+        );
+    }
+
+    /// Run every fixture in test/fixtures/transform: each directory holds an
+    /// input.jsx, the expected output.jsx and an optional config.json merged
+    /// over the shared aliases. Set UPDATE_FIXTURES=1 to rewrite the expected output.
+    #[test]
+    fn test_transform_fixtures() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("test/fixtures/transform");
+        let mut fixtures: Vec<_> = std::fs::read_dir(&root)
+            .expect("missing fixtures directory")
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        fixtures.sort();
+        assert!(!fixtures.is_empty());
+
+        for fixture in fixtures {
+            let input = std::fs::read_to_string(fixture.join("input.jsx")).unwrap();
+            let mut config: serde_json::Value = serde_json::json!({
+                "aliases": {
+                    "Button": "px-4 py-2",
+                    "ButtonLg": "h-12 px-6",
+                },
+            });
+            if let Ok(overrides) = std::fs::read_to_string(fixture.join("config.json")) {
+                let overrides: serde_json::Value = serde_json::from_str(&overrides).unwrap();
+                for (key, value) in overrides.as_object().unwrap() {
+                    config[key] = value.clone();
+                }
+            }
+
+            let output = transform_jsx(&input, serde_json::from_value(config).unwrap());
+            let expected_path = fixture.join("output.jsx");
+            if std::env::var_os("UPDATE_FIXTURES").is_some() {
+                std::fs::write(&expected_path, &output).unwrap();
+            }
+            let expected = std::fs::read_to_string(&expected_path).unwrap_or_default();
+            assert_eq!(output, expected, "fixture {}", fixture.display());
+        }
+    }
+
+    #[test]
+    fn test_debug_exclude() {
+        let mut config = source_config(true);
+        config.debug_exclude = ["Button".to_string()].into_iter().collect();
+        let mut visitor = TailwindExpandVisitor::new(config);
+
+        let mut element = class_name_element("md:Button ButtonLg", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                (
+                    "className".to_string(),
+                    "md:px-4 md:py-2 h-12 px-6".to_string()
+                ),
+                ("data-expand".to_string(), "ButtonLg".to_string()),
+            ]
+        );
+
+        // No data-expand at all when every alias is excluded
+        let mut element = class_name_element("Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(
+            attr_pairs(&element),
+            vec![("className".to_string(), "px-4 py-2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_known_variants_warn_on_unknown_variant() {
+        let mut config = source_config(false);
+        config.known_variants = Some(
+            ["hover", "lg", "group-hover"]
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+        );
+        let mut visitor = TailwindExpandVisitor::new(config);
+
+        let span = Span::new(BytePos(10), BytePos(40));
+        let mut element = class_name_element(
+            "hover:Button hvoer:ButtonLg group-hover/nav:Button data-[open]:Button lg:flex",
+            span,
+            span,
+        );
+        element.visit_mut_with(&mut visitor);
+
+        // Still expanded
+        assert_eq!(
+            attr_str(&element.attrs[0]).value.as_str(),
+            "hover:px-4 hover:py-2 hvoer:h-12 hvoer:px-6 group-hover/nav:px-4 group-hover/nav:py-2 data-[open]:px-4 data-[open]:py-2 lg:flex"
+        );
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnknownVariant);
+        assert_eq!(diagnostics[0].severity, Severity::Warn);
+        assert!(diagnostics[0]
+            .message
+            .contains("\"hvoer\" in \"hvoer:ButtonLg\""));
+    }
+
+    #[test]
+    fn test_variants_unchecked_without_known_variants() {
+        let mut visitor = TailwindExpandVisitor::new(source_config(false));
+        let mut element = class_name_element("hvoer:Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert!(visitor.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn test_expand_children_of_configured_components() {
+        let mut config = source_config(false);
+        config.expand_children_of = vec!["Classes".to_string()];
+
+        assert_eq!(
+            transform_jsx(
+                r#"const a = <><Classes>Button ButtonLg</Classes><Classes>{"lg:Button"}</Classes><Other>Button</Other></>;"#,
+                config
+            ),
+            "const a = <><Classes>px-4 py-2 h-12 px-6</Classes><Classes>{\"lg:px-4 lg:py-2\"}</Classes><Other>Button</Other></>;\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_children_of_escapes_jsx_text() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Icon".to_string(),
+            "[&>svg]:size-4 [&_a:not(.x)]:{color:red} data-[a&b;]:flex".to_string(),
+        );
+        let config = Config {
+            aliases,
+            expand_children_of: vec!["Classes".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            transform_jsx("const a = <Classes>Icon</Classes>;", config.clone()),
+            "const a = <Classes>[&&gt;svg]:size-4 [&_a:not(.x)]:&#123;color:red&#125; data-[a&amp;b;]:flex</Classes>;\n"
+        );
+        // The escaped text reads back as the expanded classes
+        let output = transform_jsx("const a = <Classes>Icon</Classes>;", config);
+        let module = parse_module(&output);
+        let mut texts = Vec::new();
+        module.visit_with(&mut JsxTextCollector(&mut texts));
+        assert_eq!(
+            texts,
+            ["[&>svg]:size-4 [&_a:not(.x)]:{color:red} data-[a&b;]:flex"]
+        );
+    }
+
+    struct JsxTextCollector<'a>(&'a mut Vec<String>);
+
+    impl Visit for JsxTextCollector<'_> {
+        fn visit_jsx_text(&mut self, text: &swc_core::ecma::ast::JSXText) {
+            self.0.push(text.value.to_string());
+        }
+    }
+
+    #[test]
+    fn test_expand_children_of_requires_a_sole_child() {
+        let mut config = source_config(false);
+        config.expand_children_of = vec!["Classes".to_string()];
+
+        assert_eq!(
+            transform_jsx(
+                r#"const a = <Classes>Button<b>ButtonLg</b></Classes>;"#,
+                config
+            ),
+            "const a = <Classes>Button<b>ButtonLg</b></Classes>;\n"
+        );
+    }
+
+    #[test]
+    fn test_transform_source_round_trip() {
+        let output = transform_source(
+            "// Page header\nexport const Header = () => <h1 className=\"hover:Button\">Title</h1>;\n",
+            &source_config(false),
+        )
+        .unwrap();
+        assert_eq!(
+            output,
+            "// Page header\nexport const Header = ()=><h1 className=\"hover:px-4 hover:py-2\">Title</h1>;\n"
+        );
+    }
+
+    #[test]
+    fn test_transform_source_errors() {
+        assert!(matches!(
+            transform_source(
+                "const a = <div className=\"Button\">;",
+                &source_config(false)
+            ),
+            Err(TransformError::Parse(_))
+        ));
+
+        let mut config = source_config(false);
+        config.namespaced_class_attributes = vec!["class".to_string()];
+        assert_eq!(
+            transform_source("const a = 1;", &config),
+            Err(TransformError::InvalidConfig(
+                ConfigError::MalformedNamespacedAttribute("class".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_config_alias_values_as_arrays() {
+        let joined: Config = serde_json::from_str(
+            r#"{"aliases": {"Button": "px-4 py-2", "ButtonLg": "h-12 px-6"}}"#,
+        )
+        .unwrap();
+        let listed: Config = serde_json::from_str(
+            r#"{"aliases": {"Button": ["px-4", "py-2"], "ButtonLg": "h-12 px-6"}}"#,
+        )
+        .unwrap();
+        assert_eq!(joined.aliases, listed.aliases);
+        assert_eq!(listed.aliases["Button"], "px-4 py-2");
+
+        assert!(serde_json::from_str::<Config>(r#"{"aliases": {"Button": 4}}"#).is_err());
+    }
+
+    #[test]
+    fn test_expand_variant_groups() {
+        assert_eq!(
+            expand_variant_groups("hover:(bg-red text-white) p-2").as_deref(),
+            Some("hover:bg-red hover:text-white p-2")
+        );
+        assert_eq!(
+            expand_variant_groups("dark:(hover:(a b) c)").as_deref(),
+            Some("dark:hover:a dark:hover:b dark:c")
+        );
+        // Arbitrary values and CSS variable shorthands aren't groups
+        assert_eq!(
+            expand_variant_groups("hover:bg-[url(a.png)] fill-(--brand)"),
+            None
+        );
+        assert_eq!(expand_variant_groups("hover:(a b"), None);
+    }
+
+    #[test]
+    fn test_variant_groups_in_aliases_and_class_names() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert(
+            "Link".to_string(),
+            "underline hover:(text-blue-500 no-underline)".to_string(),
+        );
+
+        let mut visitor = create_visitor(aliases, false);
+        let (expanded, _) = visitor.expand_class_name("Link");
+        assert_eq!(expanded, "underline hover:text-blue-500 hover:no-underline");
+
+        let (expanded, tokens) = visitor.expand_class_name("hover:(Button text-white) p-2");
+        assert_eq!(expanded, "hover:px-4 hover:py-2 hover:text-white p-2");
+        assert!(tokens.contains("hover:Button"));
+    }
+
+    #[test]
+    fn test_debug_attribute_position() {
+        let source = "const a = <div {...props} className=\"Button\" title=\"x\"/>;\n";
+        for (debug_attribute_position, expected) in [
+            (
+                DebugAttributePosition::First,
+                "const a = <div data-expand=\"Button\" {...props} className=\"px-4 py-2\" title=\"x\"/>;\n",
+            ),
+            (
+                DebugAttributePosition::Last,
+                "const a = <div {...props} className=\"px-4 py-2\" title=\"x\" data-expand=\"Button\"/>;\n",
+            ),
+        ] {
+            let config = Config {
+                debug_attribute_position,
+                ..source_config(true)
+            };
+            assert_eq!(transform_jsx(source, config), expected);
+        }
+    }
+
+    #[test]
+    fn test_custom_element_class_and_part() {
+        let source = "const a = <my-button class=\"Button\" part=\"ButtonLg\"/>;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "const a = <my-button class=\"px-4 py-2\" part=\"ButtonLg\"/>;\n"
+        );
+
+        let config = Config {
+            expand_part_attributes: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <my-button class=\"px-4 py-2\" part=\"h-12 px-6\"/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_unknown_alias_fallback() {
+        let source = "const a = <div className=\"Button  ButtonPrimary lg:Buton flex\"/>;\n";
+        for (fallback, class_name, warnings) in [
+            (
+                UnknownAliasFallback::Passthrough,
+                "px-4 py-2 ButtonPrimary lg:Buton flex",
+                0,
+            ),
+            (UnknownAliasFallback::Remove, "px-4 py-2 flex", 0),
+            (
+                UnknownAliasFallback::Keep,
+                "px-4 py-2 ButtonPrimary lg:Buton flex",
+                2,
+            ),
+        ] {
+            let mut visitor = TailwindExpandVisitor::new(Config {
+                fallback,
+                ..source_config(false)
+            });
+            let mut element =
+                class_name_element("Button  ButtonPrimary lg:Buton flex", DUMMY_SP, DUMMY_SP);
+            element.visit_mut_with(&mut visitor);
+
+            assert_eq!(attr_str(&element.attrs[0]).value.as_str(), class_name);
+            let diagnostics = visitor.diagnostics();
+            assert_eq!(diagnostics.len(), warnings);
+            assert!(diagnostics
+                .iter()
+                .all(|d| d.kind == DiagnosticKind::UnknownAlias));
+        }
+
+        // Removed tokens keep the surrounding spacing under the preserve policy
+        let config = Config {
+            fallback: UnknownAliasFallback::Remove,
+            whitespace: WhitespacePolicy::Preserve,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <div className=\"px-4 py-2    flex\"/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_uno_shortcuts() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert(
+            "/btn-(\\w+)/".to_string(),
+            "bg-$1-500 text-white".to_string(),
+        );
+        aliases.insert("btn-danger".to_string(), "bg-red-700".to_string());
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            uno_shortcuts: true,
+            ..Default::default()
+        });
+        assert_eq!(visitor.expand_token("btn-blue").0, "bg-blue-500 text-white");
+        assert_eq!(
+            visitor.expand_token("hover:btn-green").0,
+            "hover:bg-green-500 hover:text-white"
+        );
+        // Literal aliases win, and the pattern must match the whole token
+        assert_eq!(visitor.expand_token("btn-danger").0, "bg-red-700");
+        assert_eq!(
+            visitor.expand_token("my-btn-blue"),
+            ("my-btn-blue".to_string(), None)
+        );
+
+        // Without the flag, shortcut keys are plain (unmatchable) aliases
+        let visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(
+            visitor.expand_token("btn-blue"),
+            ("btn-blue".to_string(), None)
+        );
+
+        aliases.insert("/btn-(/".to_string(), "bg-red".to_string());
+        let config = Config {
+            aliases,
+            uno_shortcuts: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidShortcutPattern(key, _)) if key == "/btn-(/"
+        ));
+    }
+
+    #[test]
+    fn test_diagnostics_point_at_tokens() {
+        use swc_core::common::{sync::Lrc, FileName, SourceMap};
+        use swc_core::ecma::parser::{parse_file_as_module, EsSyntax, Syntax};
+
+        let source = "const a = <div className=\"flex hvoer:Button ButonLg\"/>;";
+        let source_map: Lrc<SourceMap> = Default::default();
+        let file = source_map.new_source_file(Lrc::new(FileName::Anon), source.to_string());
+        let module = parse_file_as_module(
+            &file,
+            Syntax::Es(EsSyntax {
+                jsx: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            None,
+            &mut vec![],
+        )
+        .unwrap();
+
+        let mut config = source_config(false);
+        config.known_variants = Some(HashSet::from(["hover".to_string()]));
+        config.fallback = UnknownAliasFallback::Keep;
+        let mut visitor = TailwindExpandVisitor::new(config);
+        transform_program(Program::Module(module), &mut visitor);
+
+        let snippets: Vec<_> = visitor
+            .diagnostics()
+            .iter()
+            .map(|d| {
+                let lo = (d.span.lo - file.start_pos).0 as usize;
+                let hi = (d.span.hi - file.start_pos).0 as usize;
+                (d.kind, source[lo..hi].to_string())
+            })
+            .collect();
+        assert_eq!(
+            snippets,
+            vec![
+                (DiagnosticKind::UnknownVariant, "hvoer:Button".to_string()),
+                (DiagnosticKind::UnknownAlias, "ButonLg".to_string()),
+            ]
+        );
+
+        // Literals that don't line up with the source keep the whole span
+        let str_lit = Str {
+            span: Span::new(BytePos(1), BytePos(4)),
+            value: Atom::from("flex Button"),
+            raw: None,
+        };
+        assert_eq!(token_span(&str_lit, "Button"), str_lit.span);
+    }
+
+    #[test]
+    fn test_bem_composition() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("Lg".to_string(), "h-12 text-lg".to_string());
+        aliases.insert("Icon".to_string(), "size-4".to_string());
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            bem_composition: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_token("Button--Lg"),
+            (
+                "px-4 py-2 h-12 text-lg".to_string(),
+                Some("Button--Lg".to_string())
+            )
+        );
+        assert_eq!(
+            visitor.expand_token("md:Button--Lg").0,
+            "md:px-4 md:py-2 md:h-12 md:text-lg"
+        );
+        // Plain utilities and tokens with unknown segments are untouched
+        for token in ["bg-[--brand]", "px--4", "Button--Xl", "--Button"] {
+            assert_eq!(visitor.expand_token(token), (token.to_string(), None));
+        }
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            bem_composition: true,
+            bem_delimiter: Some("__".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(visitor.expand_token("Button__Icon").0, "px-4 py-2 size-4");
+        assert_eq!(visitor.expand_token("Button--Lg").1, None);
+    }
+
+    #[test]
+    fn test_expand_long_class_name() {
+        let mut aliases = AliasMap::new();
+        for index in 0..100 {
+            aliases.insert(
+                format!("Alias{}", index),
+                format!("p-{} m-{}", index, index),
+            );
+        }
+        let mut visitor = create_visitor(aliases, false);
+
+        let class_name: Vec<String> = (0..500)
+            .map(|index| match index % 5 {
+                0 => format!("Alias{}", index % 100),
+                1 => format!("hover:Alias{}", index % 100),
+                _ => format!("text-{}", index),
+            })
+            .collect();
+        let expected: Vec<String> = (0..500)
+            .map(|index| match index % 5 {
+                0 => format!("p-{0} m-{0}", index % 100),
+                1 => format!("hover:p-{0} hover:m-{0}", index % 100),
+                _ => format!("text-{}", index),
+            })
+            .collect();
+
+        let class_name = class_name.join(" ");
+        let (expanded, tokens) = visitor.expand_class_name(&class_name);
+        assert_eq!(expanded, expected.join(" "));
+        assert_eq!(tokens.iter().count(), 40);
+        assert_eq!(visitor.stats().tokens, 500);
+    }
+
+    #[test]
+    fn test_glob_aliases() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button*".to_string(), "inline-flex rounded".to_string());
+        aliases.insert("ButtonLg*".to_string(), "font-bold".to_string());
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "h-12 px-6".to_string());
+
+        let visitor = create_visitor(aliases, false);
+        // The glob's utilities come first, then the exact alias's
+        assert_eq!(
+            visitor.expand_token("Button").0,
+            "inline-flex rounded px-4 py-2"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:ButtonLg").0,
+            "lg:inline-flex lg:rounded lg:font-bold lg:h-12 lg:px-6"
+        );
+        // A glob also matches names without an exact alias
+        assert_eq!(
+            visitor.expand_token("ButtonSm"),
+            (
+                "inline-flex rounded".to_string(),
+                Some("ButtonSm".to_string())
+            )
+        );
+        assert_eq!(visitor.expand_token("Card"), ("Card".to_string(), None));
+    }
+
+    #[test]
+    fn test_used_aliases_accumulate_across_program() {
+        let source = r#"
+            const button = cva("Button", { variants: { size: { lg: "lg:buttonLg" } } });
+            export const A = () => <div className="hover:Button flex" />;
+            export const B = () => <div className="hover:Button flex" />;
+        "#;
+        let program = Program::Module(parse_module(source));
+        let mut config = source_config(false);
+        config.expand_cva = true;
+        config.normalize_keys = true;
+        config.used_aliases_path = Some("used.json".to_string());
+        let mut visitor = TailwindExpandVisitor::new(config);
+        transform_program(program, &mut visitor);
+
+        // Canonical names, across elements and calls, including cached strings
+        let used: Vec<&str> = visitor
+            .used_aliases()
+            .unwrap()
+            .iter()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(used, vec!["Button", "ButtonLg"]);
+        assert_eq!(
+            module_report("a.tsx", visitor.used_aliases().unwrap()),
+            "{\n  \"a.tsx\": [\n    \"Button\",\n    \"ButtonLg\"\n  ]\n}"
+        );
+
+        assert!(TailwindExpandVisitor::new(source_config(false))
+            .used_aliases()
+            .is_none());
+    }
+
+    #[test]
+    fn test_important_base_only() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonMain".to_string(),
+            "bg-amber-500 hover:bg-amber-600".to_string(),
+        );
+
+        let visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(
+            visitor.expand_token("!ButtonMain").0,
+            "!bg-amber-500 hover:!bg-amber-600"
+        );
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            important_base_only: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_token("!ButtonMain").0,
+            "!bg-amber-500 hover:bg-amber-600"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:!ButtonMain").0,
+            "lg:!bg-amber-500 lg:hover:bg-amber-600"
+        );
+    }
+
+    #[test]
+    fn test_arbitrary_at_rule_variants_stay_opaque() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Layout".to_string(),
+            "block [@supports(display:grid)]:grid [@media(min-width:768px)]:flex".to_string(),
+        );
+
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("lg:Layout").0,
+            "lg:block lg:[@supports(display:grid)]:grid lg:[@media(min-width:768px)]:flex"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:!Layout").0,
+            "lg:!block lg:[@supports(display:grid)]:!grid lg:[@media(min-width:768px)]:!flex"
+        );
+        assert_eq!(
+            visitor.expand_token("[@supports(display:grid)]:Layout").0,
+            "[@supports(display:grid)]:block [@supports(display:grid)]:grid [@supports(display:grid)]:[@media(min-width:768px)]:flex"
+        );
+        assert_eq!(
+            insert_important("[@media(min-width:768px)]:flex"),
+            "[@media(min-width:768px)]:!flex"
+        );
+    }
+
+    #[test]
+    fn test_digit_leading_aliases() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("2col".to_string(), "grid grid-cols-2".to_string());
+        aliases.insert("3xl".to_string(), "text-3xl leading-tight".to_string());
+
+        let mut visitor = create_visitor(aliases, true);
+        let mut element = class_name_element("2col md:3xl w-1/2 2xl:flex", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+
+        // Fractions and digit-leading variants like 2xl: are left alone
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                (
+                    "className".to_string(),
+                    "grid grid-cols-2 md:text-3xl md:leading-tight w-1/2 2xl:flex".to_string()
+                ),
+                ("data-expand".to_string(), "2col md:3xl".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ignored_aliases_pass_through() {
+        let mut config = source_config(true);
+        config.ignore = HashSet::from(["Button".to_string()]);
+        config.fallback = UnknownAliasFallback::Remove;
+        let mut visitor = TailwindExpandVisitor::new(config);
+
+        let mut element = class_name_element("Button lg:!Button ButtonLg", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(
+            attr_pairs(&element),
+            vec![
+                (
+                    "className".to_string(),
+                    "Button lg:!Button h-12 px-6".to_string()
+                ),
+                ("data-expand".to_string(), "ButtonLg".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_class_fallback_pattern() {
+        assert_eq!(
+            transform_jsx(
+                "const a = <div className={props.className || \"Button\"}/>;\n",
+                source_config(true)
+            ),
+            "const a = <div className={props.className || \"px-4 py-2\"} data-expand=\"Button\"/>;\n"
+        );
+        assert_eq!(
+            transform_jsx(
+                "const a = <div className={props[\"className\"] ?? \"lg:ButtonLg\"}/>;\n",
+                source_config(false)
+            ),
+            "const a = <div className={props[\"className\"] ?? \"lg:h-12 lg:px-6\"}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_class_name_expression_containers() {
+        assert_eq!(
+            transform_jsx(
+                "const a = <div className={big ? \"ButtonLg\" : other}/>;\n",
+                source_config(true)
+            ),
+            "const a = <div className={big ? \"h-12 px-6\" : other} data-expand=\"ButtonLg\"/>;\n"
+        );
+        // Plain identifiers and member expressions are left untouched
+        assert_eq!(
+            transform_jsx(
+                "const a = <div className={Button}/>;\n",
+                source_config(false)
+            ),
+            "const a = <div className={Button}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_strict_alias_prefix() {
+        let mut config = source_config(false);
+        config.alias_prefix = Some("@".to_string());
+        config.strict_prefix = true;
+        let mut visitor = TailwindExpandVisitor::new(config);
+
+        let mut element = class_name_element(
+            "@Button lg:@ButtonLg ButtonLg @Typo px-4 Unmarked",
+            DUMMY_SP,
+            DUMMY_SP,
+        );
+        element.visit_mut_with(&mut visitor);
+
+        assert_eq!(
+            attr_str(&element.attrs[0]).value.as_str(),
+            "px-4 py-2 lg:h-12 lg:px-6 h-12 px-6 @Typo px-4 Unmarked"
+        );
+        // Only the marked token errors; px-4 and the unmarked Unmarked stay silent
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnknownAlias);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert!(diagnostics[0].message.contains("\"@Typo\""));
+    }
+
+    #[test]
+    fn test_hash_classes() {
+        let mut config = source_config(false);
+        config.hash_classes = true;
+        let mut visitor = TailwindExpandVisitor::new(config);
+
+        let mut element = class_name_element("Button lg:Button flex", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        let class_name = attr_str(&element.attrs[0]).value.to_string();
+        let classes: Vec<&str> = class_name.split(' ').collect();
+
+        // Deterministic, and distinct per set of utilities
+        assert_eq!(classes[0], hashed_class("px-4 py-2"));
+        assert_eq!(classes[1], hashed_class("lg:px-4 lg:py-2"));
+        assert_eq!(classes[2], "flex");
+        assert_eq!(hashed_class("px-4 py-2"), "tw-14231f64");
+        assert_ne!(classes[0], classes[1]);
+
+        let usage = visitor.usage().unwrap();
+        assert_eq!(
+            usage.classes,
+            BTreeMap::from([
+                (classes[0].to_string(), "px-4 py-2".to_string()),
+                (classes[1].to_string(), "lg:px-4 lg:py-2".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_classes_slot_objects() {
+        assert_eq!(
+            transform_jsx(
+                "const a = <Card classes={{ root: \"Button\", label: [\"ButtonLg\", big && \"Button\"] }}/>;\n",
+                source_config(true)
+            ),
+            "const a = <Card classes={{\n    root: \"px-4 py-2\",\n    label: [\n        \"h-12 px-6\",\n        big && \"px-4 py-2\"\n    ]\n}} data-expand=\"Button ButtonLg\"/>;\n"
+        );
+        // A plain string still works, and className objects are left alone
+        assert_eq!(
+            transform_jsx(
+                "const a = <Card classes=\"Button\" className={{ root: \"Button\" }}/>;\n",
+                source_config(false)
+            ),
+            "const a = <Card classes=\"px-4 py-2\" className={{\n    root: \"Button\"\n}}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_alias_resolution_order() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4".to_string());
+        aliases.insert("hover:Button".to_string(), "hover:underline".to_string());
+        aliases.insert("!Button".to_string(), "!px-8".to_string());
+        aliases.insert("Card".to_string(), "p-2".to_string());
+        let visitor = create_visitor(aliases, false);
+
+        // 1. The full token
+        assert_eq!(visitor.expand_token("hover:Button").0, "hover:underline");
+        assert_eq!(visitor.expand_token("!Button").0, "!px-8");
+        // 2. The part after the last variant; hover:Button only matches whole tokens
+        assert_eq!(visitor.expand_token("lg:hover:Button").0, "lg:hover:px-4");
+        assert_eq!(visitor.expand_token("lg:!Button").0, "lg:!px-4");
+        // 3. The token without its bang
+        assert_eq!(visitor.expand_token("!Card").0, "!p-2");
+    }
+
+    #[test]
+    fn test_expand_css_objects() {
+        let source = "const a = cx({ className: \"Button\", \"&:hover\": { className: \"ButtonLg\", color: \"Button\" } });\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "const a = cx({\n    className: \"Button\",\n    \"&:hover\": {\n        className: \"ButtonLg\",\n        color: \"Button\"\n    }\n});\n"
+        );
+
+        let config = Config {
+            expand_css_objects: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = cx({\n    className: \"px-4 py-2\",\n    \"&:hover\": {\n        className: \"h-12 px-6\",\n        color: \"Button\"\n    }\n});\n"
+        );
+    }
+
+    #[test]
+    fn test_lowercase_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 hover:bg-blue".to_string());
+        let config = Config {
+            aliases: aliases.clone(),
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config);
+        assert_eq!(
+            visitor.expand_class_name("Hover:Button").0,
+            "Hover:px-4 Hover:hover:bg-blue"
+        );
+
+        let config = Config {
+            aliases: aliases.clone(),
+            lowercase_variants: true,
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config);
+        assert_eq!(
+            visitor.expand_class_name("Hover:Button").0,
+            "hover:px-4 hover:bg-blue"
+        );
+        assert_eq!(
+            visitor.expand_class_name("Dark:Custom:Button").0,
+            "dark:Custom:px-4 dark:Custom:hover:bg-blue"
+        );
+        // Normal utilities are left to Tailwind
+        assert_eq!(visitor.expand_class_name("Hover:px-2").0, "Hover:px-2");
+
+        let config = Config {
+            aliases,
+            lowercase_variants: true,
+            known_variants: Some(HashSet::from(["custom".to_string()])),
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config);
+        assert_eq!(
+            visitor.expand_class_name("Custom:Button").0,
+            "custom:px-4 custom:hover:bg-blue"
+        );
+    }
+
+    #[test]
+    fn test_class_name_with_spreads() {
+        let before = "const a = <div {...props} className=\"Button\"/>;\n";
+        assert_eq!(
+            transform_jsx(before, source_config(true)),
+            "const a = <div {...props} className=\"px-4 py-2\" data-expand=\"Button\"/>;\n"
+        );
+
+        // The spread can still override className, and with it data-expand
+        let after =
+            "const a = <div className=\"Button\" id=\"x\" {...props} {...rest} title=\"y\"/>;\n";
+        assert_eq!(
+            transform_jsx(after, source_config(false)),
+            "const a = <div className=\"px-4 py-2\" id=\"x\" {...props} {...rest} title=\"y\"/>;\n"
+        );
+        assert_eq!(
+            transform_jsx(after, source_config(true)),
+            "const a = <div className=\"px-4 py-2\" id=\"x\" data-expand=\"Button\" {...props} {...rest} title=\"y\"/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_collapse_repeated_variants() {
+        assert_eq!(
+            collapse_repeated_variants("hover:hover:bg-red"),
+            "hover:bg-red"
+        );
+        assert_eq!(
+            collapse_repeated_variants("dark:dark:hover:hover:hover:!px-4"),
+            "dark:hover:!px-4"
+        );
+        assert_eq!(
+            collapse_repeated_variants("hover:dark:hover:px-4"),
+            "hover:dark:hover:px-4"
+        );
+        assert_eq!(
+            collapse_repeated_variants("supports-[a:b]:supports-[a:b]:grid"),
+            "supports-[a:b]:grid"
+        );
+        assert!(matches!(
+            collapse_repeated_variants("px-4"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_minify_option() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Link".to_string(), "dark:text-white".to_string());
+        let config = Config {
+            aliases: aliases.clone(),
+            dedupe_variants: Some(false),
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config);
+        assert_eq!(
+            visitor.expand_class_name("dark:Link hover:hover:px-4").0,
+            "dark:dark:text-white hover:hover:px-4"
+        );
+
+        let config = Config {
+            aliases,
+            dedupe_variants: Some(false),
+            minify: true,
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config);
+        assert_eq!(
+            visitor.expand_class_name("dark:Link hover:hover:px-4").0,
+            "dark:text-white hover:px-4"
+        );
+        // Strings without aliases are left alone
+        assert_eq!(
+            visitor.expand_class_name("hover:hover:px-4").0,
+            "hover:hover:px-4"
+        );
+    }
+
+    #[test]
+    fn test_important_aliases() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Reset".to_string(), "m-0 hover:p-0".to_string());
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            important_aliases: HashSet::from(["Reset".to_string()]),
+            ..Default::default()
+        });
+        assert_eq!(visitor.expand_token("Reset").0, "!m-0 hover:!p-0");
+        assert_eq!(visitor.expand_token("lg:Reset").0, "lg:!m-0 lg:hover:!p-0");
+        assert_eq!(visitor.expand_token("!Reset").0, "!m-0 hover:!p-0");
+        assert_eq!(visitor.expand_token("Button").0, "px-4 py-2");
+    }
+
+    #[test]
+    fn test_non_string_class_name_literals() {
+        let source = "const a = <div className={0} class={true} data-x={1}/>;\n";
+        assert_eq!(transform_jsx(source, source_config(true)), source);
+
+        let mut module = parse_module(source);
+        let mut visitor = TailwindExpandVisitor::new(source_config(false));
+        module.visit_mut_with(&mut visitor);
+        assert!(visitor.diagnostics().is_empty());
+
+        let mut module = parse_module(source);
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            strict: true,
+            ..source_config(false)
+        });
+        module.visit_mut_with(&mut visitor);
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::NonStringClassName && d.severity == Severity::Warn));
+        assert!(diagnostics[0].message.contains("number literal"));
+        assert!(diagnostics[1].message.contains("boolean literal"));
+    }
+
+    #[test]
+    fn test_summary_comment() {
+        let source = "const a = <div className=\"Button lg:ButtonLg flex\"/>;\nconst b = <p className=\"Button\"/>;\n";
+        let config = Config {
+            summary_comment: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <div className=\"px-4 py-2 lg:h-12 lg:px-6 flex\"/>;\nconst b = <p className=\"px-4 py-2\"/>; /* tailwind-expand: 4 tokens, 2 aliases: Button, ButtonLg */ \n"
+        );
+
+        let config = Config {
+            summary_comment: true,
+            ..source_config(false)
+        };
+        assert!(
+            transform_jsx("const a = <div className=\"flex\"/>;\n", config)
+                .ends_with("/* tailwind-expand: 1 tokens, 0 aliases */ \n")
+        );
+    }
+
+    #[test]
+    fn test_expand_default_props() {
+        let source = "Button.defaultProps = {\n    className: \"Button\",\n    type: \"Button\"\n};\nButton.props = {\n    className: \"Button\"\n};\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_default_props: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "Button.defaultProps = {\n    className: \"px-4 py-2\",\n    type: \"Button\"\n};\nButton.props = {\n    className: \"Button\"\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_is_color_utility() {
+        for utility in [
+            "bg-red-500",
+            "text-white",
+            "border-x-red-500",
+            "ring-offset-blue-500",
+            "bg-[#fff]",
+        ] {
+            assert!(is_color_utility(utility), "{}", utility);
+        }
+        for utility in [
+            "px-4",
+            "text-lg",
+            "text-2xl",
+            "border",
+            "border-2",
+            "border-x",
+            "ring-2",
+            "bg-red-500/20",
+            "text-center",
+        ] {
+            assert!(!is_color_utility(utility), "{}", utility);
+        }
+    }
+
+    #[test]
+    fn test_alias_opacity() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Button".to_string(),
+            "bg-red-500 text-white border-2 px-4 hover:bg-red-600".to_string(),
+        );
+        let visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(visitor.expand_token("Button/50").0, "Button/50");
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            alias_opacity: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_token("Button/50").0,
+            "bg-red-500/50 text-white/50 border-2 px-4 hover:bg-red-600/50"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:!Button/[.35]").0,
+            "lg:!bg-red-500/[.35] lg:!text-white/[.35] lg:!border-2 lg:!px-4 lg:hover:!bg-red-600/[.35]"
+        );
+        assert_eq!(visitor.expand_token("Button/").0, "Button/");
+        assert_eq!(visitor.expand_token("bg-red-500/50").0, "bg-red-500/50");
+    }
+
+    #[test]
+    fn test_expand_styled_attrs() {
+        let source = "const A = styled.div.attrs(()=>({\n        className: \"Button\",\n        type: \"Button\"\n    }))``;\nconst B = styled(Base).attrs(function() {\n    return {\n        className: \"ButtonLg\"\n    };\n})``;\nconst C = styled.div.attrs({\n    className: \"Button\"\n})``;\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_styled_attrs: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            source
+                .replacen("className: \"Button\"", "className: \"px-4 py-2\"", 2)
+                .replace("className: \"ButtonLg\"", "className: \"h-12 px-6\"")
+        );
+    }
+
+    #[test]
+    fn test_pseudo_element_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "ButtonBadge".to_string(),
+            "relative before:content-['\u{2022}'] before:absolute after:content-['a:)']"
+                .to_string(),
+        );
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("before:ButtonBadge").0,
+            "before:relative before:content-['\u{2022}'] before:absolute before:after:content-['a:)']"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:after:ButtonBadge").0,
+            "hover:after:relative hover:after:before:content-['\u{2022}'] hover:after:before:absolute hover:after:content-['a:)']"
+        );
+        assert_eq!(last_variant_colon("after:content-['(:']"), Some(5));
+        // Quoted parentheses don't unbalance variant groups
+        assert_eq!(
+            visitor
+                .expand_class_name("after:content-[')'] hover:(ButtonBadge)")
+                .0,
+            "after:content-[')'] hover:relative hover:before:content-['\u{2022}'] hover:before:absolute hover:after:content-['a:)']"
+        );
+    }
+
+    #[test]
+    fn test_css_important_in_alias_values() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Button".to_string(),
+            "px-4 hover:py-2 !important".to_string(),
+        );
+        aliases.insert("Card".to_string(), "p-4!important rounded".to_string());
+        aliases.insert("Link".to_string(), "underline".to_string());
+
+        let visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(visitor.expand_token("Button").0, "px-4 hover:py-2");
+        assert_eq!(visitor.expand_token("Card").0, "p-4 rounded");
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::CssImportant
+                && d.severity == Severity::Warn
+                && d.message.contains("removed")));
+        assert!(diagnostics[0].message.contains("\"Button\""));
+
+        let visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            convert_important: true,
+            ..Default::default()
+        });
+        assert_eq!(visitor.expand_token("Button").0, "!px-4 hover:!py-2");
+        assert_eq!(visitor.expand_token("Card").0, "!p-4 rounded");
+        assert_eq!(visitor.expand_token("Link").0, "underline");
+        assert!(visitor.diagnostics()[0].message.contains("converted"));
+    }
+
+    #[test]
+    fn test_expand_style_maps() {
+        let source = "const styles = {\n    primary: \"Button\",\n    sizes: {\n        lg: \"ButtonLg\"\n    }\n};\nconst labels = {\n    primary: \"Button\"\n};\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_style_maps: vec!["styles".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const styles = {\n    primary: \"px-4 py-2\",\n    sizes: {\n        lg: \"h-12 px-6\"\n    }\n};\nconst labels = {\n    primary: \"Button\"\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_filename_include_exclude() {
+        let patterns = |patterns: &[&str]| -> Vec<String> {
+            patterns.iter().map(|pattern| pattern.to_string()).collect()
+        };
+        let include = patterns(&["packages/web/**", "apps/*/src/*.tsx"]);
+        let exclude = patterns(&["**/*.stories.tsx", "**/node_modules/**"]);
+
+        for filename in [
+            "/repo/packages/web/app/page.tsx",
+            "packages/web/button.tsx",
+            "/repo/apps/admin/src/index.tsx",
+            "C:\\repo\\packages\\web\\page.tsx",
+        ] {
+            assert!(
+                is_filename_included(filename, &include, &exclude),
+                "{}",
+                filename
+            );
+        }
+        for filename in [
+            "/repo/packages/api/index.ts",
+            "/repo/packages/web/button.stories.tsx",
+            "/repo/packages/web/node_modules/lib/index.js",
+            "/repo/apps/admin/src/nested/index.tsx",
+            "/repo/mypackages/web/page.tsx",
+        ] {
+            assert!(
+                !is_filename_included(filename, &include, &exclude),
+                "{}",
+                filename
+            );
+        }
+
+        // No include patterns means every file that isn't excluded
+        assert!(is_filename_included("/repo/any.tsx", &[], &exclude));
+        assert!(!is_filename_included("/repo/a.stories.tsx", &[], &exclude));
+        assert!(is_filename_included(
+            "/abs/a.tsx",
+            &patterns(&["/abs/*.tsx"]),
+            &[]
+        ));
+        assert!(!is_filename_included(
+            "/other/abs/a.tsx",
+            &patterns(&["/abs/*.tsx"]),
+            &[]
+        ));
+    }
+
+    #[test]
+    fn test_class_hooks() {
+        let source = "const a = useClasses(\"Button ButtonLg\", big && \"ButtonLg\");\nconst b = hooks.useStyles(\"Button\");\nconst c = useOther(\"Button\");\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            class_hooks: vec!["useClasses".to_string(), "hooks.useStyles".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = useClasses(\"px-4 py-2 h-12 px-6\", big && \"h-12 px-6\");\nconst b = hooks.useStyles(\"px-4 py-2\");\nconst c = useOther(\"Button\");\n"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_utilities_in_alias_value() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 px-4 py-2 px-4".to_string());
+        let compiled = compile_aliases(aliases.clone());
+        assert_eq!(compiled["Button"], vec!["px-4", "py-2"]);
+
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("hover:Button").0,
+            "hover:px-4 hover:py-2"
+        );
+        // Repeats across tokens are still kept without the dedupe option
+        assert_eq!(visitor.expand_class_name("Button px-4").0, "px-4 py-2 px-4");
+    }
+
+    #[test]
+    fn test_render_prop_arrow_bodies() {
+        let source = "const a = <Toggle>{(state)=><div className={state.active ? \"ButtonLg\" : \"Button\"}/>}</Toggle>;\nconst b = <List render={(item)=>{\n    return <li className=\"Button\"/>;\n}}/>;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(true)),
+            "const a = <Toggle>{(state)=><div className={state.active ? \"h-12 px-6\" : \"px-4 py-2\"} data-expand=\"Button ButtonLg\"/>}</Toggle>;\nconst b = <List render={(item)=>{\n    return <li className=\"px-4 py-2\" data-expand=\"Button\"/>;\n}}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_variant_segments() {
+        assert_eq!(
+            sort_variant_segments("dark:hover:bg-red"),
+            "hover:dark:bg-red"
+        );
+        assert_eq!(
+            sort_variant_segments("lg:dark:focus:!px-4"),
+            "focus:dark:lg:!px-4"
+        );
+        // Unknown variants stay put, known ones sort on each side of them
+        assert_eq!(
+            sort_variant_segments("lg:hover:[&>*]:md:focus:p-2"),
+            "hover:lg:[&>*]:focus:md:p-2"
+        );
+        assert!(matches!(
+            sort_variant_segments("hover:dark:p-2"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(sort_variant_segments("p-2"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_normalize_variant_order() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 lg:py-2".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            normalize_variant_order: true,
+            ..Default::default()
+        });
+        let expected = "hover:dark:px-4 hover:dark:lg:py-2";
+        assert_eq!(visitor.expand_class_name("dark:hover:Button").0, expected);
+        assert_eq!(visitor.expand_class_name("hover:dark:Button").0, expected);
+        // Utilities written directly are left as-is
+        assert_eq!(
+            visitor.expand_class_name("Button dark:hover:p-2").0,
+            "px-4 lg:py-2 dark:hover:p-2"
+        );
+
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_class_name("dark:hover:Button").0,
+            "dark:hover:px-4 dark:hover:lg:py-2"
+        );
+    }
+
+    #[test]
+    fn test_responsive_shorthands() {
+        assert_eq!(
+            expand_responsive_shorthands("p-{2,md:4,lg:6} flex").as_deref(),
+            Some("p-2 md:p-4 lg:p-6 flex")
+        );
+        assert_eq!(
+            expand_responsive_shorthands("-mt-{1,md:hover:2} text-{sm,lg:[15px]}").as_deref(),
+            Some("-mt-1 md:hover:-mt-2 text-sm lg:text-[15px]")
+        );
+        assert_eq!(expand_responsive_shorthands("p-2 md:p-4"), None);
+        // Braces inside arbitrary values aren't shorthands
+        assert_eq!(
+            expand_responsive_shorthands("[&_{x}]:p-2 content-['{a}']").as_deref(),
+            None
+        );
+
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Section".to_string(),
+            "p-{2,md:4,lg:6} gap-{md:2}".to_string(),
+        );
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("dark:Section").0,
+            "dark:p-2 dark:md:p-4 dark:lg:p-6 dark:md:gap-2"
+        );
+    }
+
+    #[test]
+    fn test_max_output_tokens() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("Card".to_string(), "p-4 rounded shadow".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            max_output_tokens: Some(4),
+            ..Default::default()
+        });
+
+        let mut element = class_name_element("Button flex Card Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(
+            attr_str(&element.attrs[0]).value.as_str(),
+            "px-4 py-2 flex Card Button"
+        );
+        assert_eq!(visitor.stats().expanded, 1);
+        assert_eq!(visitor.stats().capped, 2);
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::OutputLimit);
+        assert!(diagnostics[0]
+            .message
+            .contains("more than 4 classes; 2 aliases"));
+
+        // Under the limit nothing is reported, including for cached strings
+        let mut element = class_name_element("Card", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(
+            attr_str(&element.attrs[0]).value.as_str(),
+            "p-4 rounded shadow"
+        );
+        let mut element = class_name_element("Button flex Card Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert_eq!(visitor.diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn test_debug_modes() {
+        let source = "const a = <div className=\"Button\"/>;\n";
+        for (debug_mode, debug_attribute, expected) in [
+            (
+                DebugMode::DataAttr,
+                None,
+                "const a = <div className=\"px-4 py-2\" data-expand=\"Button\"/>;\n",
+            ),
+            (
+                DebugMode::DataAttr,
+                Some("x-expand"),
+                "const a = <div className=\"px-4 py-2\" x-expand=\"Button\"/>;\n",
+            ),
+            (
+                DebugMode::Comment,
+                None,
+                "const a = <div className=\"px-4 py-2\">{ /* tailwind-expand: Button */ }</div>;\n",
+            ),
+            (
+                DebugMode::None,
+                None,
+                "const a = <div className=\"px-4 py-2\"/>;\n",
+            ),
+        ] {
+            let config = Config {
+                debug_mode,
+                debug_attribute: debug_attribute.map(str::to_string),
+                ..source_config(true)
+            };
+            assert_eq!(transform_jsx(source, config), expected, "{:?}", debug_mode);
+        }
+
+        let config = Config {
+            debug_mode: DebugMode::Comment,
+            ..source_config(true)
+        };
+        assert_eq!(
+            transform_jsx("const a = <ul className=\"Button\"><li className=\"ButtonLg\">x</li></ul>;\n", config),
+            "const a = <ul className=\"px-4 py-2\">{ /* tailwind-expand: Button */ }<li className=\"h-12 px-6\">{ /* tailwind-expand: ButtonLg */ }x</li></ul>;\n"
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 hover:bg-blue".to_string());
+        let visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(
+            visitor.expand_token("HOVER:Button").0,
+            "HOVER:px-4 HOVER:hover:bg-blue"
+        );
+
+        let mut config = Config {
+            aliases: aliases.clone(),
+            case_insensitive_variants: true,
+            known_variants: Some(HashSet::from(["hover".to_string()])),
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config.clone());
+        assert_eq!(
+            visitor.expand_token("HOVER:Button").0,
+            "HOVER:px-4 HOVER:bg-blue"
+        );
+        // Alias names stay case-sensitive
+        assert_eq!(visitor.expand_token("hover:button").0, "hover:button");
+        let mut element = class_name_element("HOVER:Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
+        assert!(visitor.diagnostics().is_empty());
+
+        // Together with lowercase_variants the output is valid Tailwind
+        config.lowercase_variants = true;
+        let visitor = TailwindExpandVisitor::new(config);
+        assert_eq!(
+            visitor.expand_token("HOVER:Button").0,
+            "hover:px-4 hover:bg-blue"
+        );
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Variants, including arbitrary ones whose brackets hold colons
+        fn variant() -> impl Strategy<Value = &'static str> {
+            prop::sample::select(vec![
+                "hover",
+                "dark",
+                "lg",
+                "group-hover/sidebar",
+                "data-[state=open]",
+                "supports-[display:grid]",
+                "[&:hover]",
+                "max-[900px]",
+                "[@media(min-width:768px)]",
+            ])
+        }
+
+        /// Base utilities that aren't aliases, with bracketed values, bangs and negatives
+        fn utility() -> impl Strategy<Value = &'static str> {
+            prop::sample::select(vec![
+                "px-4",
+                "-mt-2",
+                "!grid",
+                "flex!",
+                "bg-[url(a:b.png)]",
+                "content-['a:)']",
+                "[mask-type:luminance]",
+                "grid-cols-[1fr_auto]",
+            ])
+        }
+
+        fn prefix() -> impl Strategy<Value = String> {
+            prop::collection::vec(variant(), 0..4).prop_map(|variants| {
+                variants
+                    .iter()
+                    .map(|variant| format!("{}:", variant))
+                    .collect()
+            })
+        }
+
+        fn visitor() -> TailwindExpandVisitor {
+            let mut aliases = AliasMap::new();
+            aliases.insert(
+                "Button".to_string(),
+                "px-4 hover:bg-[url(a:b.png)] -mt-2".to_string(),
+            );
+            create_visitor(aliases, false)
+        }
+
+        // Deterministic, so failures reproduce without a persistence file
+        fn config() -> ProptestConfig {
+            ProptestConfig {
+                failure_persistence: None,
+                ..ProptestConfig::default()
+            }
+        }
+
+        proptest! {
+            #![proptest_config(config())]
+
+            #[test]
+            fn alias_name_is_extracted(prefix in prefix(), bang in any::<bool>(), leading in any::<bool>()) {
+                let token = match (bang, leading) {
+                    (true, true) => format!("!{}Button", prefix),
+                    (true, false) => format!("{}!Button", prefix),
+                    _ => format!("{}Button", prefix),
+                };
+                let visitor = visitor();
+                prop_assert_eq!(visitor.alias_of(&token), "Button");
+
+                // Same as applying the variants and bang to each utility by hand
+                let expected: Vec<String> = ["px-4", "hover:bg-[url(a:b.png)]", "-mt-2"]
+                    .iter()
+                    .map(|utility| {
+                        let prefixed = apply_variant_prefix(&prefix, utility);
+                        if bang { insert_important(&prefixed) } else { prefixed }
                     })
+                    .collect();
+                let (expanded, alias) = visitor.expand_token(&token);
+                prop_assert_eq!(expanded, expected.join(" "));
+                prop_assert_eq!(alias, Some(token));
+            }
+
+            #[test]
+            fn brackets_are_never_split(prefix in prefix(), utility in utility()) {
+                let token = format!("{}{}", prefix, utility);
+                let mut depth = 0i32;
+                let mut quoted = false;
+                let colons: Vec<usize> = variant_colons(&token).collect();
+                for (index, byte) in token.bytes().enumerate() {
+                    match byte {
+                        b'\'' if depth > 0 => quoted = !quoted,
+                        _ if quoted => {}
+                        b'[' | b'(' => depth += 1,
+                        b']' | b')' => depth -= 1,
+                        b':' => prop_assert_eq!(colons.contains(&index), depth == 0),
+                        _ => {}
+                    }
+                }
+                // The segments rebuild the prefix exactly
+                let segments: String = variant_segments(&token)
+                    .map(|segment| format!("{}:", segment))
+                    .collect();
+                prop_assert_eq!(segments, prefix);
+            }
+
+            #[test]
+            fn non_alias_tokens_round_trip(prefix in prefix(), utilities in prop::collection::vec(utility(), 1..5)) {
+                let class_name = utilities
+                    .iter()
+                    .map(|utility| format!("{}{}", prefix, utility))
                     .collect::<Vec<_>>()
                     .join(" ");
+                let mut visitor = visitor();
+                for token in class_name.split(' ') {
+                    prop_assert_eq!(visitor.expand_token(token), (token.to_string(), None));
+                }
+                let (expanded, aliases) = visitor.expand_class_name(&class_name);
+                prop_assert!(matches!(expanded, Cow::Borrowed(_)));
+                prop_assert!(aliases.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_alias_names_inside_tokens() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 hover:py-2".to_string());
+        let visitor = create_visitor(aliases.clone(), false);
+        // Accidental: alias names inside arbitrary values are never expanded
+        for token in [
+            "grid-cols-[var(--Button)]",
+            "[--Button:1px]",
+            "bg-[url(Button.png)]",
+            "[&>li]:{{Button}}",
+        ] {
+            assert_eq!(visitor.expand_token(token), (token.to_string(), None));
+        }
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            alias_interpolation: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_token("grid-cols-[var(--Button)]").0,
+            "grid-cols-[var(--Button)]"
+        );
+        // Deliberate: {{Name}} expands per utility, keeping the text around it
+        assert_eq!(
+            visitor.expand_token("[&>li]:{{Button}}").0,
+            "[&>li]:px-4 [&>li]:hover:py-2"
+        );
+        assert_eq!(
+            visitor.expand_token("hover:{{Button}}!").0,
+            "hover:px-4! hover:py-2!"
+        );
+        assert_eq!(
+            visitor.expand_token("group-{{Button}}").0,
+            "group-px-4 group-hover:py-2"
+        );
+        let (expanded, aliases) = visitor.expand_class_name("flex {{Button}} {{Missing}}");
+        assert_eq!(expanded, "flex px-4 hover:py-2 {{Missing}}");
+        assert_eq!(aliases.iter().collect::<Vec<_>>(), ["{{Button}}"]);
+    }
+
+    #[test]
+    fn test_diagnostics_report() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4".to_string());
+        aliases.insert("Empty".to_string(), " ".to_string());
+        let source = "const a = <div className=\"Button Missing\" />;\n<div className={1} />;";
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            fallback: UnknownAliasFallback::Keep,
+            strict: true,
+            ..Default::default()
+        });
+        transform_program(Program::Module(parse_module(source)), &mut visitor);
+
+        // Spans are byte positions starting at 1, on a single line here
+        let records: Vec<DiagnosticRecord> = visitor
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| DiagnosticRecord::new(diagnostic, |pos| (1, pos.0 as usize)))
+            .collect();
+        let kinds: Vec<DiagnosticKind> = records.iter().map(|record| record.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                DiagnosticKind::EmptyAlias,
+                DiagnosticKind::UnknownAlias,
+                DiagnosticKind::NonStringClassName
+            ]
+        );
+
+        let report = module_report("b.tsx", &records);
+        let parsed: BTreeMap<String, Vec<DiagnosticRecord>> =
+            serde_json::from_str(&report).unwrap();
+        assert_eq!(parsed["b.tsx"], records);
+
+        let value: serde_json::Value = serde_json::from_str(&report).unwrap();
+        // Configuration problems carry no location
+        assert_eq!(
+            value["b.tsx"][0],
+            serde_json::json!({
+                "kind": "empty-alias",
+                "severity": "warn",
+                "message": records[0].message,
+            })
+        );
+        assert_eq!(value["b.tsx"][1]["kind"], "unknown-alias");
+        assert_eq!(value["b.tsx"][1]["line"], 1);
+        assert_eq!(
+            value["b.tsx"][1]["column"],
+            source.find("Missing").unwrap() + 1
+        );
+        assert_eq!(value["b.tsx"][2]["kind"], "non-string-class-name");
+    }
+
+    #[test]
+    fn test_fallback_diagnostics_path() {
+        // Configs that don't deserialize still name where their error goes
+        let json = "{\"diagnosticsPath\": \"diagnostics\", \"aliases\": []}";
+        assert!(Config::parse(Some(json)).is_err());
+        assert_eq!(
+            fallback_diagnostics_path(json).as_deref(),
+            Some("diagnostics")
+        );
+        assert_eq!(fallback_diagnostics_path("{\"aliases\": []}"), None);
+        assert_eq!(fallback_diagnostics_path("{\"diagnosticsPath\": 1}"), None);
+        assert_eq!(fallback_diagnostics_path("not json"), None);
+    }
+
+    #[test]
+    fn test_class_hooks_nested_expressions() {
+        let source = "const a = clsx([\"Button\", active ? \"ButtonLg\" : \"flex\", [big && (\"ButtonLg\")]], cond === \"Button\" || \"Button\");\n";
+        let expected = "const a = clsx([\n    \"px-4 py-2\",\n    active ? \"h-12 px-6\" : \"flex\",\n    [\n        big && (\"h-12 px-6\")\n    ]\n], cond === \"Button\" || \"px-4 py-2\");\n";
+        for expand_arrays in [false, true] {
+            let config = Config {
+                class_hooks: vec!["clsx".to_string()],
+                expand_arrays,
+                ..source_config(false)
+            };
+            let mut visitor = TailwindExpandVisitor::new(config.clone());
+            assert_eq!(transform_jsx(source, config), expected);
+
+            // Every alias literal is expanded exactly once
+            transform_program(Program::Module(parse_module(source)), &mut visitor);
+            assert_eq!(visitor.stats().expanded, 4);
+        }
+    }
+
+    #[test]
+    fn test_theme_values() {
+        let theme: HashMap<String, String> = [
+            ("colors.brand", "#0af"),
+            ("spacing.gutter", "1rem"),
+            ("fontFamily.sans", "Inter, sans-serif"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+        assert_eq!(
+            resolve_theme("text-[theme(colors.brand)]", &theme).as_deref(),
+            Some("text-[#0af]")
+        );
+        assert_eq!(
+            resolve_theme("p-[calc(theme('spacing.gutter')*2)]", &theme).as_deref(),
+            Some("p-[calc(1rem*2)]")
+        );
+        assert_eq!(
+            resolve_theme("font-[theme(fontFamily.sans)]", &theme).as_deref(),
+            Some("font-[Inter,_sans-serif]")
+        );
+        // Unknown keys are left for Tailwind
+        assert_eq!(resolve_theme("bg-[theme(colors.other)]", &theme), None);
+        assert_eq!(resolve_theme("px-4", &theme), None);
+
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Brand".to_string(),
+            "text-[theme(colors.brand)] bg-[theme(colors.other)] hover:border-[theme(colors.brand)]"
+                .to_string(),
+        );
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            theme,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_class_name("lg:Brand").0,
+            "lg:text-[#0af] lg:bg-[theme(colors.other)] lg:hover:border-[#0af]"
+        );
+    }
+
+    #[test]
+    fn test_resolve_conflicts() {
+        let safelist = HashSet::new();
+        assert_eq!(resolve_utility_conflicts("flex grid", &safelist), "grid");
+        assert_eq!(resolve_utility_conflicts("grid flex", &safelist), "flex");
+        assert_eq!(
+            resolve_utility_conflicts(
+                "block p-2 relative hidden float-left absolute float-none m-1",
+                &safelist
+            ),
+            "p-2 hidden absolute float-none m-1"
+        );
+        // Scoped by variants and important modifier; other utilities never conflict
+        assert_eq!(
+            resolve_utility_conflicts(
+                "flex md:flex !flex md:grid grid! hover:hidden p-2 p-4",
+                &safelist
+            ),
+            "flex md:grid grid! hover:hidden p-2 p-4"
+        );
+        // Stable across runs
+        let once = resolve_utility_conflicts("flex md:block inline md:static md:fixed", &safelist);
+        assert_eq!(once, "md:block inline md:fixed");
+        assert_eq!(resolve_utility_conflicts(&once, &safelist), once);
+
+        let mut aliases = AliasMap::new();
+        aliases.insert("Row".to_string(), "flex gap-2".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            resolve_conflicts: true,
+            ..Default::default()
+        });
+        assert_eq!(visitor.expand_class_name("Row grid").0, "gap-2 grid");
+        assert_eq!(visitor.expand_class_name("grid Row").0, "flex gap-2");
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(visitor.expand_class_name("Row grid").0, "flex gap-2 grid");
+    }
+
+    #[test]
+    fn test_jsx_attr_special_characters() {
+        // Entities are decoded when parsing; backslashes are literal in JSX strings
+        for (source, expected) in [
+            (
+                "const a = <div className=\"Button [&>*]:p-2 bg-[url('a&amp;b.png')]\" />;\n",
+                "const a = <div className=\"px-4 py-2 [&>*]:p-2 bg-[url('a&b.png')]\"/>;\n",
+            ),
+            (
+                "const a = <div className=\"Button content-['&quot;'] after:content-['&amp;amp;']\" />;\n",
+                "const a = <div className=\"px-4 py-2 content-['&quot;'] after:content-['&amp;amp;']\"/>;\n",
+            ),
+            (
+                "const a = <div className='Button content-[\"x\"]' />;\n",
+                "const a = <div className='px-4 py-2 content-[\"x\"]'/>;\n",
+            ),
+            (
+                "const a = <div className=\"Button content-['\\2192']\" />;\n",
+                "const a = <div className=\"px-4 py-2 content-['\\2192']\"/>;\n",
+            ),
+            // Expression strings use JS escapes as usual
+            (
+                "const a = <div className={\"Button content-['\\\"']\"} />;\n",
+                "const a = <div className={\"px-4 py-2 content-['\\\"']\"}/>;\n",
+            ),
+        ] {
+            let output = transform_jsx(source, source_config(false));
+            assert_eq!(output, expected);
+            // The output parses back to the same class string
+            let reparsed = transform_jsx(&output, Config::default());
+            assert_eq!(reparsed, output);
+        }
+
+        assert_eq!(
+            &*jsx_attr_raw("a&b &c; &#38; &x"),
+            "\"a&b &amp;c; &amp;#38; &x\""
+        );
+        assert_eq!(&*jsx_attr_raw("['\"']"), "\"['&quot;']\"");
+    }
+
+    #[test]
+    fn test_group_variants() {
+        assert_eq!(
+            group_utilities_by_variant("hover:a b lg:c hover:d e lg:hover:f"),
+            "b e hover:a hover:d lg:c lg:hover:f"
+        );
+        assert_eq!(
+            group_utilities_by_variant("[&>li]:p-2 flex"),
+            "flex [&>li]:p-2"
+        );
+
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Button".to_string(),
+            "px-4 hover:bg-blue-600 py-2".to_string(),
+        );
+        aliases.insert(
+            "Card".to_string(),
+            "rounded lg:p-6 hover:shadow".to_string(),
+        );
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            group_variants: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_class_name("lg:Button Card flex").0,
+            "rounded flex lg:px-4 lg:py-2 lg:p-6 lg:hover:bg-blue-600 hover:shadow"
+        );
+        // Unlike sort, the base utilities keep their written order
+        assert_eq!(
+            visitor.expand_class_name("Button text-sm block").0,
+            "px-4 py-2 text-sm block hover:bg-blue-600"
+        );
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_class_name("Button text-sm").0,
+            "px-4 hover:bg-blue-600 py-2 text-sm"
+        );
+    }
+
+    #[test]
+    fn test_empty_alias_leaves_no_whitespace() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Noop".to_string(), "".to_string());
+        aliases.insert("Button".to_string(), "px-4".to_string());
+        for whitespace in [WhitespacePolicy::Single, WhitespacePolicy::Original] {
+            let mut visitor = TailwindExpandVisitor::new(Config {
+                aliases: aliases.clone(),
+                whitespace,
+                ..Default::default()
+            });
+            for (class_name, expected) in [
+                ("flex Noop Button", "flex px-4"),
+                ("Noop flex Button", "flex px-4"),
+                ("flex Button Noop", "flex px-4"),
+                (" flex\tNoop  Noop Button ", "flex px-4"),
+                ("hover:Noop Noop flex", "flex"),
+                ("Noop", ""),
+            ] {
+                assert_eq!(visitor.expand_class_name(class_name).0, expected);
+            }
+        }
+
+        // The preserve policy keeps the spacing the token sat in
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            whitespace: WhitespacePolicy::Preserve,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_class_name("flex Noop\tButton").0,
+            "flex \tpx-4"
+        );
+    }
+
+    #[test]
+    fn test_collapse() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        aliases.insert("ButtonLg".to_string(), "px-4 py-2 text-lg".to_string());
+        aliases.insert("Card".to_string(), "rounded shadow".to_string());
+        aliases.insert("Flex".to_string(), "flex".to_string());
+        let config = Config {
+            aliases,
+            collapse: true,
+            debug: true,
+            ..Default::default()
+        };
+        let mut visitor = TailwindExpandVisitor::new(config.clone());
+        assert_eq!(visitor.expand_class_name("px-4 py-2").0, "Button");
+        assert_eq!(
+            visitor.expand_class_name("flex px-4 py-2 mt-1").0,
+            "flex Button mt-1"
+        );
+        // Longest match wins
+        assert_eq!(
+            visitor
+                .expand_class_name("px-4 py-2 text-lg rounded shadow")
+                .0,
+            "ButtonLg Card"
+        );
+        // Conservative: order and contiguity must match
+        for class_name in [
+            "py-2 px-4",
+            "px-4 mt-1 py-2",
+            "px-4",
+            "hover:px-4 hover:py-2",
+            "Button",
+        ] {
+            assert!(matches!(
+                visitor.expand_class_name(class_name).0,
+                Cow::Borrowed(_)
+            ));
+        }
+
+        assert_eq!(
+            transform_jsx("const a = <div className=\"px-4 py-2 flex\" />;", config),
+            "const a = <div className=\"Button flex\" data-expand=\"Button\"/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_fallback_aliases() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-6 py-3".to_string());
+        let mut fallback_aliases = AliasMap::new();
+        fallback_aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+        fallback_aliases.insert("Card".to_string(), "rounded shadow".to_string());
+        let mut component_aliases = HashMap::new();
+        component_aliases.insert(
+            "Hero".to_string(),
+            ComponentAlias {
+                class: "h-96".to_string(),
+                style: None,
+            },
+        );
+        fallback_aliases.insert("Hero".to_string(), "h-64".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            component_aliases,
+            fallback_aliases,
+            ..Default::default()
+        });
+        // Found only in the fallback layer, with variants as usual
+        assert_eq!(
+            visitor.expand_class_name("hover:Card").0,
+            "hover:rounded hover:shadow"
+        );
+        // Overridden by the primary layers
+        assert_eq!(visitor.expand_class_name("Button Hero").0, "px-6 py-3 h-96");
+
+        let config: Config = serde_json::from_str(
+            r#"{"fallbackAliases": {"Card": ["rounded", "shadow"], "Bad Key": "p-1"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.fallback_aliases["Card"], "rounded shadow");
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::AliasKeyWithWhitespace("Bad Key".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_important_complex_utilities() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Shapes".to_string(),
+            "bg-[#fff]/50 text-[length:14px] -top-[10px] hover:bg-red-500/[0.3] [mask-type:luminance] content-['a!b'] supports-[display:grid]:grid"
+                .to_string(),
+        );
+        let visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_token("!Shapes").0,
+            "!bg-[#fff]/50 !text-[length:14px] !-top-[10px] hover:!bg-red-500/[0.3] ![mask-type:luminance] !content-['a!b'] supports-[display:grid]:!grid"
+        );
+        assert_eq!(
+            visitor.expand_token("lg:!Shapes").0,
+            "lg:!bg-[#fff]/50 lg:!text-[length:14px] lg:!-top-[10px] lg:hover:!bg-red-500/[0.3] lg:![mask-type:luminance] lg:!content-['a!b'] lg:supports-[display:grid]:!grid"
+        );
+
+        for (utility, expected) in [
+            ("bg-[#fff]/50", "!bg-[#fff]/50"),
+            ("text-[length:14px]", "!text-[length:14px]"),
+            ("-top-[10px]", "!-top-[10px]"),
+            ("md:-top-[calc(100%-1rem)]", "md:!-top-[calc(100%-1rem)]"),
+            (
+                "[&:nth-child(3)]:-top-[10px]",
+                "[&:nth-child(3)]:!-top-[10px]",
+            ),
+            ("after:content-['x:y']", "after:!content-['x:y']"),
+        ] {
+            assert_eq!(insert_important(utility), expected);
+        }
+    }
+
+    #[test]
+    fn test_expand_const_objects() {
+        let source = "const sizes = {\n    size: {\n        lg: \"ButtonLg\",\n        sm: [\n            \"Button\",\n            \"flex\"\n        ]\n    },\n    base: \"Button\"\n} as const;\nconst other = {\n    base: \"Button\"\n};\nconst wrapped = ({\n    base: \"Button\"\n}) as const;\nconst name = \"Button\" as const;\n";
+        assert_eq!(transform_tsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_const_objects: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_tsx(source, config),
+            "const sizes = {\n    size: {\n        lg: \"h-12 px-6\",\n        sm: [\n            \"px-4 py-2\",\n            \"flex\"\n        ]\n    },\n    base: \"px-4 py-2\"\n} as const;\nconst other = {\n    base: \"Button\"\n};\nconst wrapped = ({\n    base: \"px-4 py-2\"\n}) as const;\nconst name = \"Button\" as const;\n"
+        );
+    }
+
+    #[test]
+    fn test_important_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Button".to_string(),
+            "px-4 print:hidden hover:bg-blue-600".to_string(),
+        );
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            important_variants: ["print".to_string()].into_iter().collect(),
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_class_name("print:Button").0,
+            "print:!px-4 print:!hidden print:hover:!bg-blue-600"
+        );
+        // Only utilities carrying the variant, whether from the token or the alias
+        assert_eq!(
+            visitor.expand_class_name("hover:Button").0,
+            "hover:px-4 hover:print:!hidden hover:bg-blue-600"
+        );
+        assert_eq!(
+            visitor.expand_class_name("!print:Button").0,
+            "print:!px-4 print:!hidden print:hover:!bg-blue-600"
+        );
+        // Utilities written outside an alias are left alone
+        assert_eq!(visitor.expand_class_name("print:px-2").0, "print:px-2");
+
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_class_name("print:Button").0,
+            "print:px-4 print:hidden print:hover:bg-blue-600"
+        );
+    }
+
+    #[test]
+    fn test_shared_alias_keys() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button|Btn".to_string(), "px-4 py-2".to_string());
+        aliases.insert("Card|Panel|Btn".to_string(), "rounded".to_string());
+        aliases.insert("Panel".to_string(), "p-6".to_string());
+        aliases.insert("/btn-(sm|lg)/".to_string(), "text-sm".to_string());
+
+        let split = split_alias_keys(aliases.clone());
+        assert_eq!(split["Button"], "px-4 py-2");
+        assert_eq!(split["Btn"], "px-4 py-2");
+        assert_eq!(split["Card"], "rounded");
+        assert_eq!(split["Panel"], "p-6");
+        assert!(split.contains_key("/btn-(sm|lg)/"));
+        assert!(!split.contains_key("Button|Btn"));
+
+        let mut visitor = create_visitor(aliases.clone(), false);
+        assert_eq!(
+            visitor.expand_class_name("Button hover:Btn").0,
+            "px-4 py-2 hover:px-4 hover:py-2"
+        );
+
+        aliases.insert("Empty|".to_string(), "p-1".to_string());
+        let config = Config {
+            aliases,
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), Err(ConfigError::EmptyAliasKey));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(Config::parse(None).unwrap().aliases, AliasMap::new());
+        assert_eq!(Config::parse(Some("  ")).unwrap().aliases, AliasMap::new());
+        let config =
+            Config::parse(Some(r#"{"aliases": {"Button": "px-4"}, "debug": true}"#)).unwrap();
+        assert_eq!(config.aliases["Button"], "px-4");
+        assert!(config.debug);
+
+        // Broken JSON and wrongly typed options are reported instead of ignored
+        for json in [
+            r#"{"aliases": {"Button": "px-4"}"#,
+            r#"{"debug": "yes"}"#,
+            "42",
+        ] {
+            let error = Config::parse(Some(json)).unwrap_err();
+            assert!(matches!(error, ConfigError::Malformed(_)));
+            assert!(error
+                .to_string()
+                .starts_with("could not read plugin options: "));
+        }
+    }
+
+    #[test]
+    fn test_class_hooks_object_keys() {
+        let source = "const a = clsx({\n    Button: true,\n    \"ButtonLg\": isBig,\n    flex: open,\n    [dynamic]: true,\n    ...rest\n}, [\n    \"Button\",\n    {\n        Button: active\n    }\n]);\nconst b = other({\n    Button: true\n});\n";
+        let expected = "const a = clsx({\n    \"px-4\": true,\n    \"py-2\": true,\n    \"h-12\": isBig,\n    \"px-6\": isBig,\n    flex: open,\n    [dynamic]: true,\n    ...rest\n}, [\n    \"px-4 py-2\",\n    {\n        \"px-4\": active,\n        \"py-2\": active\n    }\n]);\nconst b = other({\n    Button: true\n});\n";
+        for expand_arrays in [false, true] {
+            let config = Config {
+                class_hooks: vec!["clsx".to_string()],
+                expand_arrays,
+                ..source_config(false)
+            };
+            assert_eq!(transform_jsx(source, config), expected);
+        }
+    }
+
+    #[test]
+    fn test_class_hooks_object_keys_with_side_effects() {
+        let source = "const a = clsx({\n    Button: track(),\n    ButtonLg: !props.big,\n    Button: i++\n});\n";
+        let config = Config {
+            class_hooks: vec!["clsx".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config.clone()),
+            "const a = clsx({\n    Button: track(),\n    \"h-12\": !props.big,\n    \"px-6\": !props.big,\n    Button: i++\n});\n"
+        );
+
+        let mut visitor = TailwindExpandVisitor::new(config);
+        transform_program(Program::Module(parse_module(source)), &mut visitor);
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::ImpureClassValue && d.severity == Severity::Warn));
+    }
+
+    #[test]
+    fn test_never_dedupe_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert(
+            "Button".to_string(),
+            "hover:bg-red-500 dark:text-white dark:hover:underline".to_string(),
+        );
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            never_dedupe_variants: ["hover".to_string()].into_iter().collect(),
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_class_name("hover:Button").0,
+            "hover:hover:bg-red-500 hover:dark:text-white hover:dark:hover:underline"
+        );
+        // Other variants still dedupe, up to a listed one
+        assert_eq!(
+            visitor.expand_class_name("dark:hover:Button").0,
+            "dark:hover:hover:bg-red-500 dark:hover:text-white dark:hover:hover:underline"
+        );
+
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(
+            visitor.expand_class_name("dark:hover:Button").0,
+            "dark:hover:bg-red-500 dark:hover:text-white dark:hover:underline"
+        );
+    }
+
+    #[test]
+    fn test_array_class_name() {
+        let source = "const a = <div className={[\"Button\", big && \"ButtonLg\", \"flex\"]}/>;\n";
+        for expand_arrays in [false, true] {
+            let config = Config {
+                expand_arrays,
+                ..source_config(true)
+            };
+            let mut visitor = TailwindExpandVisitor::new(config.clone());
+            assert_eq!(
+                transform_jsx(source, config),
+                "const a = <div className={[\n    \"px-4 py-2\",\n    big && \"h-12 px-6\",\n    \"flex\"\n]} data-expand=\"Button ButtonLg\"/>;\n"
+            );
+
+            // Each element is expanded once, whether or not every array is
+            transform_program(Program::Module(parse_module(source)), &mut visitor);
+            assert_eq!(visitor.stats().expanded, 2);
+        }
+    }
+
+    #[test]
+    fn test_expansion_map_variant_scope() {
+        let source =
+            "const a = <div data-tw-variant=\"dark\"><p className=\"flex lg:Button\"/></div>;\n";
+        let config = Config {
+            expansion_map_path: Some("expansions.json".to_string()),
+            variant_scope_attribute: Some("data-tw-variant".to_string()),
+            ..source_config(false)
+        };
+        let mut visitor = TailwindExpandVisitor::new(config);
+        transform_program(Program::Module(parse_module(source)), &mut visitor);
+
+        let expansions = visitor.expansions().unwrap();
+        assert_eq!(expansions.len(), 1);
+        assert_eq!(expansions[0].token, "lg:Button");
+        assert_eq!(expansions[0].alias, "Button");
+        assert_eq!(expansions[0].utilities, ["dark:lg:px-4", "dark:lg:py-2"]);
+        let offset = source.find("lg:Button").unwrap() as u32 + 1;
+        assert_eq!(expansions[0].span.lo, BytePos(offset));
+    }
+
+    #[test]
+    fn test_expansion_map() {
+        let source = "const a = <div className=\"flex lg:Button\" />;\nconst b = <div className={big ? \"!ButtonLg\" : \"hover:(Button text-sm)\"} />;\n";
+        let mut config = source_config(false);
+        assert!(TailwindExpandVisitor::new(config.clone())
+            .expansions()
+            .is_none());
+        config.expansion_map_path = Some("expansions.json".to_string());
+        let mut visitor = TailwindExpandVisitor::new(config);
+        transform_program(Program::Module(parse_module(source)), &mut visitor);
+
+        // Spans are byte positions starting at 1; lines are counted here for the test
+        let locate = |pos: BytePos| {
+            let offset = pos.0 as usize - 1;
+            let line_start = source[..offset].rfind('\n').map_or(0, |index| index + 1);
+            (
+                source[..offset].matches('\n').count() + 1,
+                offset - line_start + 1,
+            )
+        };
+        let records: Vec<ExpansionRecord> = visitor
+            .expansions()
+            .unwrap()
+            .iter()
+            .map(|expansion| ExpansionRecord::new(expansion, locate))
+            .collect();
+        let summary: Vec<(&str, &str, String, usize, usize)> = records
+            .iter()
+            .map(|record| {
+                (
+                    record.token.as_str(),
+                    record.alias.as_str(),
+                    record.utilities.join(" "),
+                    record.line,
+                    record.column,
+                )
+            })
+            .collect();
+        let line_two = source.lines().nth(1).unwrap();
+        assert_eq!(
+            summary,
+            [
+                ("lg:Button", "Button", "lg:px-4 lg:py-2".to_string(), 1, 32),
+                (
+                    "!ButtonLg",
+                    "ButtonLg",
+                    "!h-12 !px-6".to_string(),
+                    2,
+                    line_two.find("!ButtonLg").unwrap() + 1
+                ),
+                // Tokens from variant groups point at their string
+                (
+                    "hover:Button",
+                    "Button",
+                    "hover:px-4 hover:py-2".to_string(),
+                    2,
+                    line_two.find("\"hover:").unwrap() + 1
+                ),
+            ]
+        );
+
+        let map: BTreeMap<String, Vec<ExpansionRecord>> =
+            serde_json::from_str(&module_report("a.tsx", &records)).unwrap();
+        assert_eq!(map["a.tsx"], records);
+    }
+
+    #[test]
+    fn test_safelist() {
+        let safelist: HashSet<String> = ["block".to_string()].into_iter().collect();
+        assert_eq!(
+            resolve_utility_conflicts("block p-2 flex", &safelist),
+            "block p-2 flex"
+        );
+        assert_eq!(
+            resolve_utility_conflicts("hidden block grid", &safelist),
+            "block grid"
+        );
+        assert_eq!(resolve_utility_conflicts("flex block", &safelist), "block");
+        assert_eq!(
+            dedupe_utilities("block p-2 block p-2", &safelist),
+            "block p-2 block"
+        );
+
+        let mut aliases = AliasMap::new();
+        aliases.insert("Row".to_string(), "block flex gap-2".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            dedupe: true,
+            resolve_conflicts: true,
+            safelist,
+            ..Default::default()
+        });
+        assert_eq!(visitor.expand_class_name("Row grid").0, "block gap-2 grid");
+    }
+
+    #[test]
+    fn test_variant_only_tokens() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4".to_string());
+        // An empty alias key would otherwise match the empty rest
+        aliases.insert("".to_string(), "p-1".to_string());
+        let mut visitor = create_visitor(aliases.clone(), false);
+        for token in ["hover:", "dark:hover:", "hover:!", "!hover:", "[&>*]:"] {
+            assert_eq!(visitor.try_expand_token(token), TokenOutcome::Passthrough);
+            assert_eq!(visitor.expand_token(token), (token.to_string(), None));
+        }
+        assert_eq!(
+            visitor.expand_class_name("hover: Button dark:hover:").0,
+            "hover: px-4 dark:hover:"
+        );
+        assert!(visitor.diagnostics().is_empty());
+        assert!(!is_variant_only("[a:b]"));
+        assert!(!is_variant_only("hover:flex"));
+
+        let source = "const a = <div className=\"hover: Button dark:hover:\" />;";
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            strict: true,
+            ..Default::default()
+        });
+        transform_program(Program::Module(parse_module(source)), &mut visitor);
+        let warnings: Vec<(DiagnosticKind, &str)> = visitor
+            .diagnostics()
+            .iter()
+            .filter(|d| !d.span.is_dummy())
+            .map(|d| {
+                (
+                    d.kind,
+                    &source[d.span.lo.0 as usize - 1..d.span.hi.0 as usize - 1],
+                )
+            })
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (DiagnosticKind::EmptyUtility, "hover:"),
+                (DiagnosticKind::EmptyUtility, "dark:hover:")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_factories() {
+        let source = "const a = makeClass(\"Button\");\nconst b = makeClass.bind(null, \"Button\");\nconst c = makeClass(\"Button\")(\"ButtonLg\");\nconst d = makeOther(\"Button\");\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            class_factories: vec!["makeClass".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = makeClass(\"px-4 py-2\");\nconst b = makeClass.bind(null, \"px-4 py-2\");\nconst c = makeClass(\"px-4 py-2\")(\"h-12 px-6\");\nconst d = makeOther(\"Button\");\n"
+        );
+    }
+
+    #[test]
+    fn test_cn_friendly() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Row".to_string(), "flex gap-2".to_string());
+        aliases.insert("Grid".to_string(), "grid gap-2".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            cn_friendly: true,
+            resolve_conflicts: true,
+            sort: true,
+            ..Default::default()
+        });
+        // Exact repeats go, flex and grid stay for twMerge, order is untouched
+        assert_eq!(
+            visitor.expand_class_name("Row p-2 Grid gap-2").0,
+            "flex gap-2 p-2 grid"
+        );
+    }
+
+    #[test]
+    fn test_expand_memo_bodies() {
+        let source = "const a = useMemo(()=>\"Button ButtonLg\", []);\nconst b = React.useMemo(function() {\n    return big ? \"ButtonLg\" : \"Button\";\n}, [\n    big\n]);\nconst c = useCallback(()=>\"Button\", []);\nconst d = useOther(()=>\"Button\");\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_memo_bodies: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = useMemo(()=>\"px-4 py-2 h-12 px-6\", []);\nconst b = React.useMemo(function() {\n    return big ? \"h-12 px-6\" : \"px-4 py-2\";\n}, [\n    big\n]);\nconst c = useCallback(()=>\"px-4 py-2\", []);\nconst d = useOther(()=>\"Button\");\n"
+        );
+    }
+
+    #[test]
+    fn test_config_is_no_op() {
+        assert!(Config::default().is_no_op());
+        assert!(Config::parse(Some("{\"debug\": true, \"sort\": true}"))
+            .unwrap()
+            .is_no_op());
+        // Options that only act on expanded aliases can't change anything either
+        assert!(Config::parse(Some(
+            "{\"aliasPrefix\": \"@\", \"assertFullyExpanded\": true, \"safelist\": [\"px-4\"]}"
+        ))
+        .unwrap()
+        .is_no_op());
+
+        // Untouched either way, the fast exit only skips the walk
+        let source = "export const A = ()=><div className=\"Button flex\"/>;\n";
+        assert_eq!(transform_jsx(source, Config::default()), source);
+
+        assert!(!source_config(false).is_no_op());
+        for json in [
+            "{\"fallbackAliases\": {\"Button\": \"px-4\"}}",
+            "{\"renameAttributes\": {\"class\": \"className\"}}",
+            "{\"fallback\": \"remove\"}",
+            "{\"strict\": true}",
+            "{\"reportPath\": \"usage.json\"}",
+            "{\"optInAttribute\": \"data-tw-expand\"}",
+            "{\"variantScopeAttribute\": \"data-tw-variant\"}",
+            "{\"aliasPrefix\": \"@\", \"strictPrefix\": true}",
+        ] {
+            assert!(!Config::parse(Some(json)).unwrap().is_no_op(), "{}", json);
+        }
+
+        // Markers are still removed when no aliases are configured
+        let config = Config {
+            opt_in_attribute: Some("data-tw-expand".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            transform_jsx(
+                "export const A = ()=><div data-tw-expand className=\"flex\"/>;\n",
+                config
+            ),
+            "export const A = ()=><div className=\"flex\"/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_expand_const_strings() {
+        let source = "const c = \"Button ButtonLg\";\nconst other = \"Button\";\nlet mutable = \"Button\";\nexport const A = ()=><div className={c}/>;\nexport const B = ()=><div className={mutable} title={other}/>;\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_const_strings: true,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const c = \"px-4 py-2 h-12 px-6\";\nconst other = \"Button\";\nlet mutable = \"Button\";\nexport const A = ()=><div className={c}/>;\nexport const B = ()=><div className={mutable} title={other}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_class_attributes() {
+        let source = "export const A = ()=><my-card data-class={\"Button\"} data-other={\"Button\"} data-plain=\"ButtonLg\"/>;\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            class_attributes: vec!["data-class".to_string(), "data-plain".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "export const A = ()=><my-card data-class={\"px-4 py-2\"} data-other={\"Button\"} data-plain=\"h-12 px-6\"/>;\n"
+        );
+    }
 
-                // Return full token for data-expand (e.g., "lg:Button" not just "Button")
-                return (utilities, Some(token.to_string()));
-            }
-        }
+    #[test]
+    fn test_alias_variants_stack_with_usage_variants() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("ButtonState".to_string(), "aria-pressed:bg-red".to_string());
+        aliases.insert(
+            "MenuState".to_string(),
+            "data-[state=open]:aria-expanded:block p-2".to_string(),
+        );
+        let visitor = create_visitor(aliases, false);
 
-        // Check for important modifier (e.g., !Button)
-        if token.starts_with('!') {
-            let rest = &token[1..];
-            if let Some(expanded) = self.aliases.get(rest) {
-                let utilities: String = expanded
-                    .split_whitespace()
-                    .map(|u| insert_important(u))
-                    .collect::<Vec<_>>()
-                    .join(" ");
+        assert_eq!(
+            visitor.expand_token("dark:ButtonState").0,
+            "dark:aria-pressed:bg-red"
+        );
+        assert_eq!(
+            visitor.expand_token("dark:hover:ButtonState").0,
+            "dark:hover:aria-pressed:bg-red"
+        );
+        // Only exact segments dedupe
+        assert_eq!(
+            visitor.expand_token("aria-pressed:ButtonState").0,
+            "aria-pressed:bg-red"
+        );
+        assert_eq!(
+            visitor.expand_token("aria-[pressed=true]:ButtonState").0,
+            "aria-[pressed=true]:aria-pressed:bg-red"
+        );
+        assert_eq!(
+            visitor.expand_token("dark:MenuState").0,
+            "dark:data-[state=open]:aria-expanded:block dark:p-2"
+        );
+        assert_eq!(
+            visitor.expand_token("data-[state=open]:MenuState").0,
+            "data-[state=open]:aria-expanded:block data-[state=open]:p-2"
+        );
+        assert_eq!(
+            visitor.expand_token("data-[state=closed]:MenuState").0,
+            "data-[state=closed]:data-[state=open]:aria-expanded:block data-[state=closed]:p-2"
+        );
+    }
 
-                // Return full token for data-expand (e.g., "!Button")
-                return (utilities, Some(token.to_string()));
-            }
-        }
+    #[test]
+    fn test_severity_overrides() {
+        let config = Config::parse(Some(
+            r#"{
+                "aliases": { "Button": "px-4", "Empty": "" },
+                "strict": true,
+                "fallback": "keep",
+                "severity": { "empty-alias": "error", "unknown-alias": "off" }
+            }"#,
+        ))
+        .unwrap();
+        let mut visitor = TailwindExpandVisitor::new(config);
+        let mut element = class_name_element("Button Typo hover:", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
 
-        // Check if token is a direct alias
-        if let Some(expanded) = self.aliases.get(token) {
-            return (expanded.clone(), Some(token.to_string()));
-        }
+        // EmptyAlias escalated, UnknownAlias for Typo silenced, EmptyUtility unchanged
+        let reported: Vec<_> = visitor
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.severity))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (DiagnosticKind::EmptyAlias, Severity::Error),
+                (DiagnosticKind::EmptyUtility, Severity::Warn),
+            ]
+        );
 
-        // Not an alias, return as-is
-        (token.to_string(), None)
+        assert!(Config::parse(Some(r#"{"severity": {"no-such-kind": "off"}}"#)).is_err());
     }
 
-    /// Check if an attribute is a className-like attribute
-    fn is_class_attr(&self, attr: &JSXAttr) -> bool {
-        if let JSXAttrName::Ident(ident) = &attr.name {
-            let name = ident.sym.as_str();
-            return name == "className" || name == "class" || name == "classes";
+    #[test]
+    fn test_string_concatenation() {
+        let source = "export const A = ()=><div className={\"Button \" + size}/>;\nexport const B = ()=><div className={base + \" Button\"}/>;\nexport const C = ()=><div className={\"ButtonLg \" + size + \" Button text-sm\"}/>;\nexport const D = ()=><div className={\"Button\" + suffix + \"Lg Button\"}/>;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "export const A = ()=><div className={\"px-4 py-2 \" + size}/>;\nexport const B = ()=><div className={base + \" px-4 py-2\"}/>;\nexport const C = ()=><div className={\"h-12 px-6 \" + size + \" px-4 py-2 text-sm\"}/>;\nexport const D = ()=><div className={\"Button\" + suffix + \"Lg px-4 py-2\"}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_classes() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Card".to_string(), "card p-4 rounded".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            strip_classes: ["card".to_string(), "debug-outline".to_string()]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        });
+        // From an alias and written directly, with or without other aliases
+        assert_eq!(
+            visitor.expand_class_name("Card debug-outline mt-2").0,
+            "p-4 rounded mt-2"
+        );
+        assert_eq!(visitor.expand_class_name("debug-outline mt-2").0, "mt-2");
+        assert_eq!(
+            visitor.expand_class_name("mt-2 hover:card"),
+            (Cow::Borrowed("mt-2 hover:card"), AliasTokens::default())
+        );
+        assert!(!Config {
+            strip_classes: ["card".to_string()].into_iter().collect(),
+            ..Default::default()
         }
-        false
+        .is_no_op());
     }
-}
 
-impl VisitMut for TailwindExpandVisitor {
-    fn visit_mut_jsx_opening_element(&mut self, element: &mut JSXOpeningElement) {
-        // Visit children first
-        element.visit_mut_children_with(self);
+    #[test]
+    fn test_create_element_merged_props() {
+        let source = "_jsx(\"div\", Object.assign({}, props, {\n    className: \"Button\"\n}));\n_jsx(\"div\", _objectSpread(_objectSpread({}, props), {}, {\n    className: \"ButtonLg\"\n}));\n_jsx(\"div\", merge({}, {\n    className: \"Button\"\n}));\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "_jsx(\"div\", Object.assign({}, props, {\n    className: \"px-4 py-2\"\n}));\n_jsx(\"div\", _objectSpread(_objectSpread({}, props), {}, {\n    className: \"h-12 px-6\"\n}));\n_jsx(\"div\", merge({}, {\n    className: \"Button\"\n}));\n"
+        );
+    }
 
-        let mut expanded_aliases: HashSet<String> = HashSet::new();
+    #[test]
+    fn test_css_syntax_in_alias_values() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 py-2;".to_string());
+        aliases.insert(
+            "Card".to_string(),
+            "p-4; /* surface */ rounded\n// generated\nw-1/2 bg-black/50".to_string(),
+        );
+        aliases.insert("Plain".to_string(), "w-1/2 content-[';']".to_string());
 
-        // Find and transform className attribute
-        for attr_or_spread in &mut element.attrs {
-            if let JSXAttrOrSpread::JSXAttr(attr) = attr_or_spread {
-                if self.is_class_attr(attr) {
-                    if let Some(JSXAttrValue::Lit(Lit::Str(str_lit))) = &mut attr.value {
-                        let val = str_lit.value.as_str();
-                        let (expanded, aliases) = self.expand_class_name(val);
-                        if expanded != val {
-                            str_lit.value = Atom::from(expanded);
-                            str_lit.raw = None;
-                        }
-                        expanded_aliases.extend(aliases);
-                    }
-                }
-            }
-        }
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(visitor.expand_class_name("Button").0, "px-4 py-2");
+        assert_eq!(
+            visitor.expand_class_name("Card").0,
+            "p-4 rounded w-1/2 bg-black/50"
+        );
+        assert_eq!(visitor.expand_class_name("Plain").0, "w-1/2 content-[';']");
 
-        // Add data-expand attribute if debug mode and aliases were expanded
-        if self.debug && !expanded_aliases.is_empty() {
-            let mut alias_names: Vec<_> = expanded_aliases.into_iter().collect();
-            alias_names.sort();
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::CssSyntax && d.severity == Severity::Warn));
+        assert!(diagnostics[0].message.contains("\"Button\""));
+        assert!(diagnostics[1].message.contains("\"Card\""));
+    }
 
-            let data_expand_attr = JSXAttrOrSpread::JSXAttr(JSXAttr {
-                span: Default::default(),
-                name: JSXAttrName::Ident(swc_core::ecma::ast::IdentName {
-                    span: Default::default(),
-                    sym: Atom::from("data-expand"),
-                }),
-                value: Some(JSXAttrValue::Lit(Lit::Str(Str {
-                    span: Default::default(),
-                    value: Atom::from(alias_names.join(" ")),
-                    raw: None,
-                }))),
-            });
+    #[test]
+    fn test_explain() {
+        let mut aliases = AliasMap::new();
+        aliases.insert("Button".to_string(), "px-4 hover:py-2".to_string());
 
-            element.attrs.push(data_expand_attr);
-        }
+        assert_eq!(
+            explain("dark:hover:!Button", &aliases),
+            "dark:hover:!Button\n  alias: Button\n  variants: dark, hover\n  important: yes\n  utilities: dark:hover:!px-4 dark:hover:!py-2"
+        );
+        assert_eq!(
+            explain("!lg:Button", &aliases),
+            "!lg:Button\n  alias: Button\n  variants: lg\n  important: yes\n  utilities: lg:!px-4 lg:hover:!py-2"
+        );
+        assert_eq!(
+            explain("lg:Buton", &aliases),
+            "lg:Buton\n  unknown alias: Buton, left as-is"
+        );
     }
-}
-
-#[plugin_transform]
-pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
-    let config: Config = serde_json::from_str(
-        &metadata
-            .get_transform_plugin_config()
-            .unwrap_or_default(),
-    )
-    .unwrap_or_default();
 
-    program.apply(visit_mut_pass(TailwindExpandVisitor::new(config)))
-}
+    #[test]
+    fn test_expand_h_pragma() {
+        let source = "h(\"div\", {\n    class: \"Button\"\n}, h(\"span\", {\n    className: \"ButtonLg\"\n}));\ncreateElement(\"div\", {\n    class: \"Button\"\n});\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "h(\"div\", {\n    class: \"px-4 py-2\"\n}, h(\"span\", {\n    className: \"h-12 px-6\"\n}));\ncreateElement(\"div\", {\n    class: \"px-4 py-2\"\n});\n"
+        );
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_deprecated_aliases() {
+        let mut config = source_config(false);
+        config
+            .aliases
+            .insert("OldCard".to_string(), "p-4 rounded".to_string());
+        config.deprecated_aliases = [
+            ("OldCard".to_string(), "Card".to_string()),
+            ("OldButton".to_string(), "Button".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let mut visitor = TailwindExpandVisitor::new(config);
+        let mut element = class_name_element("OldCard lg:OldButton Button", DUMMY_SP, DUMMY_SP);
+        element.visit_mut_with(&mut visitor);
 
-    fn create_visitor(aliases: AliasMap, debug: bool) -> TailwindExpandVisitor {
-        TailwindExpandVisitor { aliases, debug }
+        // Still expanded, OldButton through its replacement
+        assert_eq!(
+            attr_str(&element.attrs[0]).value.as_str(),
+            "p-4 rounded lg:px-4 lg:py-2 px-4 py-2"
+        );
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::DeprecatedAlias && d.severity == Severity::Warn));
+        assert!(diagnostics[0]
+            .message
+            .contains("\"OldCard\" in \"OldCard\""));
+        assert!(diagnostics[0].message.contains("use \"Card\""));
+        assert!(diagnostics[1]
+            .message
+            .contains("\"OldButton\" in \"lg:OldButton\""));
     }
 
     #[test]
-    fn test_expand_token_direct_alias() {
-        let mut aliases = AliasMap::new();
-        aliases.insert("Button".to_string(), "px-4 py-2".to_string());
+    fn test_class_hooks_computed_keys() {
+        // Computed keys are left alone whatever they hold, even a literal alias name
+        let source = "const a = clsx({\n    [\"Button\"]: a,\n    [`${size}Button`]: b,\n    [styles.Button]: c,\n    [key()]: d,\n    1: e,\n    ButtonLg: f\n});\n";
+        let expected = "const a = clsx({\n    [\"Button\"]: a,\n    [`${size}Button`]: b,\n    [styles.Button]: c,\n    [key()]: d,\n    1: e,\n    \"h-12\": f,\n    \"px-6\": f\n});\n";
+        let config = Config {
+            class_hooks: vec!["clsx".to_string()],
+            strict: true,
+            ..source_config(false)
+        };
+        assert_eq!(transform_jsx(source, config.clone()), expected);
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, alias) = visitor.expand_token("Button");
-        assert_eq!(expanded, "px-4 py-2");
-        assert_eq!(alias, Some("Button".to_string()));
+        let mut visitor = TailwindExpandVisitor::new(config);
+        transform_program(Program::Module(parse_module(source)), &mut visitor);
+        assert!(visitor.diagnostics().is_empty());
     }
 
     #[test]
-    fn test_expand_token_with_variant() {
-        let mut aliases = AliasMap::new();
-        aliases.insert("ButtonMd".to_string(), "h-10 px-4".to_string());
+    fn test_template_tags() {
+        let source = "const a = cx`Button ButtonLg`;\nconst b = cx(\"Button\", big && \"ButtonLg\");\nconst c = cx`Button ${extra}`;\nconst d = css`Button`;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "const a = cx`px-4 py-2 h-12 px-6`;\nconst b = cx(\"px-4 py-2\", big && \"h-12 px-6\");\nconst c = cx`Button ${extra}`;\nconst d = css`Button`;\n"
+        );
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, token) = visitor.expand_token("lg:ButtonMd");
-        assert_eq!(expanded, "lg:h-10 lg:px-4");
-        // Returns full token for data-expand
-        assert_eq!(token, Some("lg:ButtonMd".to_string()));
+        let mut aliases = AliasMap::new();
+        aliases.insert("Quote".to_string(), "content-['`'] p-2".to_string());
+        let config = Config {
+            aliases,
+            template_tags: Some(vec!["tw".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            transform_jsx("tw`Quote`;\ncx`Quote`;\n", config),
+            "tw`content-['\\`'] p-2`;\ncx`Quote`;\n"
+        );
     }
 
     #[test]
-    fn test_expand_token_with_important() {
+    fn test_expand_first_only() {
         let mut aliases = AliasMap::new();
         aliases.insert("Button".to_string(), "px-4 py-2".to_string());
-
-        let visitor = create_visitor(aliases, false);
-        let (expanded, token) = visitor.expand_token("!Button");
-        assert_eq!(expanded, "!px-4 !py-2");
-        // Returns full token for data-expand
-        assert_eq!(token, Some("!Button".to_string()));
+        aliases.insert("Card".to_string(), "p-4".to_string());
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases,
+            expand_first_only: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor
+                .expand_class_name("Button Card hover:Button Button Card")
+                .0,
+            "px-4 py-2 p-4 hover:Button Button Card"
+        );
     }
 
     #[test]
-    fn test_important_with_variant_utilities() {
+    fn test_protect_utilities() {
         let mut aliases = AliasMap::new();
-        aliases.insert(
-            "ButtonMain".to_string(),
-            "bg-amber-500 hover:bg-amber-600".to_string(),
+        aliases.insert("flex".to_string(), "grid gap-2".to_string());
+        aliases.insert("text-lg".to_string(), "text-xl".to_string());
+        aliases.insert("Button".to_string(), "flex px-4".to_string());
+
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            aliases: aliases.clone(),
+            protect_utilities: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            visitor.expand_class_name("flex text-lg Button").0,
+            "flex text-lg flex px-4"
         );
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::ProtectedUtility && d.severity == Severity::Warn));
+        assert!(diagnostics[0].message.contains("\"flex\""));
+        assert!(diagnostics[1].message.contains("\"text-lg\""));
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, _) = visitor.expand_token("!ButtonMain");
-        assert_eq!(expanded, "!bg-amber-500 hover:!bg-amber-600");
+        // Off by default, so existing aliases like these keep expanding, but an
+        // alias named exactly like a utility is still reported
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(visitor.expand_class_name("flex").0, "grid gap-2");
+        assert_eq!(visitor.expand_class_name("text-lg").0, "text-xl");
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::ProtectedUtility);
+        assert!(diagnostics[0].message.contains("\"flex\""));
+
+        let mut aliases = AliasMap::new();
+        aliases.insert("text-primary".to_string(), "text-blue-600".to_string());
+        aliases.insert("bg-brand".to_string(), "bg-amber-500".to_string());
+        let mut visitor = create_visitor(aliases, false);
+        assert_eq!(visitor.expand_class_name("bg-brand").0, "bg-amber-500");
+        assert!(visitor.diagnostics().is_empty());
     }
 
     #[test]
-    fn test_expand_class_name() {
+    fn test_default_class_attributes() {
+        let source = "export const A = ()=><div className=\"Button\" class=\"Button\"/>;\nReact.createElement(\"div\", {\n    class: \"ButtonLg\"\n});\n";
+        let config = Config {
+            default_class_attributes: Some(vec!["className".to_string()]),
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "export const A = ()=><div className=\"px-4 py-2\" class=\"Button\"/>;\nReact.createElement(\"div\", {\n    class: \"ButtonLg\"\n});\n"
+        );
+
+        let config = Config {
+            default_class_attributes: Some(vec![]),
+            class_attributes: vec!["class".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "export const A = ()=><div className=\"Button\" class=\"px-4 py-2\"/>;\nReact.createElement(\"div\", {\n    class: \"ButtonLg\"\n});\n"
+        );
+
+        let config = Config {
+            default_class_attributes: Some(vec!["classname".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::UnknownDefaultClassAttribute(
+                "classname".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_expand_styled_interpolations() {
+        let source = "const A = styled.div`\n  color: red;\n  ${big ? \"Button\" : \"\"}\n  ${(p)=>p.big && \"ButtonLg\"}\n`;\nconst B = styled(Card).attrs({})`${big && \"Button\"}${Button}`;\nconst C = css`${big && \"Button\"}`;\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
+        let config = Config {
+            expand_styled_interpolations: true,
+            ..source_config(false)
+        };
+        let mut visitor = TailwindExpandVisitor::new(config.clone());
+        let module = parse_module(source);
+        transform_program(Program::Module(module), &mut visitor);
+        assert_eq!(visitor.stats().expanded, 2);
+        assert_eq!(
+            transform_jsx(source, config),
+            "const A = styled.div`\n  color: red;\n  ${big ? \"px-4 py-2\" : \"\"}\n  ${(p)=>p.big && \"ButtonLg\"}\n`;\nconst B = styled(Card).attrs({})`${big && \"px-4 py-2\"}${Button}`;\nconst C = css`${big && \"Button\"}`;\n"
+        );
+    }
+
+    #[test]
+    fn test_assert_fully_expanded() {
         let mut aliases = AliasMap::new();
+        aliases.insert("Card".to_string(), "Panel p-4".to_string());
+        aliases.insert("Panel".to_string(), "Card rounded".to_string());
         aliases.insert("Button".to_string(), "px-4 py-2".to_string());
-        aliases.insert("ButtonMd".to_string(), "h-10".to_string());
+        let config = Config {
+            aliases,
+            assert_fully_expanded: true,
+            ..Default::default()
+        };
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, tokens) = visitor.expand_class_name("Button lg:ButtonMd text-white");
-        assert_eq!(expanded, "px-4 py-2 lg:h-10 text-white");
-        // Returns full tokens for data-expand
-        assert!(tokens.contains("Button"));
-        assert!(tokens.contains("lg:ButtonMd"));
+        let mut visitor = TailwindExpandVisitor::new(config.clone());
+        let mut str_lit = Str::from("Card Button");
+        visitor.expand_str(&mut str_lit);
+        assert_eq!(str_lit.value, "Panel p-4 px-4 py-2");
+        let diagnostics = visitor.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::UnexpandedAlias);
+        assert!(diagnostics[0].message.contains("alias \"Panel\""));
+
+        let mut visitor = TailwindExpandVisitor::new(config.clone());
+        visitor.expand_str(&mut Str::from("Button hover:Button"));
+        assert!(visitor.diagnostics().is_empty());
+
+        // Aliases kept on purpose aren't leaks
+        let mut visitor = TailwindExpandVisitor::new(Config {
+            keep_alias_class: true,
+            ..config
+        });
+        visitor.expand_str(&mut Str::from("Button"));
+        assert!(visitor.diagnostics().is_empty());
     }
 
     #[test]
-    fn test_config_with_aliases() {
+    fn test_expand_default_params() {
+        let source = "function Btn({ className = \"Button\", label = \"Button\" }) {}\nconst Card = ({ className: cls = big ? \"ButtonLg\" : \"\" })=>cls;\n";
+        assert_eq!(transform_jsx(source, source_config(false)), source);
+
         let config = Config {
-            aliases: [
-                ("Button".to_string(), "px-4 py-2".to_string()),
-                ("ButtonMd".to_string(), "h-10".to_string()),
-            ]
-            .into_iter()
-            .collect(),
-            debug: false,
+            expand_default_params: true,
+            ..source_config(false)
         };
-
-        let visitor = TailwindExpandVisitor::new(config);
-        let (expanded, _) = visitor.expand_token("Button");
-        assert_eq!(expanded, "px-4 py-2");
+        assert_eq!(
+            transform_jsx(source, config),
+            "function Btn({ className = \"px-4 py-2\", label = \"Button\" }) {}\nconst Card = ({ className: cls = big ? \"h-12 px-6\" : \"\" })=>cls;\n"
+        );
     }
 
     #[test]
-    fn test_variant_deduplication_same_variant() {
-        let mut aliases = AliasMap::new();
-        aliases.insert(
-            "ButtonMain".to_string(),
-            "bg-amber-500 hover:bg-amber-600".to_string(),
+    fn test_debug_verbose() {
+        let source = "const a = <div className=\"lg:ButtonLg Button text-white\"/>;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(true)),
+            "const a = <div className=\"lg:h-12 lg:px-6 px-4 py-2 text-white\" data-expand=\"Button lg:ButtonLg\"/>;\n"
         );
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, _) = visitor.expand_token("hover:ButtonMain");
-        assert_eq!(expanded, "hover:bg-amber-500 hover:bg-amber-600");
+        let config = Config {
+            debug_verbose: true,
+            ..source_config(true)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <div className=\"lg:h-12 lg:px-6 px-4 py-2 text-white\" data-expand=\"Button=px-4 py-2; lg:ButtonLg=lg:h-12 lg:px-6\"/>;\n"
+        );
     }
 
     #[test]
-    fn test_variant_deduplication_dark() {
-        let mut aliases = AliasMap::new();
-        aliases.insert(
-            "ButtonGitHub".to_string(),
-            "text-slate-950 dark:text-white".to_string(),
+    fn test_debug_verbose_matches_output() {
+        let source = "const a = <div data-tw-variant=\"dark\"><p className=\"lg:ButtonLg Button mx-2\"/></div>;\n";
+        let config = Config {
+            debug_verbose: true,
+            variant_scope_attribute: Some("data-tw-variant".to_string()),
+            important_variants: ["lg".to_string()].into_iter().collect(),
+            strip_classes: ["dark:py-2".to_string()].into_iter().collect(),
+            ..source_config(true)
+        };
+        // Pairs show the utilities as written, after scoping, important_variants and strip_classes
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <div><p className=\"dark:lg:!h-12 dark:lg:!px-6 dark:px-4 mx-2\" data-expand=\"dark:Button=dark:px-4; dark:lg:ButtonLg=dark:lg:!h-12 dark:lg:!px-6\"/></div>;\n"
         );
-
-        let visitor = create_visitor(aliases, false);
-        let (expanded, _) = visitor.expand_token("dark:ButtonGitHub");
-        assert_eq!(expanded, "dark:text-slate-950 dark:text-white");
     }
 
     #[test]
-    fn test_variant_deduplication_chained() {
-        let mut aliases = AliasMap::new();
-        aliases.insert(
-            "ButtonMain".to_string(),
-            "bg-amber-500 hover:bg-amber-600".to_string(),
+    fn test_variant_scope_attribute() {
+        let source = "const a = <div data-tw-variant=\"dark\" className=\"Button\"><>\n    <p className=\"Button text-white\"/>\n    <section data-tw-variant=\"md\"><i className=\"ButtonLg\"/></section>\n  </></div>;\nconst b = <p className=\"Button\"/>;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "const a = <div data-tw-variant=\"dark\" className=\"px-4 py-2\"><>\n    <p className=\"px-4 py-2 text-white\"/>\n    <section data-tw-variant=\"md\"><i className=\"h-12 px-6\"/></section>\n  </></div>;\nconst b = <p className=\"px-4 py-2\"/>;\n"
         );
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, _) = visitor.expand_token("dark:hover:ButtonMain");
-        assert_eq!(expanded, "dark:hover:bg-amber-500 dark:hover:bg-amber-600");
+        let config = Config {
+            variant_scope_attribute: Some("data-tw-variant".to_string()),
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <div className=\"px-4 py-2\"><>\n    <p className=\"dark:px-4 dark:py-2 text-white\"/>\n    <section><i className=\"dark:md:h-12 dark:md:px-6\"/></section>\n  </></div>;\nconst b = <p className=\"px-4 py-2\"/>;\n"
+        );
     }
 
     #[test]
-    fn test_variant_stacking_different_variants() {
-        let mut aliases = AliasMap::new();
-        aliases.insert(
-            "ButtonMain".to_string(),
-            "bg-amber-500 hover:bg-amber-600".to_string(),
+    fn test_template_tags_crlf_and_tabs() {
+        let source = "const a = cx`\r\n\tButton\r\n\t\tButtonLg text-white\r\n`;\nconst b = cx`\r\n\ttext-white\r\n`;\n";
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "const a = cx`px-4 py-2 h-12 px-6 text-white`;\nconst b = cx`\r\n\ttext-white\r\n`;\n"
         );
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, _) = visitor.expand_token("dark:ButtonMain");
-        assert_eq!(expanded, "dark:bg-amber-500 dark:hover:bg-amber-600");
+        // Preserved spacing keeps the line breaks, without the \r
+        let config = Config {
+            whitespace: WhitespacePolicy::Preserve,
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx("const a = cx`\r\n\tButton\r\n\tButtonLg\r\n`;\n", config),
+            "const a = cx`\n\tpx-4 py-2\n\th-12 px-6\n`;\n"
+        );
     }
 
     #[test]
-    fn test_variant_with_important_modifier() {
-        let mut aliases = AliasMap::new();
-        aliases.insert(
-            "ButtonMain".to_string(),
-            "bg-amber-500 hover:bg-amber-600".to_string(),
+    fn test_opt_in_attribute() {
+        let source = "const a = <ul><li data-tw-expand className=\"Button\"/><li className=\"Button\"/></ul>;\n";
+        let config = Config {
+            opt_in_attribute: Some("data-tw-expand".to_string()),
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <ul><li className=\"px-4 py-2\"/><li className=\"Button\"/></ul>;\n"
         );
 
-        let visitor = create_visitor(aliases, false);
-        let (expanded, _) = visitor.expand_token("hover:!ButtonMain");
-        assert_eq!(expanded, "hover:!bg-amber-500 hover:!bg-amber-600");
+        assert_eq!(
+            transform_jsx(source, source_config(false)),
+            "const a = <ul><li data-tw-expand className=\"px-4 py-2\"/><li className=\"px-4 py-2\"/></ul>;\n"
+        );
     }
 
     #[test]
-    fn test_debug_mode_tracks_aliases() {
+    fn test_merge_helper_with_prop() {
+        let source = "const A = (props)=><div className={twMerge(\"Button\", props.className)}/>;\nconst B = ({ className })=><div className={cn(\"Button hover:ButtonLg\", active && \"ButtonLg\", className, props?.classes?.root)}/>;\n";
+        let config = Config {
+            class_hooks: vec!["twMerge".to_string(), "cn".to_string()],
+            ..source_config(false)
+        };
+        // Only the literals change; the prop arguments are passed on untouched
+        assert_eq!(
+            transform_jsx(source, config),
+            "const A = (props)=><div className={twMerge(\"px-4 py-2\", props.className)}/>;\nconst B = ({ className })=><div className={cn(\"px-4 py-2 hover:h-12 hover:px-6\", active && \"h-12 px-6\", className, props?.classes?.root)}/>;\n"
+        );
+    }
+
+    #[test]
+    fn test_alias_map_shared_across_elements() {
         let mut aliases = AliasMap::new();
         aliases.insert("Button".to_string(), "px-4 py-2".to_string());
-        aliases.insert("ButtonMd".to_string(), "h-10".to_string());
+        let mut visitor = create_visitor(aliases, false);
+        let utilities = visitor.aliases["Button"].as_ptr();
 
-        let visitor = create_visitor(aliases, true);
-        let (expanded, tracked_aliases) = visitor.expand_class_name("Button ButtonMd");
-        assert_eq!(expanded, "px-4 py-2 h-10");
-        assert!(tracked_aliases.contains("Button"));
-        assert!(tracked_aliases.contains("ButtonMd"));
+        let source: String = (0..50)
+            .map(|index| {
+                format!(
+                    "const A{} = <div className=\"Button\"><b className=\"Button text-white\"/></div>;\n",
+                    index
+                )
+            })
+            .collect();
+        transform_program(Program::Module(parse_module(&source)), &mut visitor);
+
+        // The compiled aliases are read in place, never copied or rebuilt per element
+        assert_eq!(visitor.aliases["Button"].as_ptr(), utilities);
+        // Repeated class strings are expanded once and then served from the cache
+        assert_eq!(visitor.cache[""].len(), 2);
+        assert_eq!(visitor.stats().expanded, 100);
+    }
+
+    #[test]
+    fn test_disable_next_line_expression_class_name() {
+        let source = "const a = <ul>\n    {/* tailwind-expand-disable-next-line */}\n    <li className={clsx(\"Button\", big && \"ButtonLg\")}/>\n    <li className={clsx(\"Button\")}/>\n</ul>;\n";
+        let config = Config {
+            class_hooks: vec!["clsx".to_string()],
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <ul>\n    { /* tailwind-expand-disable-next-line */ }\n    <li className={clsx(\"Button\", big && \"ButtonLg\")}/>\n    <li className={clsx(\"px-4 py-2\")}/>\n</ul>;\n"
+        );
+    }
+
+    #[test]
+    fn test_opt_in_attribute_expression_class_name() {
+        let source = "const a = <ul><li data-tw-expand className={cx(\"Button\")}/><li className={cx(\"Button\", big && \"ButtonLg\")}/></ul>;\n";
+        let config = Config {
+            opt_in_attribute: Some("data-tw-expand".to_string()),
+            ..source_config(false)
+        };
+        assert_eq!(
+            transform_jsx(source, config),
+            "const a = <ul><li className={cx(\"px-4 py-2\")}/><li className={cx(\"Button\", big && \"ButtonLg\")}/></ul>;\n"
+        );
     }
 }
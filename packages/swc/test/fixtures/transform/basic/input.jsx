@@ -0,0 +1 @@
+export const Basic = () => <button className="Button" type="button">Save</button>;
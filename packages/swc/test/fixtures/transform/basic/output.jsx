@@ -0,0 +1 @@
+export const Basic = ()=><button className="px-4 py-2" type="button">Save</button>;
@@ -0,0 +1,5 @@
+export const Debug = () => (
+  <div id="card" className="ButtonLg" onClick={open} aria-label="Card">
+    <span className="Button" />
+  </div>
+);
@@ -0,0 +1,3 @@
+export const Debug = ()=>(<div id="card" className="h-12 px-6" onClick={open} aria-label="Card" data-expand="ButtonLg">
+    <span className="px-4 py-2" data-expand="Button"/>
+  </div>);
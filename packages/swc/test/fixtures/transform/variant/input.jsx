@@ -0,0 +1 @@
+export const Variant = () => <div className="Button lg:ButtonLg dark:hover:!Button text-sm" />;
@@ -0,0 +1 @@
+export const Variant = ()=><div className="px-4 py-2 lg:h-12 lg:px-6 dark:hover:!px-4 dark:hover:!py-2 text-sm"/>;
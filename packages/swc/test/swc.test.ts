@@ -43,6 +43,14 @@ describe('swc plugin', () => {
 
       expect(options.debug).toBe(true);
     });
+
+    it('forwards WASM plugin options', () => {
+      const [, options] = tailwindExpandSWC({ cssPath: fixture('globals.css'), expandArrays: true });
+
+      expect(options.expandArrays).toBe(true);
+      expect(options).not.toHaveProperty('cssPath');
+      expect(options).not.toHaveProperty('mergerFn');
+    });
   });
 
   describe('alias extraction', () => {